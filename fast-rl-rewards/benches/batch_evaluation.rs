@@ -0,0 +1,210 @@
+//! benches/batch_evaluation.rs
+//!
+//! Throughput benchmarks for batch execution rewards and their hot paths.
+//!
+//! Runs the sandbox if one is installed, otherwise bare `python3` (silent fallback). Benchmarks
+//! link against libpython, so the `extension-module` feature must be disabled:
+//! ```bash
+//! cargo bench --no-default-features --bench batch_evaluation
+//! ```
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use fastrlrewards::evaluator::{EvaluatorConfig, RewardEvaluator};
+use fastrlrewards::extraction::extract_code_from_completion;
+use fastrlrewards::sandbox::{SandboxFallbackPolicy, wait_with_output};
+use fastrlrewards::test_wrapper::wrap_tests_for_complete_execution;
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use std::hint::black_box;
+use std::io::{self, BufReader, Read};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+const BATCH_SIZE: usize = 100;
+const THREAD_COUNTS: [usize; 5] = [1, 4, 16, 32, 64];
+
+const TEST_CODE: &str = "def check(candidate):
+    assert candidate([1, 2, 3]) == 6
+    assert candidate([]) == 0
+    assert candidate([-1, 1]) == 0
+    assert candidate([10] * 10) == 100";
+
+/// HumanEval-style completions: alternating correct and wrong solutions.
+fn completions() -> Vec<String> {
+    (0..BATCH_SIZE)
+        .map(|i| {
+            let body = if i % 2 == 0 {
+                "    total = 0\n    for x in xs:\n        total += x\n    return total"
+            } else {
+                "    return len(xs)"
+            };
+            format!(
+                "<think>Sum the list.</think>\n<answer>\n```python\ndef sum_list(xs):\n{}\n```\n</answer>",
+                body
+            )
+        })
+        .collect()
+}
+
+fn bench_execution_batch(c: &mut Criterion) {
+    let evaluator = RewardEvaluator::new(EvaluatorConfig {
+        num_threads: None, // Each benchmark installs its own pool
+        fallback_policy: SandboxFallbackPolicy::Silent,
+        ..EvaluatorConfig::default()
+    })
+    .expect("valid configuration");
+
+    let completions = completions();
+    let tests = vec![Some(TEST_CODE.to_string()); BATCH_SIZE];
+    let entry_points = vec![Some("sum_list".to_string()); BATCH_SIZE];
+
+    let mut group = c.benchmark_group("evaluate_execution_batch");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for num_threads in THREAD_COUNTS {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("failed to build thread pool");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_threads),
+            &num_threads,
+            |b, _| {
+                b.iter(|| {
+                    pool.install(|| {
+                        evaluator.evaluate_execution_batch(&completions, &tests, &entry_points)
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Execution rewards on a batch where half of the completions lack the `<think>` block,
+/// with and without `gate_execution_on_format`.
+fn bench_format_gate(c: &mut Criterion) {
+    let completions: Vec<String> = completions()
+        .into_iter()
+        .enumerate()
+        .map(|(i, completion)| {
+            if i % 4 < 2 {
+                completion
+            } else {
+                completion.replace("<think>Sum the list.</think>\n", "")
+            }
+        })
+        .collect();
+    let tests = vec![Some(TEST_CODE.to_string()); BATCH_SIZE];
+    let entry_points = vec![Some("sum_list".to_string()); BATCH_SIZE];
+
+    let mut group = c.benchmark_group("format_gate");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for gate in [false, true] {
+        let evaluator = RewardEvaluator::new(EvaluatorConfig {
+            fallback_policy: SandboxFallbackPolicy::Silent,
+            gate_execution_on_format: gate,
+            ..EvaluatorConfig::default()
+        })
+        .expect("valid configuration");
+        group.bench_with_input(BenchmarkId::from_parameter(gate), &gate, |b, _| {
+            b.iter(|| evaluator.evaluate_execution_batch(&completions, &tests, &entry_points))
+        });
+    }
+    group.finish();
+}
+
+/// Output collection alternative to [`wait_with_output`]: wait for the exit, then read
+/// both pipes. Only safe while the output fits in the pipe buffer (64 KiB on Linux),
+/// otherwise the child blocks on a full pipe and never exits.
+fn wait_then_read(child: &mut Child, timeout: Duration) -> io::Result<Option<Output>> {
+    let Some(status) = child.wait_timeout(timeout)? else {
+        return Ok(None);
+    };
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    BufReader::with_capacity(64 * 1024, child.stdout.take().expect("piped stdout"))
+        .read_to_end(&mut stdout)?;
+    BufReader::with_capacity(64 * 1024, child.stderr.take().expect("piped stderr"))
+        .read_to_end(&mut stderr)?;
+    Ok(Some(Output {
+        status,
+        stdout,
+        stderr,
+    }))
+}
+
+/// Polling the non-blocking pipes while waiting vs reading them after the exit, for
+/// `BATCH_SIZE` short `python3` processes (about 10 KiB of stdout each) run in parallel.
+fn bench_output_collection(c: &mut Criterion) {
+    type Collect = fn(&mut Child, Duration) -> io::Result<Option<Output>>;
+    let strategies: [(&str, Collect); 2] = [
+        ("poll_nonblocking", wait_with_output),
+        ("read_after_exit", wait_then_read),
+    ];
+    let run = |collect: Collect| {
+        let mut child = Command::new("python3")
+            .args(["-c", "for i in range(1000): print('line', i)"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn python3");
+        let output = collect(&mut child, Duration::from_secs(60))
+            .expect("failed to collect output")
+            .expect("python3 timed out");
+        output.stdout.len()
+    };
+
+    let mut group = c.benchmark_group("output_collection");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for num_threads in [1, 64] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .expect("failed to build thread pool");
+        for (name, collect) in strategies {
+            group.bench_with_input(
+                BenchmarkId::new(name, num_threads),
+                &collect,
+                |b, &collect| {
+                    b.iter(|| {
+                        pool.install(|| {
+                            (0..BATCH_SIZE)
+                                .into_par_iter()
+                                .map(|_| run(collect))
+                                .sum::<usize>()
+                        })
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_wrap_tests(c: &mut Criterion) {
+    c.bench_function("wrap_tests_for_complete_execution", |b| {
+        b.iter(|| wrap_tests_for_complete_execution(black_box(TEST_CODE), "sum_list", None))
+    });
+}
+
+fn bench_extract_code(c: &mut Criterion) {
+    let completion = &completions()[0];
+    c.bench_function("extract_code_from_completion", |b| {
+        b.iter(|| extract_code_from_completion(black_box(completion)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_execution_batch,
+    bench_format_gate,
+    bench_output_collection,
+    bench_wrap_tests,
+    bench_extract_code
+);
+criterion_main!(benches);
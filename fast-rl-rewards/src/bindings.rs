@@ -2,26 +2,43 @@
 //!
 //! Python bindings via PyO3.
 //!
-//! Provides two interfaces:
+//! Provides three interfaces:
 //! 1. Module-level functions - Simple API using a default RewardEvaluator
 //! 2. RewardEvaluator class - Advanced API with custom configuration
+//! 3. RewardRegistry class - Reward functions looked up by name
 //!
 //! # Input Handling
-//! Accepts completions in multiple formats for compatibility with various RL libraries:
+//! Accepts completions (as a list or tuple) in multiple formats for compatibility with
+//! various RL libraries:
 //! - Direct strings: `["code1", "code2"]`
 //! - Dicts with "content" key: `[{"content": "code1"}, ...]`
 //! - Lists of dicts: `[[{"content": "code1"}], ...]`
 //!
+//! The `test=` and `entry_point=` kwargs may be lists, numpy arrays, or pandas `Series`.
+//!
 //! This flexibility allows drop-in replacement in TRL, Ray RLlib, and custom workflows.
 
-use crate::evaluator::{EvaluatorConfig, RewardEvaluator};
+use crate::evaluator::{
+    CodeQualityScore, EvaluationRecord, EvaluationSummary, EvaluatorConfig, QualityWeights,
+    RewardEvaluator, evaluation_summary, evaluation_summary_detailed,
+};
+use crate::extraction::{Language, extract_code_from_completion};
+use crate::registry::RewardRegistry;
 use once_cell::sync::Lazy;
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
 
 // ==========================================================================================
 
+/// Share of identical completions above which a batch is reported as suspicious.
+const DUPLICATE_WARNING_RATIO: f64 = 0.8;
+
+/// Smallest batch checked for duplicates (small batches are often identical by chance).
+const DUPLICATE_WARNING_MIN_BATCH: usize = 10;
+
 /// Global default evaluator for module-level functions.
 ///
 /// Uses default configuration (32 threads for parallelism, 15s timeout, 512MB memory limit
@@ -47,6 +64,7 @@ static DEFAULT_EVALUATOR: Lazy<RewardEvaluator> = Lazy::new(|| {
 ///     memory_limit_mb = 1024,
 ///     cpu_time_limit = 15,
 ///     num_threads = None,
+///     fallback_policy = "warn",  # run without Firejail in dev environments
 /// )
 ///
 /// format_scores = evaluator.format_reward(completions)
@@ -56,7 +74,7 @@ static DEFAULT_EVALUATOR: Lazy<RewardEvaluator> = Lazy::new(|| {
 ///     entry_point = entry_points
 /// )
 /// ```
-#[pyclass(name = "RewardEvaluator")]
+#[pyclass(name = "RewardEvaluator", module = "fastrlrewards")]
 pub struct PyRewardEvaluator {
     evaluator: RewardEvaluator,
 }
@@ -64,18 +82,110 @@ pub struct PyRewardEvaluator {
 #[pymethods]
 impl PyRewardEvaluator {
     #[new]
-    #[pyo3(signature = (timeout_seconds=15, memory_limit_mb=512, cpu_time_limit=12, num_threads=32))]
+    #[pyo3(signature = (
+        timeout_seconds=15,
+        memory_limit_mb=512,
+        cpu_time_limit=12,
+        num_threads=32,
+        fallback_policy="error",
+        sandbox_backend="auto",
+        max_completion_chars=None,
+        min_code_lines=1,
+        stdin_input=None,
+        allow_lower_wall_timeout=false,
+        type_check_timeout=30,
+        linting_tool="ruff",
+        linting_timeout=30,
+        check_function_names=None,
+        prefer_ram_tmpdir=true,
+        clamp_rewards=true,
+        extra_firejail_args=None,
+        reject_stubs=true,
+        multi_method_mode=false,
+        max_open_files=50,
+        gate_execution_on_format=false,
+        solution_class_names=None,
+        dry_run=false,
+        seccomp_profile="strict",
+        debug=None,
+        quality_weights=None,
+        adaptive_timeout=false,
+        pre_wrapped_tests=false,
+    ))]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
     fn new(
         timeout_seconds: u64,
         memory_limit_mb: u64,
         cpu_time_limit: u64,
-        num_threads: usize,
+        num_threads: Option<usize>,
+        fallback_policy: &str,
+        sandbox_backend: &str,
+        max_completion_chars: Option<usize>,
+        min_code_lines: usize,
+        stdin_input: Option<String>,
+        allow_lower_wall_timeout: bool,
+        type_check_timeout: u64,
+        linting_tool: &str,
+        linting_timeout: u64,
+        check_function_names: Option<Vec<String>>,
+        prefer_ram_tmpdir: bool,
+        clamp_rewards: bool,
+        extra_firejail_args: Option<Vec<String>>,
+        reject_stubs: bool,
+        multi_method_mode: bool,
+        max_open_files: u32,
+        gate_execution_on_format: bool,
+        solution_class_names: Option<Vec<String>>,
+        dry_run: bool,
+        seccomp_profile: &str,
+        debug: Option<&str>,
+        quality_weights: Option<HashMap<String, f64>>,
+        adaptive_timeout: bool,
+        pre_wrapped_tests: bool,
     ) -> PyResult<Self> {
+        let defaults = EvaluatorConfig::default();
         let config = EvaluatorConfig {
             timeout_seconds,
             memory_limit_mb,
             cpu_time_limit,
-            num_threads: Some(num_threads),
+            num_threads,
+            fallback_policy: fallback_policy
+                .parse()
+                .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?,
+            sandbox_backend: sandbox_backend
+                .parse()
+                .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?,
+            max_completion_chars,
+            min_code_lines,
+            stdin_input,
+            allow_lower_wall_timeout,
+            type_check_timeout,
+            linting_tool: linting_tool
+                .parse()
+                .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?,
+            linting_timeout,
+            check_function_names: check_function_names.unwrap_or(defaults.check_function_names),
+            prefer_ram_tmpdir,
+            clamp_rewards,
+            extra_firejail_args: extra_firejail_args.unwrap_or_default(),
+            reject_stubs,
+            multi_method_mode,
+            max_open_files,
+            gate_execution_on_format,
+            solution_class_names: solution_class_names.unwrap_or(defaults.solution_class_names),
+            dry_run,
+            seccomp_profile: seccomp_profile
+                .parse()
+                .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?,
+            debug: parse_log_level(debug)
+                .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?,
+            quality_weights: quality_weights
+                .map(quality_weights_from_map)
+                .transpose()?
+                .unwrap_or(defaults.quality_weights),
+            adaptive_timeout,
+            pre_wrapped_tests,
+            ..defaults
         };
 
         let evaluator = RewardEvaluator::new(config)
@@ -84,6 +194,124 @@ impl PyRewardEvaluator {
         Ok(Self { evaluator })
     }
 
+    /// Copy of the active configuration.
+    #[getter]
+    fn config(&self) -> PyEvaluatorConfig {
+        PyEvaluatorConfig::from(self.evaluator.config())
+    }
+
+    /// Wall-clock timeout per test in seconds (read-only).
+    #[getter]
+    fn timeout_seconds(&self) -> u64 {
+        self.evaluator.config().timeout_seconds
+    }
+
+    /// Memory limit per test in MB (read-only).
+    #[getter]
+    fn memory_limit_mb(&self) -> u64 {
+        self.evaluator.config().memory_limit_mb
+    }
+
+    /// CPU time limit per test in seconds (read-only).
+    #[getter]
+    fn cpu_time_limit(&self) -> u64 {
+        self.evaluator.config().cpu_time_limit
+    }
+
+    /// Requested number of Rayon threads, or `None` for one per CPU core (read-only).
+    #[getter]
+    fn num_threads(&self) -> Option<usize> {
+        self.evaluator.config().num_threads
+    }
+
+    /// Configuration as a dict of Python-native values (JSON-serializable), e.g. for
+    /// saving alongside a training checkpoint. Restore it with `RewardEvaluator.from_dict`.
+    ///
+    /// `sandbox_backend` is the requested backend (e.g. `"auto"`), not the one it resolved
+    /// to on this machine (see `config.sandbox_backend`).
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        PyEvaluatorConfig::from(&self.evaluator.requested_config()).dict(py)
+    }
+
+    /// Create an evaluator from a dict produced by `to_dict`.
+    ///
+    /// Missing keys take their default values.
+    ///
+    /// # Errors
+    /// Raises `ValueError` for unknown keys or an invalid configuration, and `TypeError`
+    /// for values of the wrong type.
+    #[staticmethod]
+    fn from_dict(py: Python, config: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let defaults = PyEvaluatorConfig::new();
+        let known_keys = defaults.dict(py)?;
+        let py_config = Bound::new(py, defaults)?;
+        for (key, value) in config.iter() {
+            if !known_keys.contains(&key)? {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown configuration key: {}",
+                    key.repr()?
+                )));
+            }
+            py_config.setattr(key.downcast::<PyString>()?, value)?;
+        }
+
+        let config = EvaluatorConfig::try_from(&*py_config.borrow())?;
+        let evaluator = RewardEvaluator::new(config)
+            .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?;
+        Ok(Self { evaluator })
+    }
+
+    /// New evaluator with the same configuration. It shares the process-wide Rayon pool.
+    fn __copy__(&self) -> PyResult<Self> {
+        let evaluator = RewardEvaluator::new(self.evaluator.requested_config())
+            .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?;
+        Ok(Self { evaluator })
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> PyResult<Self> {
+        self.__copy__()
+    }
+
+    /// Pickle support (e.g. for `multiprocessing`): only the configuration is serialized,
+    /// and unpickling creates a new evaluator from it with `from_dict`.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyDict>,))> {
+        let from_dict = slf.get_type().getattr("from_dict")?;
+        Ok((from_dict, (slf.borrow().to_dict(slf.py())?,)))
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "RewardEvaluator({})",
+            repr_fields(&self.to_dict(py)?)?
+        ))
+    }
+
+    fn __str__(&self, py: Python) -> PyResult<String> {
+        let fields = self
+            .to_dict(py)?
+            .iter()
+            .map(|(key, value)| Ok((format!("{}:", key), value.str()?.to_string())))
+            .collect::<PyResult<Vec<_>>>()?;
+        let width = fields
+            .iter()
+            .map(|(key, _)| key.len() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec!["RewardEvaluator".to_string()];
+        for (key, value) in fields {
+            lines.push(format!("  {:<width$}{}", key, value));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Pre-run a trivial evaluation on every worker thread to absorb cold-start costs
+    /// (thread pool, regex compilation, sandbox and Python startup) before training.
+    fn warmup(&self, py: Python) {
+        py.detach(|| self.evaluator.warmup());
+    }
+
     /// Evaluate format compliance of LLM outputs (checks for `<think>` and `<answer>` tags).
     ///
     /// Returns 1.0 for completions with valid format, 0.0 otherwise.
@@ -93,9 +321,86 @@ impl PyRewardEvaluator {
     ///
     /// # Returns
     /// List of floats (1.0 or 0.0)
-    fn format_reward(&self, completions: &Bound<'_, PyList>) -> PyResult<Vec<f64>> {
+    fn format_reward(&self, completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        Ok(self.evaluator.evaluate_format_batch(&completions))
+    }
+
+    /// Evaluate completions against custom regex patterns.
+    ///
+    /// Returns 1.0 for completions matching their pattern, 0.0 otherwise. Each completion is
+    /// checked against one pattern; combine several requirements into that pattern.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of completion strings/dicts
+    /// - `patterns`: A single regex applied to all completions, or a list with one regex per
+    ///   completion
+    ///
+    /// # Returns
+    /// List of floats (1.0 or 0.0)
+    fn regex_format_reward(
+        &self,
+        completions: &Bound<'_, PyAny>,
+        patterns: &Bound<'_, PyAny>,
+    ) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        let patterns = extract_patterns(patterns, completions.len())?;
+        self.evaluator
+            .evaluate_format_regex_batch(&completions, &patterns)
+            .map_err(|e| PyValueError::new_err(format!("{:#}", e)))
+    }
+
+    /// Evaluate complexity rewards (simpler code scores higher).
+    ///
+    /// Returns `1.0 - min(complexity / max_complexity, 1.0)` based on the approximate
+    /// cyclomatic complexity of the extracted code; completions without code get 0.0.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of completion strings/dicts
+    /// - `max_complexity`: Complexity at (or above) which the reward is 0.0
+    ///
+    /// # Returns
+    /// List of floats in [0.0, 1.0]
+    #[pyo3(signature = (completions, max_complexity=10))]
+    fn complexity_reward(
+        &self,
+        completions: &Bound<'_, PyAny>,
+        max_complexity: u32,
+    ) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        check_max_complexity(max_complexity)?;
+        Ok(self
+            .evaluator
+            .evaluate_complexity_batch(&completions, max_complexity))
+    }
+
+    /// Evaluate type check rewards (runs `mypy --strict` on the extracted code).
+    ///
+    /// mypy must be installed; it runs outside the sandbox since it only parses the code.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of completion strings/dicts
+    ///
+    /// # Returns
+    /// List of floats (1.0 = no type errors, 0.0 = type errors/timeout/mypy failure)
+    fn type_check_reward(&self, py: Python, completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        py.detach(|| Ok(self.evaluator.evaluate_type_check_batch(&completions)))
+    }
+
+    /// Evaluate code style rewards (runs the configured linter on the extracted code).
+    ///
+    /// The linter (`linting_tool`: ruff or flake8) must be installed; it checks the
+    /// pycodestyle (E) and pyflakes (F) rules outside the sandbox.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of completion strings/dicts
+    ///
+    /// # Returns
+    /// List of floats `1 / (1 + error_count)` (0.0 = no code/timeout/linter failure)
+    fn linting_reward(&self, py: Python, completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
         let completions = extract_completions_from_pylist(completions)?;
-        Ok(self.evaluator.evaluate_response_format(&completions))
+        py.detach(|| Ok(self.evaluator.evaluate_linting_batch(&completions)))
     }
 
     /// Evaluate execution rewards (runs code with tests).
@@ -105,8 +410,21 @@ impl PyRewardEvaluator {
     ///
     /// # Arguments:
     /// - `completions`: List of LLM outputs
-    /// - `kwargs["test"]`: List of test code strings
-    /// - `kwargs["entry_point"]`: List of entry points (e.g., "add" or "Solution().method")
+    /// - `kwargs["test"]`: List of test code strings (`None` items score 0.0)
+    /// - `kwargs["entry_point"]`: List of entry points (e.g., "add" or "Solution().method");
+    ///   a `None` item skips the entry point check and passes `None` to the check function
+    /// - `kwargs["stdin"]`: Optional list of stdin inputs. When given, the code runs without
+    ///   test wrapping and its stdout is compared against `kwargs["expected_output"]`
+    /// - `kwargs["language"]`: Optional list of languages (e.g. `"python"`, `"javascript"`)
+    ///   for mixed-language batches. Python and Rust can be executed: other languages
+    ///   score 0.0
+    /// - `kwargs["dry_run"]`: If true, only check code extraction and the entry point for this
+    ///   call (1.0 if both pass) without running the sandbox
+    /// - `kwargs["pre_wrapped"]`: If true, the tests already print `TESTS_PASSED:X/Y` and run
+    ///   unwrapped for this call (see `pre_wrapped_tests`)
+    /// - `kwargs["chunk_size"]`: Optional maximum number of completions evaluated at once, to
+    ///   bound the sandbox processes and temp files of very large batches (not combined with
+    ///   `language`)
     ///
     /// # Returns
     /// List of floats (1.0 = all tests passed, 0.0 = failed/error)
@@ -114,48 +432,917 @@ impl PyRewardEvaluator {
     fn execution_reward(
         &self,
         py: Python,
-        completions: &Bound<'_, PyList>,
+        completions: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<f64>> {
+        evaluate_execution_rewards(py, &self.evaluator, completions, kwargs)
+    }
+
+    /// Evaluate execution rewards lazily, as an iterator of floats in input order.
+    ///
+    /// Completions are evaluated `chunk_size` at a time (default: one per thread) as the
+    /// iterator is consumed, so very large batches never hold every reward at once.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `chunk_size`: Optional number of completions evaluated (in parallel) per step
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`: As in `execution_reward`
+    ///
+    /// # Examples
+    /// ```python
+    /// for reward in evaluator.execution_reward_stream(completions, test=tests, entry_point=entry_points):
+    ///     ...
+    /// ```
+    #[pyo3(signature = (completions, chunk_size=None, **kwargs))]
+    fn execution_reward_stream(
+        slf: &Bound<'_, Self>,
+        completions: &Bound<'_, PyAny>,
+        chunk_size: Option<usize>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyExecutionRewardStream> {
+        PyExecutionRewardStream::new(Some(slf.clone().unbind()), completions, chunk_size, kwargs)
+    }
+
+    /// Select the completions worth executing, before an (expensive) execution reward.
+    ///
+    /// Drops completions that would get 0.0 without reaching the sandbox: a missing or
+    /// blank test, no extractable code, or an entry point the code does not define.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`: As in `execution_reward`
+    ///
+    /// # Returns
+    /// Tuple `(valid_indices, filtered_completions, filtered_tests, filtered_entry_points)`;
+    /// the rewards of the other indices are 0.0
+    ///
+    /// # Examples
+    /// ```python
+    /// indices, completions, tests, entry_points = evaluator.filter_valid_completions(
+    ///     completions, test=tests, entry_point=entry_points)
+    /// rewards = [0.0] * len(batch)
+    /// for idx, reward in zip(indices, evaluator.execution_reward(
+    ///         completions, test=tests, entry_point=entry_points)):
+    ///     rewards[idx] = reward
+    /// ```
+    #[pyo3(signature = (completions, **kwargs))]
+    fn filter_valid_completions(
+        &self,
+        py: Python,
+        completions: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<FilteredCompletions> {
+        filter_completions(py, &self.evaluator, completions, kwargs)
+    }
+
+    /// Evaluate execution rewards with the details behind each reward.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`: As in `execution_reward`
+    ///
+    /// # Returns
+    /// List of dicts with keys `reward`, `code_extracted`, `timed_out`, `entry_point_found`,
+    /// `tests_passed`, `tests_total`, `wall_ms` and `stderr_snippet` (str or None)
+    #[pyo3(signature = (completions, **kwargs))]
+    fn execution_reward_detailed<'py>(
+        &self,
+        py: Python<'py>,
+        completions: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        evaluate_execution_records(py, &self.evaluator, completions, kwargs)
+    }
+
+    /// Evaluate execution together with static code-quality signals.
+    ///
+    /// The composite score is a weighted mean of the execution reward and the type hint,
+    /// docstring and comment signals, using the evaluator's `quality_weights`.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`: As in `execution_reward`
+    ///
+    /// # Returns
+    /// List of dicts with keys `execution`, `has_type_hints`, `has_docstring`,
+    /// `has_comments`, `code_length_chars` and `composite`
+    #[pyo3(signature = (completions, **kwargs))]
+    fn code_quality_reward<'py>(
+        &self,
+        py: Python<'py>,
+        completions: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        evaluate_code_quality(py, &self.evaluator, completions, kwargs)
+    }
+
+    /// Evaluate execution rewards while showing a `tqdm` progress bar.
+    ///
+    /// Same as `execution_reward` (without `stdin=` support); the bar advances as each
+    /// completion finishes. If `tqdm` is not installed, no progress is displayed.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `desc`: Progress bar description (default: "evaluating")
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`: As in `execution_reward`
+    ///
+    /// # Returns
+    /// List of floats (1.0 = all tests passed, 0.0 = failed/error)
+    #[pyo3(signature = (completions, *, desc="evaluating", **kwargs))]
+    fn execution_reward_tqdm(
+        &self,
+        py: Python,
+        completions: &Bound<'_, PyAny>,
+        desc: &str,
         kwargs: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<Vec<f64>> {
         let completions = extract_completions_from_pylist(completions)?;
+        let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
 
-        let (tests, entry_points) = if let Some(kwargs) = kwargs {
-            let tests = extract_string_list_from_kwargs(kwargs, "test", completions.len())?;
-            let entry_points =
-                extract_string_list_from_kwargs(kwargs, "entry_point", completions.len())?;
-            (tests, entry_points)
-        } else {
-            (
-                vec![String::new(); completions.len()],
-                vec![String::new(); completions.len()],
-            )
+        // tqdm is an optional Python dependency
+        let progress_bar = match py.import("tqdm") {
+            Ok(tqdm) => {
+                let bar_kwargs = PyDict::new(py);
+                bar_kwargs.set_item("total", completions.len())?;
+                bar_kwargs.set_item("desc", desc)?;
+                Some(tqdm.getattr("tqdm")?.call((), Some(&bar_kwargs))?.unbind())
+            }
+            Err(_) => None,
         };
 
+        let rewards = py.detach(|| {
+            self.evaluator.evaluate_execution_batch_with_progress(
+                &completions,
+                &tests,
+                &entry_points,
+                || {
+                    if let Some(bar) = &progress_bar {
+                        Python::attach(|py| {
+                            if let Err(e) = bar.call_method1(py, "update", (1,)) {
+                                tracing::warn!(error = %e, "Failed to update progress bar");
+                            }
+                        });
+                    }
+                },
+            )
+        });
+
+        if let Some(bar) = progress_bar {
+            bar.call_method0(py, "close")?;
+        }
+        Ok(rewards)
+    }
+
+    /// Evaluate output rewards (runs code and compares its stdout).
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `normalize_whitespace`: Ignore trailing whitespace, blank lines at the ends and
+    ///   repeated blank lines
+    ///   (default: the evaluator configuration, which is `True`)
+    /// - `kwargs["expected_output"]`: List of expected stdout strings
+    ///
+    /// # Returns
+    /// List of floats (1.0 = output matches, 0.0 = mismatch/error)
+    #[pyo3(signature = (completions, normalize_whitespace=None, **kwargs))]
+    fn output_reward(
+        &self,
+        py: Python,
+        completions: &Bound<'_, PyAny>,
+        normalize_whitespace: Option<bool>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        let expected_outputs = extract_expected_outputs(kwargs, completions.len())?;
+        let normalize_whitespace =
+            normalize_whitespace.unwrap_or(self.evaluator.config().output_normalize_whitespace);
+
+        py.detach(|| {
+            Ok(self.evaluator.evaluate_output_batch_with(
+                &completions,
+                &expected_outputs,
+                normalize_whitespace,
+            ))
+        })
+    }
+
+    /// Evaluate output match rewards (runs code without test wrapping and compares its
+    /// stdout), as used for competitive programming problems.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `normalize`: Ignore trailing whitespace, blank lines at the ends and repeated blank
+    ///   lines (default: `True`)
+    /// - `kwargs["expected_output"]`: List of expected stdout strings
+    ///
+    /// # Returns
+    /// List of floats (1.0 = output matches, 0.0 = mismatch/error)
+    #[pyo3(signature = (completions, normalize=true, **kwargs))]
+    fn output_match_reward(
+        &self,
+        py: Python,
+        completions: &Bound<'_, PyAny>,
+        normalize: bool,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        let expected_outputs = extract_expected_outputs(kwargs, completions.len())?;
+
+        py.detach(|| {
+            Ok(self.evaluator.evaluate_output_match_batch(
+                &completions,
+                &expected_outputs,
+                normalize,
+            ))
+        })
+    }
+
+    /// Evaluate format and execution in a single pass and return their weighted sum.
+    ///
+    /// Completions that fail the format check are not executed.
+    ///
+    /// # Arguments:
+    /// - `completions`: List of LLM outputs
+    /// - `format_weight`: Weight of the format score (default: 0.1)
+    /// - `exec_weight`: Weight of the execution score (default: 0.9)
+    /// - `kwargs["test"]`: List of test code strings
+    /// - `kwargs["entry_point"]`: List of entry points
+    ///
+    /// # Returns
+    /// List of floats (`format_weight * format + exec_weight * execution`)
+    #[pyo3(signature = (completions, format_weight=0.1, exec_weight=0.9, **kwargs))]
+    fn combined_reward(
+        &self,
+        py: Python,
+        completions: &Bound<'_, PyAny>,
+        format_weight: f64,
+        exec_weight: f64,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions = extract_completions_from_pylist(completions)?;
+        let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+        py.detach(|| {
+            Ok(self.evaluator.evaluate_combined_gated(
+                &completions,
+                &tests,
+                &entry_points,
+                format_weight,
+                exec_weight,
+            ))
+        })
+    }
+
+    /// Compute Pass@K per problem from K or more sampled completions per problem.
+    ///
+    /// # Arguments:
+    /// - `completions_per_problem`: List of lists of LLM outputs (one inner list per problem)
+    /// - `test`: List of test code strings (one per problem)
+    /// - `entry_point`: List of entry points (one per problem)
+    /// - `k`: Number of samples considered per problem
+    ///
+    /// # Returns
+    /// List of floats in [0.0, 1.0], one per problem
+    fn pass_at_k(
+        &self,
+        py: Python,
+        completions_per_problem: &Bound<'_, PyAny>,
+        test: Vec<Option<String>>,
+        entry_point: Vec<Option<String>>,
+        k: usize,
+    ) -> PyResult<Vec<f64>> {
+        if k == 0 {
+            return Err(PyValueError::new_err("k must be at least 1"));
+        }
+        let completions_per_problem =
+            extract_grouped_completions(completions_per_problem, &test, &entry_point, k)?;
+
+        py.detach(|| {
+            Ok(self.evaluator.batch_evaluate_pass_at_k(
+                &completions_per_problem,
+                &test,
+                &entry_point,
+                k,
+            ))
+        })
+    }
+
+    /// Best-of-N reward: maximum execution reward across each problem's completions.
+    ///
+    /// # Arguments:
+    /// - `completions_per_problem`: List of lists of LLM outputs (one inner list per problem)
+    /// - `test`: List of test code strings (one per problem)
+    /// - `entry_point`: List of entry points (one per problem)
+    ///
+    /// # Returns
+    /// List of floats, one per problem
+    fn best_of_n_reward(
+        &self,
+        py: Python,
+        completions_per_problem: &Bound<'_, PyAny>,
+        test: Vec<Option<String>>,
+        entry_point: Vec<Option<String>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions_per_problem =
+            extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
+        py.detach(|| {
+            Ok(self
+                .evaluator
+                .evaluate_best_of_n(&completions_per_problem, &test, &entry_point))
+        })
+    }
+
+    /// Execution rewards for grouped completions, keeping the grouping.
+    ///
+    /// Matches the group rollout format (`List[List[str]]`, K rollouts per problem) without
+    /// manual flattening: all completions are evaluated in one parallel batch, then reshaped.
+    ///
+    /// # Arguments:
+    /// - `completions_grouped`: List of lists of LLM outputs (one inner list per problem)
+    /// - `test`: List of test code strings (one per problem)
+    /// - `entry_point`: List of entry points (one per problem)
+    /// - `detailed`: Return a record per completion instead of its reward
+    ///
+    /// # Returns
+    /// List of lists with the same shape as `completions_grouped`: floats, or with
+    /// `detailed=True` dicts with the keys of `execution_reward_detailed` plus `flat_index`,
+    /// the position of the completion in the flattened batch (the `completion_idx` of logs)
+    #[pyo3(signature = (completions_grouped, test, entry_point, detailed=false))]
+    fn execution_reward_batch_of_lists<'py>(
+        &self,
+        py: Python<'py>,
+        completions_grouped: &Bound<'_, PyAny>,
+        test: Vec<Option<String>>,
+        entry_point: Vec<Option<String>>,
+        detailed: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        evaluate_execution_grouped(
+            py,
+            &self.evaluator,
+            completions_grouped,
+            &test,
+            &entry_point,
+            detailed,
+        )
+    }
+
+    /// Mean execution reward across each problem's completions.
+    ///
+    /// Arguments and return value are the same as for `best_of_n_reward`.
+    fn mean_reward(
+        &self,
+        py: Python,
+        completions_per_problem: &Bound<'_, PyAny>,
+        test: Vec<Option<String>>,
+        entry_point: Vec<Option<String>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions_per_problem =
+            extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
+        py.detach(|| {
+            Ok(self
+                .evaluator
+                .evaluate_mean_of_n(&completions_per_problem, &test, &entry_point))
+        })
+    }
+
+    /// Fraction of each problem's completions that pass all tests.
+    ///
+    /// Arguments and return value are the same as for `best_of_n_reward`.
+    fn majority_reward(
+        &self,
+        py: Python,
+        completions_per_problem: &Bound<'_, PyAny>,
+        test: Vec<Option<String>>,
+        entry_point: Vec<Option<String>>,
+    ) -> PyResult<Vec<f64>> {
+        let completions_per_problem =
+            extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
         py.detach(|| {
             Ok(self
                 .evaluator
-                .evaluate_execution_batch(&completions, &tests, &entry_points))
+                .evaluate_majority_of_n(&completions_per_problem, &test, &entry_point))
         })
     }
 }
 
 // ==========================================================================================
 
-/// Module-level function for format reward (uses default evaluator)
+/// Python-facing view of `EvaluatorConfig`.
 ///
-/// Convenience function for simple use cases. Uses global default evaluator
-/// with standard configuration.
+/// Returned by `RewardEvaluator.config` as a copy: modifying it does not affect the
+/// evaluator it came from (configuration is immutable after construction).
 ///
 /// # Examples
 /// ```python
-/// from fastrlrewards import format_reward
-///
-/// scores = format_reward(completions)
+/// evaluator = RewardEvaluator(timeout_seconds=20)
+/// print(evaluator.config.timeout_seconds)  # 20
+/// print(vars(evaluator.config))            # {'timeout_seconds': 20, ...}
 /// ```
-#[pyfunction]
-pub fn format_reward(completions: &Bound<'_, PyList>) -> PyResult<Vec<f64>> {
-    let completions = extract_completions_from_pylist(completions)?;
-    Ok(DEFAULT_EVALUATOR.evaluate_response_format(&completions))
+#[pyclass(name = "EvaluatorConfig")]
+#[derive(Clone)]
+pub struct PyEvaluatorConfig {
+    #[pyo3(get, set)]
+    timeout_seconds: u64,
+    #[pyo3(get, set)]
+    memory_limit_mb: u64,
+    #[pyo3(get, set)]
+    cpu_time_limit: u64,
+    #[pyo3(get, set)]
+    num_threads: Option<usize>,
+    #[pyo3(get, set)]
+    fallback_policy: String,
+    #[pyo3(get, set)]
+    sandbox_backend: String,
+    #[pyo3(get, set)]
+    output_normalize_whitespace: bool,
+    #[pyo3(get, set)]
+    max_completion_chars: Option<usize>,
+    #[pyo3(get, set)]
+    min_code_lines: usize,
+    #[pyo3(get, set)]
+    stdin_input: Option<String>,
+    #[pyo3(get, set)]
+    allow_lower_wall_timeout: bool,
+    #[pyo3(get, set)]
+    type_check_timeout: u64,
+    #[pyo3(get, set)]
+    linting_tool: String,
+    #[pyo3(get, set)]
+    linting_timeout: u64,
+    #[pyo3(get, set)]
+    check_function_names: Vec<String>,
+    #[pyo3(get, set)]
+    prefer_ram_tmpdir: bool,
+    #[pyo3(get, set)]
+    clamp_rewards: bool,
+    #[pyo3(get, set)]
+    extra_firejail_args: Vec<String>,
+    #[pyo3(get, set)]
+    reject_stubs: bool,
+    #[pyo3(get, set)]
+    multi_method_mode: bool,
+    #[pyo3(get, set)]
+    max_open_files: u32,
+    #[pyo3(get, set)]
+    gate_execution_on_format: bool,
+    #[pyo3(get, set)]
+    solution_class_names: Vec<String>,
+    #[pyo3(get, set)]
+    dry_run: bool,
+    #[pyo3(get, set)]
+    seccomp_profile: String,
+    #[pyo3(get, set)]
+    debug: Option<String>,
+    quality_weights: QualityWeights,
+    #[pyo3(get, set)]
+    adaptive_timeout: bool,
+    #[pyo3(get, set)]
+    pre_wrapped_tests: bool,
+}
+
+impl From<&EvaluatorConfig> for PyEvaluatorConfig {
+    fn from(config: &EvaluatorConfig) -> Self {
+        Self {
+            timeout_seconds: config.timeout_seconds,
+            memory_limit_mb: config.memory_limit_mb,
+            cpu_time_limit: config.cpu_time_limit,
+            num_threads: config.num_threads,
+            fallback_policy: config.fallback_policy.to_string(),
+            sandbox_backend: config.sandbox_backend.to_string(),
+            output_normalize_whitespace: config.output_normalize_whitespace,
+            max_completion_chars: config.max_completion_chars,
+            min_code_lines: config.min_code_lines,
+            stdin_input: config.stdin_input.clone(),
+            allow_lower_wall_timeout: config.allow_lower_wall_timeout,
+            type_check_timeout: config.type_check_timeout,
+            linting_tool: config.linting_tool.to_string(),
+            linting_timeout: config.linting_timeout,
+            check_function_names: config.check_function_names.clone(),
+            prefer_ram_tmpdir: config.prefer_ram_tmpdir,
+            clamp_rewards: config.clamp_rewards,
+            extra_firejail_args: config.extra_firejail_args.clone(),
+            reject_stubs: config.reject_stubs,
+            multi_method_mode: config.multi_method_mode,
+            max_open_files: config.max_open_files,
+            gate_execution_on_format: config.gate_execution_on_format,
+            solution_class_names: config.solution_class_names.clone(),
+            dry_run: config.dry_run,
+            seccomp_profile: config.seccomp_profile.to_string(),
+            debug: config
+                .debug
+                .map(|level| level.as_str().to_ascii_lowercase()),
+            quality_weights: config.quality_weights,
+            adaptive_timeout: config.adaptive_timeout,
+            pre_wrapped_tests: config.pre_wrapped_tests,
+        }
+    }
+}
+
+impl TryFrom<&PyEvaluatorConfig> for EvaluatorConfig {
+    type Error = PyErr;
+
+    fn try_from(config: &PyEvaluatorConfig) -> PyResult<Self> {
+        let invalid = |e: String| PyValueError::new_err(format!("Invalid configuration: {}", e));
+        Ok(Self {
+            timeout_seconds: config.timeout_seconds,
+            memory_limit_mb: config.memory_limit_mb,
+            cpu_time_limit: config.cpu_time_limit,
+            num_threads: config.num_threads,
+            fallback_policy: config.fallback_policy.parse().map_err(invalid)?,
+            sandbox_backend: config.sandbox_backend.parse().map_err(invalid)?,
+            output_normalize_whitespace: config.output_normalize_whitespace,
+            max_completion_chars: config.max_completion_chars,
+            min_code_lines: config.min_code_lines,
+            stdin_input: config.stdin_input.clone(),
+            allow_lower_wall_timeout: config.allow_lower_wall_timeout,
+            type_check_timeout: config.type_check_timeout,
+            linting_tool: config.linting_tool.parse().map_err(invalid)?,
+            linting_timeout: config.linting_timeout,
+            check_function_names: config.check_function_names.clone(),
+            prefer_ram_tmpdir: config.prefer_ram_tmpdir,
+            clamp_rewards: config.clamp_rewards,
+            extra_firejail_args: config.extra_firejail_args.clone(),
+            reject_stubs: config.reject_stubs,
+            multi_method_mode: config.multi_method_mode,
+            max_open_files: config.max_open_files,
+            gate_execution_on_format: config.gate_execution_on_format,
+            solution_class_names: config.solution_class_names.clone(),
+            dry_run: config.dry_run,
+            seccomp_profile: config.seccomp_profile.parse().map_err(invalid)?,
+            debug: parse_log_level(config.debug.as_deref()).map_err(invalid)?,
+            quality_weights: config.quality_weights,
+            adaptive_timeout: config.adaptive_timeout,
+            pre_wrapped_tests: config.pre_wrapped_tests,
+        })
+    }
+}
+
+#[pymethods]
+impl PyEvaluatorConfig {
+    /// Create a configuration with default values.
+    #[new]
+    fn new() -> Self {
+        Self::from(&EvaluatorConfig::default())
+    }
+
+    /// All fields as a dict (also makes `vars(config)` work).
+    #[getter(__dict__)]
+    fn dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("timeout_seconds", self.timeout_seconds)?;
+        dict.set_item("memory_limit_mb", self.memory_limit_mb)?;
+        dict.set_item("cpu_time_limit", self.cpu_time_limit)?;
+        dict.set_item("num_threads", self.num_threads)?;
+        dict.set_item("fallback_policy", &self.fallback_policy)?;
+        dict.set_item("sandbox_backend", &self.sandbox_backend)?;
+        dict.set_item(
+            "output_normalize_whitespace",
+            self.output_normalize_whitespace,
+        )?;
+        dict.set_item("max_completion_chars", self.max_completion_chars)?;
+        dict.set_item("min_code_lines", self.min_code_lines)?;
+        dict.set_item("stdin_input", &self.stdin_input)?;
+        dict.set_item("allow_lower_wall_timeout", self.allow_lower_wall_timeout)?;
+        dict.set_item("type_check_timeout", self.type_check_timeout)?;
+        dict.set_item("linting_tool", &self.linting_tool)?;
+        dict.set_item("linting_timeout", self.linting_timeout)?;
+        dict.set_item("check_function_names", &self.check_function_names)?;
+        dict.set_item("prefer_ram_tmpdir", self.prefer_ram_tmpdir)?;
+        dict.set_item("clamp_rewards", self.clamp_rewards)?;
+        dict.set_item("extra_firejail_args", &self.extra_firejail_args)?;
+        dict.set_item("reject_stubs", self.reject_stubs)?;
+        dict.set_item("multi_method_mode", self.multi_method_mode)?;
+        dict.set_item("max_open_files", self.max_open_files)?;
+        dict.set_item("gate_execution_on_format", self.gate_execution_on_format)?;
+        dict.set_item("solution_class_names", &self.solution_class_names)?;
+        dict.set_item("dry_run", self.dry_run)?;
+        dict.set_item("seccomp_profile", &self.seccomp_profile)?;
+        dict.set_item("debug", &self.debug)?;
+        dict.set_item("quality_weights", self.quality_weights(py)?)?;
+        dict.set_item("adaptive_timeout", self.adaptive_timeout)?;
+        dict.set_item("pre_wrapped_tests", self.pre_wrapped_tests)?;
+        Ok(dict)
+    }
+
+    /// Weights of `code_quality_reward`'s composite score, as a dict keyed by signal name.
+    #[getter]
+    fn quality_weights<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, weight) in self.quality_weights.pairs() {
+            dict.set_item(name, weight)?;
+        }
+        Ok(dict)
+    }
+
+    /// Signals missing from `weights` keep their default weight.
+    #[setter]
+    fn set_quality_weights(&mut self, weights: HashMap<String, f64>) -> PyResult<()> {
+        self.quality_weights = quality_weights_from_map(weights)?;
+        Ok(())
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "EvaluatorConfig({})",
+            repr_fields(&self.dict(py)?)?
+        ))
+    }
+}
+
+// ==========================================================================================
+
+/// Python-facing registry of named reward functions.
+///
+/// Rewards are evaluated with the given `RewardEvaluator` (or the default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import RewardEvaluator, RewardRegistry
+///
+/// registry = RewardRegistry(evaluator=RewardEvaluator(timeout_seconds=20))
+/// registry.register("length", lambda completion, context: min(len(completion) / 1000, 1.0))
+/// print(registry.names())  # ['execution', 'format', 'length', 'output']
+/// scores = registry.evaluate("execution", completions, test=tests, entry_point=entry_points)
+/// ```
+#[pyclass(name = "RewardRegistry")]
+pub struct PyRewardRegistry {
+    registry: RewardRegistry,
+    evaluator: Option<Py<PyRewardEvaluator>>,
+}
+
+#[pymethods]
+impl PyRewardRegistry {
+    #[new]
+    #[pyo3(signature = (evaluator=None))]
+    fn new(evaluator: Option<Py<PyRewardEvaluator>>) -> Self {
+        Self {
+            registry: RewardRegistry::new(),
+            evaluator,
+        }
+    }
+
+    /// Register a Python callable as a reward function (UDF).
+    ///
+    /// The callable is invoked as `f(completion, context)` where `context` is a dict with
+    /// `test`, `entry_point` and `expected_output`, and must return a float. Registering an
+    /// existing name replaces it (including built-ins).
+    fn register(&mut self, name: &str, callable: &Bound<'_, PyAny>) -> PyResult<()> {
+        if !callable.is_callable() {
+            return Err(PyTypeError::new_err(format!(
+                "Reward function '{}' must be callable",
+                name
+            )));
+        }
+
+        self.registry
+            .register_python_fn(name, callable.clone().unbind());
+        Ok(())
+    }
+
+    /// Names of all registered reward functions (sorted).
+    fn names(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.registry.get(name).is_some()
+    }
+
+    /// Evaluate the reward function registered under `name`.
+    ///
+    /// # Arguments:
+    /// - `name`: Registered reward function name
+    /// - `completions`: List of LLM outputs
+    /// - `kwargs["test"]`, `kwargs["entry_point"]`, `kwargs["expected_output"]`: Optional
+    ///   per-completion lists passed to the reward function
+    ///
+    /// # Returns
+    /// List of floats, one per completion
+    #[pyo3(signature = (name, completions, **kwargs))]
+    fn evaluate(
+        &self,
+        py: Python,
+        name: &str,
+        completions: &Bound<'_, PyAny>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Vec<f64>> {
+        if self.registry.get(name).is_none() {
+            return Err(PyKeyError::new_err(format!(
+                "Unknown reward function '{}' (registered: {})",
+                name,
+                self.registry.names().join(", ")
+            )));
+        }
+
+        let completions = extract_completions_from_pylist(completions)?;
+        let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+        let expected_outputs = extract_expected_outputs(kwargs, completions.len())?;
+
+        let py_evaluator = self.evaluator.as_ref().map(|e| e.borrow(py));
+        let evaluator = match &py_evaluator {
+            Some(py_evaluator) => &py_evaluator.evaluator,
+            None => &*DEFAULT_EVALUATOR,
+        };
+
+        py.detach(|| {
+            Ok(self
+                .registry
+                .evaluate_batch(
+                    name,
+                    evaluator,
+                    &completions,
+                    &tests,
+                    &entry_points,
+                    &expected_outputs,
+                )
+                .unwrap_or_default())
+        })
+    }
+}
+
+// ==========================================================================================
+
+/// Iterator over execution rewards, returned by `execution_reward_stream`.
+///
+/// Each time its buffered rewards run out, the next chunk of completions is evaluated in
+/// parallel (without holding the GIL).
+#[pyclass(name = "ExecutionRewardStream")]
+pub struct PyExecutionRewardStream {
+    evaluator: Option<Py<PyRewardEvaluator>>,
+    completions: Vec<String>,
+    tests: Vec<Option<String>>,
+    entry_points: Vec<Option<String>>,
+    chunk_size: usize,
+    next_start: usize,
+    pending: VecDeque<f64>,
+}
+
+impl PyExecutionRewardStream {
+    /// Stream over `completions` evaluated by `evaluator` (`None` for the default evaluator).
+    fn new(
+        evaluator: Option<Py<PyRewardEvaluator>>,
+        completions: &Bound<'_, PyAny>,
+        chunk_size: Option<usize>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let completions = extract_completions_from_pylist(completions)?;
+        let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+        let chunk_size = match chunk_size {
+            Some(0) => return Err(PyValueError::new_err("chunk_size must be at least 1")),
+            Some(chunk_size) => chunk_size,
+            None => rayon::current_num_threads(),
+        };
+
+        Ok(Self {
+            evaluator,
+            completions,
+            tests,
+            entry_points,
+            chunk_size,
+            next_start: 0,
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+#[pymethods]
+impl PyExecutionRewardStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> Option<f64> {
+        if self.pending.is_empty() && self.next_start < self.completions.len() {
+            let start = self.next_start;
+            let end = (start + self.chunk_size).min(self.completions.len());
+            let py_evaluator = self.evaluator.as_ref().map(|e| e.borrow(py));
+            let evaluator = match &py_evaluator {
+                Some(py_evaluator) => &py_evaluator.evaluator,
+                None => &*DEFAULT_EVALUATOR,
+            };
+
+            let rewards = py.detach(|| {
+                evaluator.evaluate_execution_chunk(
+                    start,
+                    &self.completions[start..end],
+                    &self.tests[start..end],
+                    &self.entry_points[start..end],
+                )
+            });
+            self.pending.extend(rewards);
+            self.next_start = end;
+        }
+        self.pending.pop_front()
+    }
+
+    /// Number of rewards not yet yielded.
+    fn __len__(&self) -> usize {
+        self.pending.len() + self.completions.len() - self.next_start
+    }
+}
+
+// ==========================================================================================
+
+/// Module-level function for format reward (uses default evaluator)
+///
+/// Convenience function for simple use cases. Uses global default evaluator
+/// with standard configuration.
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import format_reward
+///
+/// scores = format_reward(completions)
+/// ```
+#[pyfunction]
+pub fn format_reward(completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    Ok(DEFAULT_EVALUATOR.evaluate_format_batch(&completions))
+}
+
+/// Format reward of a single completion (uses default evaluator).
+///
+/// Convenience for interactive debugging; use `format_reward` for batches.
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import format_reward_single
+///
+/// format_reward_single("<think>...</think>\n<answer>...</answer>")  # 1.0
+/// ```
+#[pyfunction]
+pub fn format_reward_single(completion: String) -> f64 {
+    DEFAULT_EVALUATOR.evaluate_format_batch(&[completion])[0]
+}
+
+/// Module-level function for regex format reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import regex_format_reward
+///
+/// scores = regex_format_reward(completions, r"(?s)<answer>.*</answer>")
+/// ```
+#[pyfunction]
+pub fn regex_format_reward(
+    completions: &Bound<'_, PyAny>,
+    patterns: &Bound<'_, PyAny>,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let patterns = extract_patterns(patterns, completions.len())?;
+    DEFAULT_EVALUATOR
+        .evaluate_format_regex_batch(&completions, &patterns)
+        .map_err(|e| PyValueError::new_err(format!("{:#}", e)))
+}
+
+/// Module-level function for complexity reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import complexity_reward
+///
+/// scores = complexity_reward(completions, max_complexity=10)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, max_complexity=10))]
+pub fn complexity_reward(
+    completions: &Bound<'_, PyAny>,
+    max_complexity: u32,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    check_max_complexity(max_complexity)?;
+    Ok(DEFAULT_EVALUATOR.evaluate_complexity_batch(&completions, max_complexity))
+}
+
+/// Module-level function for type check reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import type_check_reward
+///
+/// scores = type_check_reward(completions)
+/// ```
+#[pyfunction]
+pub fn type_check_reward(py: Python, completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    py.detach(|| Ok(DEFAULT_EVALUATOR.evaluate_type_check_batch(&completions)))
+}
+
+/// Module-level function for linting reward (uses default evaluator, i.e. ruff).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import linting_reward
+///
+/// scores = linting_reward(completions)
+/// ```
+#[pyfunction]
+pub fn linting_reward(py: Python, completions: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    py.detach(|| Ok(DEFAULT_EVALUATOR.evaluate_linting_batch(&completions)))
 }
 
 /// Module-level function for execution reward (uses default evaluator).
@@ -173,57 +1360,707 @@ pub fn format_reward(completions: &Bound<'_, PyList>) -> PyResult<Vec<f64>> {
 #[pyo3(signature = (completions, **kwargs))]
 pub fn execution_reward(
     py: Python,
-    completions: &Bound<'_, PyList>,
+    completions: &Bound<'_, PyAny>,
     kwargs: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Vec<f64>> {
-    let completions = extract_completions_from_pylist(completions)?;
+    evaluate_execution_rewards(py, &DEFAULT_EVALUATOR, completions, kwargs)
+}
 
-    let (tests, entry_points) = if let Some(kwargs) = kwargs {
-        let tests = extract_string_list_from_kwargs(kwargs, "test", completions.len())?;
-        let entry_points =
-            extract_string_list_from_kwargs(kwargs, "entry_point", completions.len())?;
-        (tests, entry_points)
-    } else {
-        (
-            vec![String::new(); completions.len()],
-            vec![String::new(); completions.len()],
+/// Module-level function for lazily evaluated execution rewards (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import execution_reward_stream
+///
+/// for reward in execution_reward_stream(completions, test=tests, entry_point=entry_points):
+///     ...
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, chunk_size=None, **kwargs))]
+pub fn execution_reward_stream(
+    completions: &Bound<'_, PyAny>,
+    chunk_size: Option<usize>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyExecutionRewardStream> {
+    PyExecutionRewardStream::new(None, completions, chunk_size, kwargs)
+}
+
+/// Module-level function to select the completions worth executing (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import filter_valid_completions
+///
+/// indices, completions, tests, entry_points = filter_valid_completions(
+///     completions, test=tests, entry_point=entry_points)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, **kwargs))]
+pub fn filter_valid_completions(
+    py: Python,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<FilteredCompletions> {
+    filter_completions(py, &DEFAULT_EVALUATOR, completions, kwargs)
+}
+
+/// Execution reward of a single completion (uses default evaluator).
+///
+/// Convenience for interactive debugging; use `execution_reward` for batches.
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import execution_reward_single
+///
+/// execution_reward_single(completion, test, "add")  # 1.0 if all tests pass
+/// ```
+#[pyfunction]
+pub fn execution_reward_single(
+    py: Python,
+    completion: String,
+    test: Option<String>,
+    entry_point: Option<String>,
+) -> f64 {
+    py.detach(|| {
+        DEFAULT_EVALUATOR.evaluate_execution_single(
+            &completion,
+            test.as_deref(),
+            entry_point.as_deref(),
         )
-    };
+    })
+}
+
+/// Module-level function for detailed execution rewards (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import execution_reward_detailed
+///
+/// records = execution_reward_detailed(completions, test=tests, entry_point=entry_points)
+/// failed = [r for r in records if r["reward"] == 0.0 and not r["entry_point_found"]]
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, **kwargs))]
+pub fn execution_reward_detailed<'py>(
+    py: Python<'py>,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    evaluate_execution_records(py, &DEFAULT_EVALUATOR, completions, kwargs)
+}
+
+/// Module-level function for code-quality rewards (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import code_quality_reward
+///
+/// scores = code_quality_reward(completions, test=tests, entry_point=entry_points)
+/// rewards = [s["composite"] for s in scores]
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, **kwargs))]
+pub fn code_quality_reward<'py>(
+    py: Python<'py>,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    evaluate_code_quality(py, &DEFAULT_EVALUATOR, completions, kwargs)
+}
+
+/// Execution reward with a user-provided Python test function instead of test code.
+///
+/// For each completion, the code is extracted and passed to `test_fn(code) -> bool`;
+/// a truthy result gives 1.0, anything else (including an exception) gives 0.0.
+/// The code is *not* run in the sandbox: `test_fn` is responsible for isolation.
+/// Completions are processed in parallel, but calls to `test_fn` hold the GIL.
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import execution_reward_callable
+///
+/// def test_fn(code):
+///     namespace = {}
+///     exec(code, namespace)
+///     return namespace["add"](1, 2) == 3
+///
+/// scores = execution_reward_callable(completions, test_fn)
+/// ```
+#[pyfunction]
+pub fn execution_reward_callable(
+    py: Python,
+    completions: &Bound<'_, PyAny>,
+    test_fn: &Bound<'_, PyAny>,
+) -> PyResult<Vec<f64>> {
+    if !test_fn.is_callable() {
+        return Err(PyTypeError::new_err("test_fn must be callable"));
+    }
+    let completions = extract_completions_from_pylist(completions)?;
+    let test_fn = test_fn.clone().unbind();
 
     py.detach(|| {
-        Ok(DEFAULT_EVALUATOR.evaluate_execution_batch(&completions, &tests, &entry_points))
+        Ok(completions
+            .par_iter()
+            .enumerate()
+            .map(|(idx, completion)| {
+                let code = extract_code_from_completion(completion);
+                if code.trim().is_empty() {
+                    return 0.0;
+                }
+
+                Python::attach(|py| {
+                    let passed = test_fn
+                        .call1(py, (code,))
+                        .and_then(|result| result.bind(py).is_truthy());
+                    match passed {
+                        Ok(true) => 1.0,
+                        Ok(false) => 0.0,
+                        Err(e) => {
+                            tracing::error!(
+                                completion_idx = idx,
+                                error = %e,
+                                "Python test function failed"
+                            );
+                            0.0
+                        }
+                    }
+                })
+            })
+            .collect())
     })
 }
 
+/// Module-level function for output reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import output_reward
+///
+/// scores = output_reward(completions, expected_output=["3\n", "Hello\n"])
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, normalize_whitespace=true, **kwargs))]
+pub fn output_reward(
+    py: Python,
+    completions: &Bound<'_, PyAny>,
+    normalize_whitespace: bool,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let expected_outputs = extract_expected_outputs(kwargs, completions.len())?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_output_batch_with(
+            &completions,
+            &expected_outputs,
+            normalize_whitespace,
+        ))
+    })
+}
+
+/// Module-level function for output match reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import output_match_reward
+///
+/// scores = output_match_reward(completions, expected_output=["3\n", "YES\n"])
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, normalize=true, **kwargs))]
+pub fn output_match_reward(
+    py: Python,
+    completions: &Bound<'_, PyAny>,
+    normalize: bool,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let expected_outputs = extract_expected_outputs(kwargs, completions.len())?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_output_match_batch(
+            &completions,
+            &expected_outputs,
+            normalize,
+        ))
+    })
+}
+
+/// Module-level function for combined format and execution reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import combined_reward
+///
+/// scores = combined_reward(completions, test=tests, entry_point=entry_points, format_weight=0.2)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions, format_weight=0.1, exec_weight=0.9, **kwargs))]
+pub fn combined_reward(
+    py: Python,
+    completions: &Bound<'_, PyAny>,
+    format_weight: f64,
+    exec_weight: f64,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_combined_gated(
+            &completions,
+            &tests,
+            &entry_points,
+            format_weight,
+            exec_weight,
+        ))
+    })
+}
+
+/// Module-level function for grouped execution rewards (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import execution_reward_batch_of_lists
+///
+/// # rollouts[i] holds the K completions for problem i
+/// rewards = execution_reward_batch_of_lists(rollouts, test=tests, entry_point=entry_points)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (completions_grouped, test, entry_point, detailed=false))]
+pub fn execution_reward_batch_of_lists<'py>(
+    py: Python<'py>,
+    completions_grouped: &Bound<'_, PyAny>,
+    test: Vec<Option<String>>,
+    entry_point: Vec<Option<String>>,
+    detailed: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    evaluate_execution_grouped(
+        py,
+        &DEFAULT_EVALUATOR,
+        completions_grouped,
+        &test,
+        &entry_point,
+        detailed,
+    )
+}
+
+/// Module-level function for Pass@K (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import pass_at_k
+///
+/// # Two problems with 4 samples each
+/// scores = pass_at_k(samples, test=tests, entry_point=entry_points, k=1)
+/// ```
+#[pyfunction]
+pub fn pass_at_k(
+    py: Python,
+    completions_per_problem: &Bound<'_, PyAny>,
+    test: Vec<Option<String>>,
+    entry_point: Vec<Option<String>>,
+    k: usize,
+) -> PyResult<Vec<f64>> {
+    if k == 0 {
+        return Err(PyValueError::new_err("k must be at least 1"));
+    }
+    let completions_per_problem =
+        extract_grouped_completions(completions_per_problem, &test, &entry_point, k)?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.batch_evaluate_pass_at_k(
+            &completions_per_problem,
+            &test,
+            &entry_point,
+            k,
+        ))
+    })
+}
+
+/// Module-level function for best-of-N reward (uses default evaluator).
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import best_of_n_reward
+///
+/// scores = best_of_n_reward(rollouts, test=tests, entry_point=entry_points)
+/// ```
+#[pyfunction]
+pub fn best_of_n_reward(
+    py: Python,
+    completions_per_problem: &Bound<'_, PyAny>,
+    test: Vec<Option<String>>,
+    entry_point: Vec<Option<String>>,
+) -> PyResult<Vec<f64>> {
+    let completions_per_problem =
+        extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_best_of_n(&completions_per_problem, &test, &entry_point))
+    })
+}
+
+/// Module-level function for mean-of-N reward (uses default evaluator).
+#[pyfunction]
+pub fn mean_reward(
+    py: Python,
+    completions_per_problem: &Bound<'_, PyAny>,
+    test: Vec<Option<String>>,
+    entry_point: Vec<Option<String>>,
+) -> PyResult<Vec<f64>> {
+    let completions_per_problem =
+        extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_mean_of_n(&completions_per_problem, &test, &entry_point))
+    })
+}
+
+/// Module-level function for majority reward (uses default evaluator).
+#[pyfunction]
+pub fn majority_reward(
+    py: Python,
+    completions_per_problem: &Bound<'_, PyAny>,
+    test: Vec<Option<String>>,
+    entry_point: Vec<Option<String>>,
+) -> PyResult<Vec<f64>> {
+    let completions_per_problem =
+        extract_grouped_completions(completions_per_problem, &test, &entry_point, 0)?;
+
+    py.detach(|| {
+        Ok(DEFAULT_EVALUATOR.evaluate_majority_of_n(&completions_per_problem, &test, &entry_point))
+    })
+}
+
+/// Aggregate statistics of a batch of rewards.
+///
+/// # Returns
+/// Dict with `pass_rate`, `total`, `passed` (rewards of 1.0), `failed`, `mean_reward` and
+/// `std_reward` (population standard deviation)
+///
+/// # Examples
+/// ```python
+/// from fastrlrewards import evaluation_summary, execution_reward
+///
+/// summary = evaluation_summary(execution_reward(completions, test=tests, entry_point=entry_points))
+/// print(f"pass rate: {summary['pass_rate']:.1%}")
+/// ```
+#[pyfunction]
+#[pyo3(name = "evaluation_summary")]
+pub fn evaluation_summary_dict(py: Python<'_>, rewards: Vec<f64>) -> PyResult<Bound<'_, PyDict>> {
+    summary_dict(py, &evaluation_summary(&rewards))
+}
+
+/// Aggregate statistics of the records returned by `execution_reward_detailed`.
+///
+/// # Returns
+/// The keys of `evaluation_summary`, plus the fractions `timeout_rate`, `syntax_error_rate`,
+/// `extraction_failure_rate` and `not_executed_rate` (tests never ran), and `mean_wall_ms`
+#[pyfunction]
+#[pyo3(name = "evaluation_summary_detailed")]
+pub fn evaluation_summary_detailed_dict<'py>(
+    py: Python<'py>,
+    records: Vec<Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let records = records
+        .iter()
+        .map(evaluation_record_from_dict)
+        .collect::<PyResult<Vec<_>>>()?;
+    let detailed = evaluation_summary_detailed(&records);
+
+    let dict = summary_dict(py, &detailed.summary)?;
+    dict.set_item("timeout_rate", detailed.timeout_rate)?;
+    dict.set_item("syntax_error_rate", detailed.syntax_error_rate)?;
+    dict.set_item("extraction_failure_rate", detailed.extraction_failure_rate)?;
+    dict.set_item("not_executed_rate", detailed.not_executed_rate)?;
+    dict.set_item("mean_wall_ms", detailed.mean_wall_ms)?;
+    Ok(dict)
+}
+
 // ==========================================================================================
 
+/// Helper function to render a config dict as `key=repr(value)` pairs for `__repr__`
+fn repr_fields(config: &Bound<'_, PyDict>) -> PyResult<String> {
+    let fields = config
+        .iter()
+        .map(|(key, value)| Ok(format!("{}={}", key, value.repr()?)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(fields.join(", "))
+}
+
+/// Helper function to build `QualityWeights` from a Python dict, starting from the defaults.
+fn quality_weights_from_map(weights: HashMap<String, f64>) -> PyResult<QualityWeights> {
+    let mut quality_weights = QualityWeights::default();
+    for (name, weight) in weights {
+        quality_weights
+            .set(&name, weight)
+            .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?;
+    }
+    Ok(quality_weights)
+}
+
+/// Helper function shared by the `execution_reward` entry points.
+///
+/// Uses test-based evaluation, or stdin/stdout evaluation when a `stdin=` kwarg is given.
+fn evaluate_execution_rewards(
+    py: Python,
+    evaluator: &RewardEvaluator,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<f64>> {
+    let completions = extract_completions_from_pylist(completions)?;
+
+    if let Some(kwargs) = kwargs
+        && kwargs
+            .get_item("stdin")?
+            .is_some_and(|value| !value.is_none())
+    {
+        let stdin_inputs = extract_string_list_from_kwargs(kwargs, "stdin", completions.len())?;
+        let expected_outputs = extract_expected_outputs(Some(kwargs), completions.len())?;
+        return py.detach(|| {
+            Ok(evaluator.evaluate_stdin_batch(&completions, &stdin_inputs, &expected_outputs))
+        });
+    }
+
+    let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+    // `dry_run=True` and `pre_wrapped=True` for this call only
+    let dry_run = !evaluator.config().dry_run && kwarg_is_truthy(kwargs, "dry_run")?;
+    let pre_wrapped =
+        !evaluator.config().pre_wrapped_tests && kwarg_is_truthy(kwargs, "pre_wrapped")?;
+    let call_evaluator;
+    let evaluator = if dry_run || pre_wrapped {
+        let config = evaluator.config();
+        call_evaluator = RewardEvaluator::new(EvaluatorConfig {
+            dry_run: config.dry_run || dry_run,
+            pre_wrapped_tests: config.pre_wrapped_tests || pre_wrapped,
+            ..config.clone()
+        })
+        .map_err(|e| PyValueError::new_err(format!("Invalid configuration: {}", e)))?;
+        &call_evaluator
+    } else {
+        evaluator
+    };
+
+    if let Some(kwargs) = kwargs
+        && kwargs
+            .get_item("language")?
+            .is_some_and(|value| !value.is_none())
+    {
+        let languages: Vec<Language> =
+            extract_string_list_from_kwargs(kwargs, "language", completions.len())?
+                .iter()
+                .map(|tag| Language::from_tag(tag))
+                .collect();
+        return py.detach(|| {
+            Ok(evaluator.evaluate_execution_batch_mixed(
+                &completions,
+                &tests,
+                &entry_points,
+                &languages,
+            ))
+        });
+    }
+
+    let chunk_size = match kwargs
+        .map(|kwargs| kwargs.get_item("chunk_size"))
+        .transpose()?
+    {
+        Some(Some(value)) if !value.is_none() => Some(value.extract::<usize>()?),
+        _ => None,
+    };
+    match chunk_size {
+        Some(0) => Err(PyValueError::new_err("chunk_size must be at least 1")),
+        Some(chunk_size) => py.detach(|| {
+            Ok(evaluator.evaluate_execution_batch_chunked(
+                &completions,
+                &tests,
+                &entry_points,
+                chunk_size,
+            ))
+        }),
+        None => py
+            .detach(|| Ok(evaluator.evaluate_execution_batch(&completions, &tests, &entry_points))),
+    }
+}
+
+/// Helper function shared by the `execution_reward_detailed` entry points.
+fn evaluate_execution_records<'py>(
+    py: Python<'py>,
+    evaluator: &RewardEvaluator,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+    let records = py.detach(|| {
+        evaluator.evaluate_execution_batch_with_metadata(&completions, &tests, &entry_points)
+    });
+    records
+        .iter()
+        .map(|record| evaluation_record_dict(py, record))
+        .collect()
+}
+
+/// Helper function shared by the `execution_reward_batch_of_lists` entry points.
+fn evaluate_execution_grouped<'py>(
+    py: Python<'py>,
+    evaluator: &RewardEvaluator,
+    completions_grouped: &Bound<'_, PyAny>,
+    tests: &[Option<String>],
+    entry_points: &[Option<String>],
+    detailed: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let completions_grouped =
+        extract_grouped_completions(completions_grouped, tests, entry_points, 0)?;
+
+    if !detailed {
+        let rewards = py.detach(|| {
+            evaluator.evaluate_execution_grouped(&completions_grouped, tests, entry_points)
+        });
+        return rewards.into_pyobject(py);
+    }
+
+    let records = py.detach(|| {
+        evaluator.evaluate_execution_grouped_with_metadata(
+            &completions_grouped,
+            tests,
+            entry_points,
+        )
+    });
+    let mut flat_index = 0;
+    let mut groups = Vec::with_capacity(records.len());
+    for group in &records {
+        let mut dicts = Vec::with_capacity(group.len());
+        for record in group {
+            let dict = evaluation_record_dict(py, record)?;
+            dict.set_item("flat_index", flat_index)?;
+            flat_index += 1;
+            dicts.push(dict);
+        }
+        groups.push(dicts);
+    }
+    groups.into_pyobject(py)
+}
+
+/// Helper function shared by the `code_quality_reward` entry points.
+fn evaluate_code_quality<'py>(
+    py: Python<'py>,
+    evaluator: &RewardEvaluator,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+    let scores =
+        py.detach(|| evaluator.evaluate_code_quality_batch(&completions, &tests, &entry_points));
+    scores
+        .iter()
+        .map(|score| code_quality_dict(py, score))
+        .collect()
+}
+
+/// Helper function to convert a `CodeQualityScore` to a dict keyed by field name.
+fn code_quality_dict<'py>(
+    py: Python<'py>,
+    score: &CodeQualityScore,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("execution", score.execution)?;
+    dict.set_item("has_type_hints", score.has_type_hints)?;
+    dict.set_item("has_docstring", score.has_docstring)?;
+    dict.set_item("has_comments", score.has_comments)?;
+    dict.set_item("code_length_chars", score.code_length_chars)?;
+    dict.set_item("composite", score.composite)?;
+    Ok(dict)
+}
+
+/// Helper function to convert an `EvaluationRecord` to a dict keyed by field name.
+fn evaluation_record_dict<'py>(
+    py: Python<'py>,
+    record: &EvaluationRecord,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("reward", record.reward)?;
+    dict.set_item("code_extracted", &record.code_extracted)?;
+    dict.set_item("timed_out", record.timed_out)?;
+    dict.set_item("entry_point_found", record.entry_point_found)?;
+    dict.set_item("tests_passed", record.tests_passed)?;
+    dict.set_item("tests_total", record.tests_total)?;
+    dict.set_item("wall_ms", record.wall_ms)?;
+    dict.set_item("stderr_snippet", &record.stderr_snippet)?;
+    dict.set_item("exception_type", &record.exception_type)?;
+    Ok(dict)
+}
+
+/// Inverse of [`evaluation_record_dict`].
+fn evaluation_record_from_dict(dict: &Bound<'_, PyDict>) -> PyResult<EvaluationRecord> {
+    fn field<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<T> {
+        dict.get_item(key)?
+            .ok_or_else(|| PyKeyError::new_err(format!("Evaluation record is missing '{}'", key)))?
+            .extract()
+    }
+
+    Ok(EvaluationRecord {
+        reward: field(dict, "reward")?,
+        code_extracted: field(dict, "code_extracted")?,
+        timed_out: field(dict, "timed_out")?,
+        entry_point_found: field(dict, "entry_point_found")?,
+        tests_passed: field(dict, "tests_passed")?,
+        tests_total: field(dict, "tests_total")?,
+        wall_ms: field(dict, "wall_ms")?,
+        stderr_snippet: field(dict, "stderr_snippet")?,
+        exception_type: field(dict, "exception_type")?,
+    })
+}
+
+/// Helper function to convert an [`EvaluationSummary`] to a Python dict
+fn summary_dict<'py>(py: Python<'py>, summary: &EvaluationSummary) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("pass_rate", summary.pass_rate)?;
+    dict.set_item("total", summary.total)?;
+    dict.set_item("passed", summary.passed)?;
+    dict.set_item("failed", summary.failed)?;
+    dict.set_item("mean_reward", summary.mean_reward)?;
+    dict.set_item("std_reward", summary.std_reward)?;
+    Ok(dict)
+}
+
+/// Helper function to reject a `max_complexity` of 0 with a `ValueError`.
+fn check_max_complexity(max_complexity: u32) -> PyResult<()> {
+    if max_complexity == 0 {
+        return Err(PyValueError::new_err("max_complexity must be at least 1"));
+    }
+    Ok(())
+}
+
 /// Helper function to extract completions from various Python input formats:
 ///
 /// - Direct strings: `["code1", "code2"]` (Ray RLlib)
 /// - Dicts with "content": `[{"content": "code1"}]` (TRL)
 /// - Lists of dicts: `[[{"content": "code1"}]]` (some TRL versions)
 /// - Fallback to string conversion
-fn extract_completions_from_pylist(completions: &Bound<'_, PyList>) -> PyResult<Vec<String>> {
+fn extract_completions_from_pylist(completions: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    let completions = sequence_items(completions, "completions")?;
     let mut result = Vec::with_capacity(completions.len());
 
-    for item in completions.iter() {
+    for item in completions {
         let text = if let Ok(s) = item.extract::<String>() {
             // Case 1: Direct string
             s
         } else if let Ok(dict) = item.downcast::<PyDict>() {
-            // Case 2: Dictionary with "content" key
-            dict.get_item("content")?
-                .and_then(|value| value.extract::<String>().ok())
-                .unwrap_or_default()
+            // Case 2: Dictionary with "content" (or another text) key
+            completion_text_from_dict(dict)?
         } else if let Ok(list) = item.downcast::<PyList>() {
             // Case 3: List of dicts (take first element)
             if !list.is_empty() {
                 if let Ok(first) = list.get_item(0) {
                     if let Ok(dict) = first.downcast::<PyDict>() {
                         // First element is a dict - extract "content"
-                        dict.get_item("content")?
-                            .and_then(|value| value.extract::<String>().ok())
-                            .unwrap_or_default()
+                        completion_text_from_dict(dict)?
                     } else {
                         // First element is not a dict - convert to string
                         first.str()?.to_string()
@@ -242,10 +2079,152 @@ fn extract_completions_from_pylist(completions: &Bound<'_, PyList>) -> PyResult<
         result.push(text);
     }
 
+    warn_on_suspicious_completions(&result);
     Ok(result)
 }
 
-/// Helper function to extract string lists from kwargs (for test= and entry_point= arguments)
+/// Keys holding the completion text in a completion dict, in order of preference. TRL uses
+/// `"content"`; the others come from older versions and custom pipelines.
+const COMPLETION_TEXT_KEYS: [&str; 4] = ["content", "message", "text", "response"];
+
+/// Text of a completion dict: the first string value among [`COMPLETION_TEXT_KEYS`], or an
+/// empty string.
+fn completion_text_from_dict(dict: &Bound<'_, PyDict>) -> PyResult<String> {
+    for key in COMPLETION_TEXT_KEYS {
+        if let Some(text) = dict
+            .get_item(key)?
+            .and_then(|value| value.extract::<String>().ok())
+        {
+            if key != "content" {
+                tracing::debug!(key, "Completion text taken from a non-standard key");
+            }
+            return Ok(text);
+        }
+    }
+    Ok(String::new())
+}
+
+/// Warn about batches that look like an input preparation bug: only empty completions
+/// (usually a format the extraction does not understand), or mostly identical ones.
+fn warn_on_suspicious_completions(completions: &[String]) {
+    if completions.is_empty() {
+        return;
+    }
+    if completions.iter().all(|completion| completion.is_empty()) {
+        tracing::warn!(
+            batch_size = completions.len(),
+            "All completions are empty; expected strings, {{\"content\": ...}} dicts or \
+             lists of message dicts"
+        );
+        return;
+    }
+    if completions.len() < DUPLICATE_WARNING_MIN_BATCH {
+        return;
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for completion in completions {
+        *counts.entry(completion).or_default() += 1;
+    }
+    let most_common = counts.values().copied().max().unwrap_or(0);
+    if most_common as f64 > DUPLICATE_WARNING_RATIO * completions.len() as f64 {
+        tracing::warn!(
+            batch_size = completions.len(),
+            identical = most_common,
+            "Most completions in the batch are identical; check the data preparation"
+        );
+    }
+}
+
+/// Per-completion tests and entry points (`None` where missing).
+type TestsAndEntryPoints = (Vec<Option<String>>, Vec<Option<String>>);
+
+/// `(valid_indices, filtered_completions, filtered_tests, filtered_entry_points)`
+type FilteredCompletions = (
+    Vec<usize>,
+    Vec<String>,
+    Vec<Option<String>>,
+    Vec<Option<String>>,
+);
+
+/// Shared implementation of `filter_valid_completions`
+fn filter_completions(
+    py: Python,
+    evaluator: &RewardEvaluator,
+    completions: &Bound<'_, PyAny>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<FilteredCompletions> {
+    let completions = extract_completions_from_pylist(completions)?;
+    let (tests, entry_points) = extract_tests_and_entry_points(kwargs, completions.len())?;
+
+    let valid =
+        py.detach(|| evaluator.filter_valid_completions(&completions, &tests, &entry_points));
+    Ok((
+        valid.indices,
+        valid.completions,
+        valid.tests,
+        valid.entry_points,
+    ))
+}
+
+/// Helper function to extract the `test=` and `entry_point=` kwargs, defaulting to `None`
+/// when kwargs are missing entirely
+fn extract_tests_and_entry_points(
+    kwargs: Option<&Bound<'_, PyDict>>,
+    expected_len: usize,
+) -> PyResult<TestsAndEntryPoints> {
+    match kwargs {
+        Some(kwargs) => Ok((
+            extract_optional_string_list_from_kwargs(kwargs, "test", expected_len)?,
+            extract_optional_string_list_from_kwargs(kwargs, "entry_point", expected_len)?,
+        )),
+        None => Ok((vec![None; expected_len], vec![None; expected_len])),
+    }
+}
+
+/// Helper function to extract the `expected_output=` kwarg, defaulting to empty strings
+/// when kwargs are missing entirely
+fn extract_expected_outputs(
+    kwargs: Option<&Bound<'_, PyDict>>,
+    expected_len: usize,
+) -> PyResult<Vec<String>> {
+    match kwargs {
+        Some(kwargs) => extract_string_list_from_kwargs(kwargs, "expected_output", expected_len),
+        None => Ok(vec![String::new(); expected_len]),
+    }
+}
+
+/// Regex patterns given as a single string (applied to all completions) or a list with one
+/// pattern per completion.
+fn extract_patterns(patterns: &Bound<'_, PyAny>, expected_len: usize) -> PyResult<Vec<String>> {
+    if let Ok(pattern) = patterns.extract::<String>() {
+        return Ok(vec![pattern]);
+    }
+    let patterns: Vec<String> = patterns.extract()?;
+    if patterns.len() != 1 && patterns.len() != expected_len {
+        return Err(PyValueError::new_err(format!(
+            "'patterns' must be a string or have one pattern per completion ({}), got {}",
+            expected_len,
+            patterns.len()
+        )));
+    }
+    Ok(patterns)
+}
+
+/// Truthiness of `kwargs[key]` (false if missing).
+fn kwarg_is_truthy(kwargs: Option<&Bound<'_, PyDict>>, key: &str) -> PyResult<bool> {
+    match kwargs {
+        Some(kwargs) => Ok(kwargs
+            .get_item(key)?
+            .map(|value| value.is_truthy())
+            .transpose()?
+            .unwrap_or(false)),
+        None => Ok(false),
+    }
+}
+
+/// Helper function to extract string lists from kwargs (e.g. for language= and stdin=
+/// arguments); non-string items and a missing key give empty strings.
 ///
 /// # Errors
 /// Returns an error if the provided list length does not match the expected length
@@ -254,27 +2233,164 @@ fn extract_string_list_from_kwargs(
     key: &str,
     expected_len: usize,
 ) -> PyResult<Vec<String>> {
-    if let Some(value) = kwargs.get_item(key)? {
-        if let Ok(list) = value.downcast::<PyList>() {
-            let mut result = Vec::with_capacity(list.len());
-            for item in list.iter() {
-                result.push(item.extract::<String>().unwrap_or_default());
-            }
+    extract_list_from_kwargs(kwargs, key, expected_len, |item| {
+        item.extract::<String>().unwrap_or_default()
+    })
+}
 
-            // Validate length
-            if result.len() != expected_len {
-                return Err(PyValueError::new_err(format!(
-                    "Length mismatch: {} has {} items but expected {} (same as completions)",
-                    key,
-                    result.len(),
-                    expected_len
-                )));
-            }
+/// Parse the `debug` log level (`"error"`, `"warn"`, `"info"`, `"debug"` or `"trace"`).
+fn parse_log_level(level: Option<&str>) -> Result<Option<tracing::Level>, String> {
+    level
+        .map(|level| {
+            level.parse().map_err(|_| {
+                format!(
+                    "unknown debug log level '{}' (expected 'error', 'warn', 'info', 'debug' or 'trace')",
+                    level
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Helper function to extract lists of optional strings from kwargs (for test= and
+/// entry_point= arguments): `None` items, non-string items, empty or whitespace-only
+/// strings and a missing key give `None`.
+///
+/// # Errors
+/// Returns an error if the provided list length does not match the expected length
+fn extract_optional_string_list_from_kwargs(
+    kwargs: &Bound<'_, PyDict>,
+    key: &str,
+    expected_len: usize,
+) -> PyResult<Vec<Option<String>>> {
+    extract_list_from_kwargs(kwargs, key, expected_len, |item| {
+        item.extract::<Option<String>>()
+            .ok()
+            .flatten()
+            .filter(|value| !value.trim().is_empty())
+    })
+}
+
+/// Shared implementation of the kwargs list helpers, converting each item with `convert`.
+///
+/// Besides Python lists, accepts numpy arrays and pandas `Series` (e.g., Hugging Face
+/// `Dataset` columns in numpy format), which are converted via their `tolist()` method.
+fn extract_list_from_kwargs<T: Clone + Default>(
+    kwargs: &Bound<'_, PyDict>,
+    key: &str,
+    expected_len: usize,
+    convert: impl Fn(&Bound<'_, PyAny>) -> T,
+) -> PyResult<Vec<T>> {
+    let value = match kwargs.get_item(key)? {
+        Some(value) if value.downcast::<PyList>().is_err() && value.hasattr("tolist")? => {
+            Some(value.call_method0("tolist")?)
+        }
+        value => value,
+    };
+
+    if let Some(value) = value
+        && let Ok(list) = value.downcast::<PyList>()
+    {
+        let mut result = Vec::with_capacity(list.len());
+        for item in list.iter() {
+            result.push(convert(&item));
+        }
+
+        // Validate length
+        if result.len() != expected_len {
+            let hint = if result.len() > expected_len {
+                format!(
+                    ". Did you accidentally pass the entire {} dataset instead of the \
+                     per-completion {}s?",
+                    key, key
+                )
+            } else {
+                String::new()
+            };
+            return Err(PyValueError::new_err(format!(
+                "Length mismatch: '{}' has {} items but there are {} completions (one {} \
+                 per completion is expected){}",
+                key,
+                result.len(),
+                expected_len,
+                key,
+                hint
+            )));
+        }
+
+        return Ok(result);
+    }
+
+    // Key not found - return default values (allow missing kwargs entirely)
+    Ok(vec![T::default(); expected_len])
+}
+
+/// Helper function to collect the items of a Python list or tuple.
+///
+/// # Errors
+/// Returns a `TypeError` naming `arg_name` for any other type
+fn sequence_items<'py>(
+    sequence: &Bound<'py, PyAny>,
+    arg_name: &str,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    if let Ok(list) = sequence.downcast::<PyList>() {
+        Ok(list.iter().collect())
+    } else if let Ok(tuple) = sequence.downcast::<PyTuple>() {
+        Ok(tuple.iter().collect())
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "{} must be a list or tuple, got {}",
+            arg_name,
+            sequence.get_type().name()?
+        )))
+    }
+}
 
-            return Ok(result);
+/// Helper function to extract per-problem groups of completions (`List[List[completion]]`).
+///
+/// # Errors
+/// Returns an error if the number of groups does not match `tests`/`entry_points`, or if
+/// any group has fewer than `min_samples` completions
+fn extract_grouped_completions(
+    completions_per_problem: &Bound<'_, PyAny>,
+    tests: &[Option<String>],
+    entry_points: &[Option<String>],
+    min_samples: usize,
+) -> PyResult<Vec<Vec<String>>> {
+    let completions_per_problem =
+        sequence_items(completions_per_problem, "completions_per_problem")?;
+    if completions_per_problem.len() != tests.len()
+        || completions_per_problem.len() != entry_points.len()
+    {
+        return Err(PyValueError::new_err(format!(
+            "Length mismatch: {} problems but test has {} items and entry_point has {} items",
+            completions_per_problem.len(),
+            tests.len(),
+            entry_points.len()
+        )));
+    }
+
+    let mut result = Vec::with_capacity(completions_per_problem.len());
+    for (idx, group) in completions_per_problem.iter().enumerate() {
+        if group.downcast::<PyList>().is_err() && group.downcast::<PyTuple>().is_err() {
+            return Err(PyValueError::new_err(format!(
+                "completions_per_problem[{}] must be a list of completions",
+                idx
+            )));
         }
+        let completions = extract_completions_from_pylist(group)?;
+
+        if completions.len() < min_samples {
+            return Err(PyValueError::new_err(format!(
+                "Problem {} has {} samples but at least {} are required",
+                idx,
+                completions.len(),
+                min_samples
+            )));
+        }
+
+        result.push(completions);
     }
 
-    // Key not found - return empty strings (allow missing kwargs entirely)
-    Ok(vec![String::new(); expected_len])
+    Ok(result)
 }
@@ -2,17 +2,49 @@
 //!
 //! Core reward evaluation logic.
 
-use crate::extraction::extract_code_from_completion;
-use crate::sandbox::run_sandboxed_tests;
-use crate::test_wrapper::wrap_tests_for_complete_execution;
-use anyhow::{Result, ensure};
+use crate::extraction::{
+    Language, count_code_lines, cyclomatic_complexity, extract_code_from_completion,
+    extract_code_with_language, has_comments, has_docstring, has_type_hints, is_stub_code,
+};
+use crate::linting::{LintingTool, count_lint_errors};
+use crate::metrics::pass_at_k_estimate;
+use crate::sandbox::{
+    DEFAULT_MAX_OPEN_FILES, SandboxBackend, SandboxFallbackPolicy, SandboxOptions, SandboxResult,
+    SeccompProfile, execute_rust_sandboxed, execute_sandboxed, is_syscall_name, temp_root,
+};
+use crate::test_wrapper::{
+    DEFAULT_CHECK_NAMES, estimate_test_complexity, is_multi_method_test, is_unwrapped_check_test,
+    wrap_rust_tests_for_complete_execution, wrap_tests_with_check_names,
+};
+use crate::type_check::run_mypy_strict;
+use anyhow::{Context, Result, ensure};
 use once_cell::sync::Lazy;
 use rayon::ThreadPoolBuilder;
 use rayon::prelude::*;
 use regex::Regex;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::Level;
 
 // ==========================================================================================
 
+/// Smallest accepted [`EvaluatorConfig::memory_limit_mb`]. The limit covers the whole
+/// process, and the Python interpreter alone takes about 30-40MB.
+pub const MIN_MEMORY_MB: u64 = 32;
+
+/// Number of user patterns kept compiled by [`RewardEvaluator::evaluate_format_regex_batch`].
+const REGEX_CACHE_CAPACITY: usize = 32;
+
+/// Recently compiled user patterns, most recently used first.
+static REGEX_CACHE: Lazy<Mutex<VecDeque<(String, Regex)>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(REGEX_CACHE_CAPACITY)));
+
+/// `fn main` of a Rust solution, which is renamed so it does not clash with the test `main`.
+static RUST_MAIN_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^(\s*(?:pub\s+)?fn\s+)main(\s*\()").unwrap());
+
 /// Configuration for `RewardEvaluator`.
 #[derive(Clone, Debug)]
 pub struct EvaluatorConfig {
@@ -37,7 +69,135 @@ pub struct EvaluatorConfig {
     ///
     /// - `Some(n)`: Use exactly `n` threads
     /// - `None`: Use default (number of CPU cores)
+    ///
+    /// The thread pool is shared by the whole process and sized by the first evaluator that
+    /// sets this; later evaluators asking for a different size log a warning.
     pub num_threads: Option<usize>,
+
+    /// Behavior when Firejail is not installed.
+    ///
+    /// Defaults to `Error`, which fails every execution. `Warn` and `Silent` fall back
+    /// to running code with bare `python3` (no sandboxing, timeout still enforced).
+    pub fallback_policy: SandboxFallbackPolicy,
+
+    /// Sandbox used to run the code.
+    ///
+    /// `Auto` (the default) is resolved when the evaluator is created: Firejail if installed,
+    /// otherwise bubblewrap. If neither is installed, Firejail is kept and `fallback_policy`
+    /// decides what happens.
+    pub sandbox_backend: SandboxBackend,
+
+    /// Whether output-matching rewards ignore trailing whitespace on each line,
+    /// leading/trailing blank lines and repeated blank lines when comparing stdout to the
+    /// expected output.
+    pub output_normalize_whitespace: bool,
+
+    /// Maximum completion length (`str::len`, i.e. UTF-8 bytes; equal to characters for ASCII).
+    ///
+    /// Longer completions (e.g., a model stuck in a repetition loop) get 0.0 reward without
+    /// code extraction or execution. `None` means no limit.
+    pub max_completion_chars: Option<usize>,
+
+    /// Minimum number of code lines (non-blank, non-comment) in the extracted code.
+    ///
+    /// Shorter code (e.g., `<answer>pass</answer>` with a threshold of 2) gets 0.0 reward
+    /// without being executed.
+    pub min_code_lines: usize,
+
+    /// Text written to the stdin of every executed script (`None` leaves stdin untouched).
+    ///
+    /// Per-completion input can be given with [`RewardEvaluator::evaluate_stdin_batch`].
+    pub stdin_input: Option<String>,
+
+    /// Accept `timeout_seconds < cpu_time_limit` (with a warning) instead of rejecting it.
+    ///
+    /// Such a configuration makes `cpu_time_limit` unreachable, since the wall-clock
+    /// timeout always fires first, so it is usually a mistake.
+    pub allow_lower_wall_timeout: bool,
+
+    /// Maximum wall-clock time in seconds for one mypy run in
+    /// [`RewardEvaluator::evaluate_type_check_batch`]; slower runs count as failed.
+    pub type_check_timeout: u64,
+
+    /// Linter used by [`RewardEvaluator::evaluate_linting_batch`].
+    pub linting_tool: LintingTool,
+
+    /// Maximum wall-clock time in seconds for one linter run; slower runs score 0.0.
+    pub linting_timeout: u64,
+
+    /// Accepted names of the test function in `test` code, in order of preference
+    /// (default: `check`, `test`, `validate`, `verify`).
+    pub check_function_names: Vec<String>,
+
+    /// Write scripts to a RAM-backed tmpfs (`/dev/shm` or `/run/shm`) instead of `/tmp`
+    /// when one is mounted, avoiding disk I/O for the many small files written in parallel.
+    ///
+    /// Only applies where it does not weaken isolation (see
+    /// [`SandboxOptions::prefer_ram_tmpdir`]): with Firejail, scripts stay in `/tmp`.
+    pub prefer_ram_tmpdir: bool,
+
+    /// Replace NaN with 0.0 and clamp rewards to `[0.0, 1.0]` at the end of
+    /// [`RewardEvaluator::evaluate_execution_batch`], so downstream training code never
+    /// sees non-finite values.
+    pub clamp_rewards: bool,
+
+    /// Additional Firejail arguments (inserted before `python3`) for options not covered
+    /// by the typed configuration. Ignored by other sandbox backends.
+    pub extra_firejail_args: Vec<String>,
+
+    /// Score 0.0 without running the tests when the code only defines placeholder
+    /// functions (bodies of `pass`, `...` or `return None`; see [`is_stub_code`]).
+    pub reject_stubs: bool,
+
+    /// Pass the class itself (e.g. `LRUCache` for entry point `LRUCache().get`) to the
+    /// check function when it calls several methods on an instance created from its
+    /// parameter (see [`is_multi_method_test`]). No instance is generated: the tests must
+    /// construct it themselves (`cache = candidate(2)`) and call its methods. Other tests
+    /// keep the single-method entry point.
+    pub multi_method_mode: bool,
+
+    /// Maximum number of open file descriptors in the sandbox (`--rlimit-nofile`).
+    pub max_open_files: u32,
+
+    /// Seccomp filter applied by Firejail (`--seccomp` or `--seccomp.drop=...`).
+    pub seccomp_profile: SeccompProfile,
+
+    /// Give execution reward 0.0 without running the sandbox to completions that fail the
+    /// `<think>`/`<answer>` format check, as [`RewardEvaluator::evaluate_combined`] always
+    /// does. Such completions almost never pass the tests, so this saves a process spawn each.
+    pub gate_execution_on_format: bool,
+
+    /// Classes that hold the solution methods in LeetCode-style problems (`Solution`;
+    /// other datasets use e.g. `Main` or `Program`), in order of preference.
+    ///
+    /// An entry point naming a bare method (`"twoSum"`) that the code defines only inside
+    /// one of these classes is called on an instance (`"Solution().twoSum"`), and a
+    /// constructor entry point for one of them (`"Solution().twoSum"`) uses whichever of
+    /// them the code defines. Other explicit entry points such as `"LRUCache()"` name their
+    /// own class.
+    pub solution_class_names: Vec<String>,
+
+    /// Check code extraction and the entry point without running the sandbox: completions
+    /// that pass get 1.0. A cheap pre-filter for dataset preparation, after which only the
+    /// passing completions need real execution.
+    pub dry_run: bool,
+
+    /// Level at which the stderr of every sandboxed execution is logged, or `None` to not
+    /// log it (tracebacks are otherwise only summarized by the exception type and the
+    /// record's stderr snippet). Events still go through the installed subscriber's filter.
+    pub debug: Option<Level>,
+
+    /// Weights of the signals combined into [`CodeQualityScore::composite`].
+    pub quality_weights: QualityWeights,
+
+    /// Scale `timeout_seconds` and `cpu_time_limit` per test by the estimated test
+    /// complexity (x1 low, x1.5 medium, x2 high; see [`estimate_test_complexity`]), so
+    /// tests with large inputs or recursion get more time without raising every limit.
+    pub adaptive_timeout: bool,
+
+    /// The tests already print `TESTS_PASSED:X/Y` themselves, so they are appended to the
+    /// code as-is instead of being wrapped (which would count their assertions twice).
+    pub pre_wrapped_tests: bool,
 }
 
 impl Default for EvaluatorConfig {
@@ -47,6 +207,31 @@ impl Default for EvaluatorConfig {
             memory_limit_mb: 512,
             cpu_time_limit: 12,
             num_threads: Some(32),
+            fallback_policy: SandboxFallbackPolicy::Error,
+            sandbox_backend: SandboxBackend::Auto,
+            output_normalize_whitespace: true,
+            max_completion_chars: None,
+            min_code_lines: 1,
+            stdin_input: None,
+            allow_lower_wall_timeout: false,
+            type_check_timeout: 30,
+            linting_tool: LintingTool::Ruff,
+            linting_timeout: 30,
+            check_function_names: DEFAULT_CHECK_NAMES.map(String::from).to_vec(),
+            prefer_ram_tmpdir: true,
+            clamp_rewards: true,
+            extra_firejail_args: Vec::new(),
+            reject_stubs: true,
+            multi_method_mode: false,
+            max_open_files: DEFAULT_MAX_OPEN_FILES,
+            seccomp_profile: SeccompProfile::Strict,
+            gate_execution_on_format: false,
+            solution_class_names: vec!["Solution".to_string()],
+            dry_run: false,
+            debug: None,
+            quality_weights: QualityWeights::default(),
+            adaptive_timeout: false,
+            pre_wrapped_tests: false,
         }
     }
 }
@@ -59,8 +244,10 @@ impl EvaluatorConfig {
             self.timeout_seconds
         );
         ensure!(
-            self.memory_limit_mb >= 64,
-            "memory_limit_mb must be at least 64MB for Python execution, got {}MB",
+            self.memory_limit_mb >= MIN_MEMORY_MB,
+            "memory_limit_mb must be at least {}MB (the limit includes the ~30-40MB \
+             of the Python interpreter itself), got {}MB",
+            MIN_MEMORY_MB,
             self.memory_limit_mb
         );
         ensure!(
@@ -69,17 +256,277 @@ impl EvaluatorConfig {
             self.cpu_time_limit
         );
 
-        // Warn if timeout is lower than CPU limit (unusual but not invalid)
+        ensure!(
+            self.type_check_timeout > 0,
+            "type_check_timeout must be at least 1, got {}",
+            self.type_check_timeout
+        );
+        ensure!(
+            self.linting_timeout > 0,
+            "linting_timeout must be at least 1, got {}",
+            self.linting_timeout
+        );
+        ensure!(
+            !self.solution_class_names.is_empty()
+                && self
+                    .solution_class_names
+                    .iter()
+                    .all(|name| !name.trim().is_empty()),
+            "solution_class_names must contain at least one name, and no empty names"
+        );
+        ensure!(
+            !self.check_function_names.is_empty(),
+            "check_function_names must contain at least one name"
+        );
+        ensure!(
+            self.quality_weights
+                .pairs()
+                .iter()
+                .all(|(_, weight)| weight.is_finite() && *weight >= 0.0)
+                && self.quality_weights.total() > 0.0,
+            "quality_weights must be non-negative and not all zero, got {:?}",
+            self.quality_weights
+        );
+        if let SeccompProfile::Custom(syscalls) = &self.seccomp_profile {
+            ensure!(
+                !syscalls.is_empty() && syscalls.iter().all(|name| is_syscall_name(name)),
+                "seccomp_profile must list syscall names (letters, digits and '_'), got {:?}",
+                syscalls
+            );
+        }
+
+        // A timeout lower than the CPU limit makes the CPU limit unreachable
+        ensure!(
+            self.allow_lower_wall_timeout || self.timeout_seconds >= self.cpu_time_limit,
+            "timeout_seconds ({}) must be >= cpu_time_limit ({}) to avoid unreachable CPU limit \
+             (set allow_lower_wall_timeout to override)",
+            self.timeout_seconds,
+            self.cpu_time_limit
+        );
         if self.timeout_seconds < self.cpu_time_limit {
-            eprintln!(
-                "Warning: timeout_seconds ({}) is lower than cpu_time_limit ({}). \
-                 Wall-clock timeout will likely be hit first.",
-                self.timeout_seconds, self.cpu_time_limit
+            tracing::warn!(
+                timeout_seconds = self.timeout_seconds,
+                cpu_time_limit = self.cpu_time_limit,
+                "timeout_seconds is lower than cpu_time_limit; \
+                 wall-clock timeout will likely be hit first"
             );
         }
 
         Ok(())
     }
+
+    /// Sandbox options derived from this configuration.
+    pub fn sandbox_options(&self) -> SandboxOptions {
+        SandboxOptions {
+            timeout: self.timeout_seconds,
+            memory_limit_mb: self.memory_limit_mb,
+            cpu_time_limit: self.cpu_time_limit,
+            fallback_policy: self.fallback_policy,
+            backend: self.sandbox_backend,
+            stdin_input: self.stdin_input.clone(),
+            prefer_ram_tmpdir: self.prefer_ram_tmpdir,
+            extra_firejail_args: self.extra_firejail_args.clone(),
+            max_open_files: self.max_open_files,
+            seccomp_profile: self.seccomp_profile.clone(),
+        }
+    }
+}
+
+// ==========================================================================================
+
+/// Format and execution scores of a single completion, computed in one pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CombinedReward {
+    /// 1.0 if the completion has valid `<think>`/`<answer>` format, 0.0 otherwise.
+    pub format_score: f64,
+
+    /// 1.0 if all tests passed, 0.0 otherwise (including when the format check failed).
+    pub execution_score: f64,
+}
+
+impl CombinedReward {
+    /// Weighted sum of the two scores.
+    pub fn weighted(&self, format_weight: f64, exec_weight: f64) -> f64 {
+        format_weight * self.format_score + exec_weight * self.execution_score
+    }
+}
+
+/// Weights of the signals in [`CodeQualityScore::composite`]. Only their ratios matter: the
+/// composite is divided by their sum.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QualityWeights {
+    pub execution: f64,
+    pub type_hints: f64,
+    pub docstring: f64,
+    pub comments: f64,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self {
+            execution: 0.7,
+            type_hints: 0.1,
+            docstring: 0.1,
+            comments: 0.1,
+        }
+    }
+}
+
+impl QualityWeights {
+    /// Signal names and weights, in declaration order.
+    pub fn pairs(&self) -> [(&'static str, f64); 4] {
+        [
+            ("execution", self.execution),
+            ("type_hints", self.type_hints),
+            ("docstring", self.docstring),
+            ("comments", self.comments),
+        ]
+    }
+
+    /// Set the weight of the signal called `name`.
+    ///
+    /// # Errors
+    /// Returns the unknown name if it is not one of [`pairs`](Self::pairs).
+    pub fn set(&mut self, name: &str, weight: f64) -> std::result::Result<(), String> {
+        match name {
+            "execution" => self.execution = weight,
+            "type_hints" => self.type_hints = weight,
+            "docstring" => self.docstring = weight,
+            "comments" => self.comments = weight,
+            _ => {
+                return Err(format!(
+                    "Unknown quality weight '{}'. Expected: execution, type_hints, docstring, comments",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn total(&self) -> f64 {
+        self.pairs().iter().map(|(_, weight)| weight).sum()
+    }
+}
+
+/// Execution score and static code-quality signals of a single completion.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CodeQualityScore {
+    /// Execution reward, as returned by [`RewardEvaluator::evaluate_execution_batch`].
+    pub execution: f64,
+
+    /// A function has a return or parameter annotation (see [`has_type_hints`]).
+    pub has_type_hints: bool,
+
+    /// The module, a function or a class has a docstring (see [`has_docstring`]).
+    pub has_docstring: bool,
+
+    /// The code has a `#` comment (see [`has_comments`]).
+    pub has_comments: bool,
+
+    /// Length of the extracted code in characters (informational, not weighted).
+    pub code_length_chars: usize,
+
+    /// Weighted mean of the signals, using [`EvaluatorConfig::quality_weights`].
+    pub composite: f64,
+}
+
+/// Kind of problem found by [`RewardEvaluator::validate_rewards`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationIssueKind {
+    /// The reward is NaN.
+    NaN,
+    /// The reward is positive or negative infinity.
+    Infinite,
+    /// The reward is finite but outside `[0.0, 1.0]`.
+    OutOfRange,
+}
+
+/// A reward that is not a finite value in `[0.0, 1.0]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValidationIssue {
+    /// Index of the reward in the batch.
+    pub index: usize,
+    /// The offending value.
+    pub value: f64,
+    /// What is wrong with it.
+    pub kind: ValidationIssueKind,
+}
+
+/// Outcome of one execution evaluation with the details behind its reward
+/// (see [`RewardEvaluator::evaluate_execution_batch_with_metadata`]).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvaluationRecord {
+    /// The reward, as returned by [`RewardEvaluator::evaluate_execution_batch`].
+    pub reward: f64,
+    /// Code extracted from the completion (empty if evaluation stopped before extraction).
+    pub code_extracted: String,
+    /// True if the process was killed after exceeding the wall-clock timeout.
+    pub timed_out: bool,
+    /// True if the entry point (and its `Solution` class, if any) is defined in the code.
+    pub entry_point_found: bool,
+    /// Number of tests that passed.
+    pub tests_passed: i32,
+    /// Total number of tests run.
+    pub tests_total: i32,
+    /// Wall-clock time of the whole evaluation in milliseconds.
+    pub wall_ms: u64,
+    /// End of the captured stderr (at most [`STDERR_SNIPPET_CHARS`] characters), if any.
+    pub stderr_snippet: Option<String>,
+    /// Class name of the uncaught exception that ended the script (e.g. `"SyntaxError"`).
+    pub exception_type: Option<String>,
+}
+
+/// Maximum length of [`EvaluationRecord::stderr_snippet`] in characters.
+pub const STDERR_SNIPPET_CHARS: usize = 500;
+
+/// Aggregate statistics of a batch of rewards (see [`evaluation_summary`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EvaluationSummary {
+    /// Fraction of completions that passed (`passed / total`).
+    pub pass_rate: f64,
+    /// Number of rewards.
+    pub total: usize,
+    /// Number of rewards of 1.0 (all tests passed).
+    pub passed: usize,
+    /// Number of rewards below 1.0.
+    pub failed: usize,
+    /// Mean reward.
+    pub mean_reward: f64,
+    /// Population standard deviation of the rewards.
+    pub std_reward: f64,
+}
+
+/// [`EvaluationSummary`] with a breakdown of failures, from [`EvaluationRecord`]s
+/// (see [`evaluation_summary_detailed`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DetailedSummary {
+    /// Statistics of the rewards.
+    pub summary: EvaluationSummary,
+    /// Fraction of completions killed by the wall-clock timeout.
+    pub timeout_rate: f64,
+    /// Fraction of completions whose script ended with a `SyntaxError` (or its subclasses
+    /// `IndentationError` and `TabError`).
+    pub syntax_error_rate: f64,
+    /// Fraction of completions from which no code was extracted.
+    pub extraction_failure_rate: f64,
+    /// Fraction of completions whose tests never ran (no code, missing entry point, stub, ...).
+    pub not_executed_rate: f64,
+    /// Mean wall-clock time per evaluation in milliseconds.
+    pub mean_wall_ms: f64,
+}
+
+/// The completions of a batch that pass the checks done before execution, with their tests
+/// and entry points (see [`RewardEvaluator::filter_valid_completions`]).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidCompletions {
+    /// Batch index of each valid completion, in increasing order.
+    pub indices: Vec<usize>,
+    /// The valid completions.
+    pub completions: Vec<String>,
+    /// Their tests.
+    pub tests: Vec<Option<String>>,
+    /// Their entry points.
+    pub entry_points: Vec<Option<String>>,
 }
 
 // ==========================================================================================
@@ -99,31 +546,62 @@ impl EvaluatorConfig {
 /// ```
 pub struct RewardEvaluator {
     config: EvaluatorConfig,
+    /// Backend as requested, before `Auto` was resolved.
+    requested_sandbox_backend: SandboxBackend,
 }
 
 impl RewardEvaluator {
-    pub fn new(config: EvaluatorConfig) -> Result<Self> {
+    pub fn new(mut config: EvaluatorConfig) -> Result<Self> {
         config.validate()?;
+        let requested_sandbox_backend = config.sandbox_backend;
+        config.sandbox_backend = config.sandbox_backend.resolve();
 
-        if let Some(num_threads) = config.num_threads {
-            ThreadPoolBuilder::new()
+        // The global pool can only be built once per process. Checking its size before
+        // building would initialize it with the default size, so check after a failed build.
+        if let Some(num_threads) = config.num_threads
+            && ThreadPoolBuilder::new()
                 .num_threads(num_threads)
                 .build_global()
-                .ok();
+                .is_err()
+            && rayon::current_num_threads() != num_threads
+        {
+            tracing::warn!(
+                "Rayon global pool already initialized with {} threads; ignoring request for {} threads",
+                rayon::current_num_threads(),
+                num_threads
+            );
         }
 
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            requested_sandbox_backend,
+        })
+    }
+
+    /// Active configuration.
+    pub fn config(&self) -> &EvaluatorConfig {
+        &self.config
+    }
+
+    /// Configuration as passed to [`RewardEvaluator::new`], with the sandbox backend as
+    /// requested (e.g. `Auto`) rather than resolved, so that an evaluator created from it
+    /// on another machine detects its own sandbox.
+    pub fn requested_config(&self) -> EvaluatorConfig {
+        EvaluatorConfig {
+            sandbox_backend: self.requested_sandbox_backend,
+            ..self.config.clone()
+        }
     }
 
     /// Check if text has valid `<think>...</think>` and `<answer>...</answer>` format.
     ///
     /// This validates that the model followed the structured reasoning format
     /// required for code generation tasks.
-    fn has_valid_format(text: &str) -> bool {
+    pub(crate) fn has_valid_format(text: &str) -> bool {
         static THINK_PATTERN: Lazy<Regex> =
             Lazy::new(|| Regex::new(r"(?is)<think>.*?</think>").unwrap());
         static ANSWER_PATTERN: Lazy<Regex> =
-            Lazy::new(|| Regex::new(r"(?is)<answer>.*?</answer>").unwrap());
+            Lazy::new(|| Regex::new(r"(?is)<answer(?:\s[^>]*)?>.*?</answer>").unwrap());
 
         THINK_PATTERN.is_match(text) && ANSWER_PATTERN.is_match(text)
     }
@@ -132,7 +610,7 @@ impl RewardEvaluator {
     ///
     /// Returns 1.0 for properly formatted outputs (with both `<think>` and `<answer>` tags),
     /// 0.0 otherwise.
-    pub fn evaluate_response_format(&self, completions: &[String]) -> Vec<f64> {
+    pub fn evaluate_format_batch(&self, completions: &[String]) -> Vec<f64> {
         completions
             .iter()
             .map(|completion| {
@@ -145,17 +623,285 @@ impl RewardEvaluator {
             .collect()
     }
 
+    /// Former name of [`evaluate_format_batch`](Self::evaluate_format_batch).
+    #[deprecated(note = "renamed to `evaluate_format_batch`")]
+    pub fn evaluate_response_format(&self, completions: &[String]) -> Vec<f64> {
+        self.evaluate_format_batch(completions)
+    }
+
+    /// Evaluate a batch of LLM outputs against arbitrary regex patterns.
+    ///
+    /// `patterns` holds one pattern per completion, or a single pattern applied to all of
+    /// them. Returns 1.0 for completions matching their pattern, 0.0 otherwise. Patterns are
+    /// compiled on first use and kept in a small LRU cache, so repeated patterns compile once.
+    ///
+    /// Each completion is checked against exactly one pattern; there is no list of patterns
+    /// that must all match. Several requirements go in one pattern (e.g.
+    /// `(?s)<think>.*</think>.*<answer>.*</answer>`) or in separate calls whose rewards are
+    /// multiplied.
+    ///
+    /// # Errors
+    /// Returns an error if `patterns` has neither one element nor one per completion, or if
+    /// a pattern is not a valid regex.
+    pub fn evaluate_format_regex_batch(
+        &self,
+        completions: &[String],
+        patterns: &[String],
+    ) -> Result<Vec<f64>> {
+        ensure!(
+            patterns.len() == 1 || patterns.len() == completions.len(),
+            "Patterns must hold one pattern or one per completion, got {} patterns for {} completions",
+            patterns.len(),
+            completions.len()
+        );
+
+        completions
+            .iter()
+            .enumerate()
+            .map(|(idx, completion)| {
+                let pattern = &patterns[if patterns.len() == 1 { 0 } else { idx }];
+                let regex = cached_regex(pattern)?;
+                Ok(if regex.is_match(completion) { 1.0 } else { 0.0 })
+            })
+            .collect()
+    }
+
+    /// Score the simplicity of the extracted code for a batch of LLM outputs.
+    ///
+    /// Returns `1.0 - min(complexity / max_complexity, 1.0)`, where `complexity` is the
+    /// approximate cyclomatic complexity (see [`cyclomatic_complexity`]). Completions without
+    /// code get 0.0.
+    ///
+    /// # Panics
+    /// Panics if `max_complexity` is 0.
+    pub fn evaluate_complexity_batch(
+        &self,
+        completions: &[String],
+        max_complexity: u32,
+    ) -> Vec<f64> {
+        assert!(max_complexity > 0, "max_complexity must be at least 1");
+
+        completions
+            .iter()
+            .enumerate()
+            .map(|(idx, completion)| {
+                if self.exceeds_max_completion_chars(idx, completion) {
+                    return 0.0;
+                }
+
+                let code = extract_code_from_completion(completion);
+                if code.trim().is_empty() {
+                    return 0.0;
+                }
+
+                let ratio = cyclomatic_complexity(&code) as f64 / max_complexity as f64;
+                1.0 - ratio.min(1.0)
+            })
+            .collect()
+    }
+
+    /// Type check the extracted code of a batch of LLM outputs with `mypy --strict`, in parallel.
+    ///
+    /// mypy runs outside the sandbox (it only parses the code) and is limited to
+    /// [`EvaluatorConfig::type_check_timeout`] seconds. The code is written to the same temp
+    /// directory as the sandboxed scripts (see [`EvaluatorConfig::prefer_ram_tmpdir`]).
+    ///
+    /// # Returns
+    /// Vector of rewards (1.0 = no type errors, 0.0 = type errors, timeout or mypy failure)
+    pub fn evaluate_type_check_batch(&self, completions: &[String]) -> Vec<f64> {
+        let timeout = Duration::from_secs(self.config.type_check_timeout);
+        let dir = temp_root(&self.config.sandbox_options());
+
+        completions
+            .par_iter()
+            .enumerate()
+            .map(|(idx, completion)| {
+                if self.exceeds_max_completion_chars(idx, completion) {
+                    return 0.0;
+                }
+
+                let code = extract_code_from_completion(completion);
+                if code.trim().is_empty() || self.below_min_code_lines(&code) {
+                    return 0.0;
+                }
+
+                match run_mypy_strict(&code, timeout, dir) {
+                    Ok(true) => 1.0,
+                    Ok(false) => 0.0,
+                    Err(e) => {
+                        tracing::error!(completion_idx = idx, error = %e, "Type check error");
+                        0.0
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Lint the extracted code of a batch of LLM outputs, in parallel.
+    ///
+    /// Runs [`EvaluatorConfig::linting_tool`] with the pycodestyle (`E`) and pyflakes (`F`)
+    /// rules outside the sandbox, limited to [`EvaluatorConfig::linting_timeout`] seconds.
+    ///
+    /// # Returns
+    /// Vector of rewards `1 / (1 + error_count)` (1.0 = clean; 0.0 = no code, timeout or
+    /// linter failure)
+    pub fn evaluate_linting_batch(&self, completions: &[String]) -> Vec<f64> {
+        let timeout = Duration::from_secs(self.config.linting_timeout);
+
+        completions
+            .par_iter()
+            .enumerate()
+            .map(|(idx, completion)| {
+                if self.exceeds_max_completion_chars(idx, completion) {
+                    return 0.0;
+                }
+
+                let code = extract_code_from_completion(completion);
+                if code.trim().is_empty() || self.below_min_code_lines(&code) {
+                    return 0.0;
+                }
+
+                match count_lint_errors(&code, self.config.linting_tool, timeout) {
+                    Ok(Some(error_count)) => 1.0 / (1.0 + error_count as f64),
+                    Ok(None) => 0.0,
+                    Err(e) => {
+                        tracing::error!(completion_idx = idx, error = %e, "Linting error");
+                        0.0
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Check the completion against `max_completion_chars`, logging a warning if it is too long.
+    fn exceeds_max_completion_chars(&self, completion_idx: usize, completion: &str) -> bool {
+        match self.config.max_completion_chars {
+            Some(max_chars) if completion.len() > max_chars => {
+                tracing::warn!(
+                    completion_idx,
+                    completion_chars = completion.len(),
+                    max_completion_chars = max_chars,
+                    "Skipping evaluation of overlong completion"
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check the extracted code against `min_code_lines`.
+    fn below_min_code_lines(&self, code: &str) -> bool {
+        count_code_lines(code) < self.config.min_code_lines
+    }
+
+    /// Execution reward of a single completion, for callers that evaluate completions one
+    /// at a time (e.g. Rust-native training loops). Runs on the calling thread and gives
+    /// the same reward as [`evaluate_execution_batch`](Self::evaluate_execution_batch).
+    pub fn evaluate_execution_single(
+        &self,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+    ) -> f64 {
+        self.evaluate_execution_single_detailed(completion, test, entry_point)
+            .reward
+    }
+
+    /// Same as [`evaluate_execution_single`](Self::evaluate_execution_single), returning the
+    /// full [`EvaluationRecord`].
+    pub fn evaluate_execution_single_detailed(
+        &self,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+    ) -> EvaluationRecord {
+        let mut record = self.evaluate_single_execution_record(0, completion, test, entry_point);
+        if self.config.clamp_rewards {
+            record.reward = clamp_reward(record.reward);
+        }
+        record
+    }
+
     /// Evaluate a single LLM output by executing the extracted code against tests.
     ///
-    /// Returns 1.0 if all tests pass, 0.0 otherwise.
-    fn evaluate_single_execution(&self, completion: &str, test: &str, entry_point: &str) -> f64 {
-        if test.is_empty() || test == "null" {
-            return 0.0;
+    /// Returns 1.0 if all tests pass, 0.0 otherwise. Every 0.0 is logged at DEBUG level
+    /// with `completion_idx` (the position in the batch) and the `reason`.
+    pub(crate) fn evaluate_single_execution(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+    ) -> f64 {
+        self.evaluate_single_execution_record(completion_idx, completion, test, entry_point)
+            .reward
+    }
+
+    /// Same as [`evaluate_single_execution`](Self::evaluate_single_execution), returning
+    /// the full [`EvaluationRecord`].
+    fn evaluate_single_execution_record(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+    ) -> EvaluationRecord {
+        let start = Instant::now();
+        let mut record = EvaluationRecord::default();
+        self.run_single_execution(
+            completion_idx,
+            completion,
+            test,
+            entry_point,
+            self.config.dry_run,
+            &mut record,
+        );
+        record.wall_ms = start.elapsed().as_millis() as u64;
+        record
+    }
+
+    /// Body of [`evaluate_single_execution_record`](Self::evaluate_single_execution_record):
+    /// fills in `record` as far as the evaluation gets (the reward stays 0.0 on early exit).
+    /// With `dry_run`, stops before the sandbox with a reward of 1.0 if every check passed.
+    fn run_single_execution(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+        dry_run: bool,
+        record: &mut EvaluationRecord,
+    ) {
+        let test = non_blank(test);
+        let entry_point = non_blank(entry_point);
+        let Some(test) = test else {
+            tracing::debug!(completion_idx, reason = %"missing_test", "Zero reward");
+            return;
+        };
+
+        if self.exceeds_max_completion_chars(completion_idx, completion) {
+            tracing::debug!(completion_idx, reason = %"completion_too_long", "Zero reward");
+            return;
+        }
+
+        if self.config.gate_execution_on_format && !Self::has_valid_format(completion) {
+            tracing::debug!(completion_idx, reason = %"invalid_format", "Zero reward");
+            return;
         }
 
         let code = extract_code_from_completion(completion);
+        record.code_extracted = code.clone();
         if code.trim().is_empty() {
-            return 0.0;
+            tracing::debug!(completion_idx, reason = %"extraction_failed", "Zero reward");
+            return;
+        }
+        if self.below_min_code_lines(&code) {
+            tracing::debug!(completion_idx, reason = %"too_few_code_lines", "Zero reward");
+            return;
+        }
+        if self.config.reject_stubs && is_stub_code(&code) {
+            tracing::debug!(completion_idx, reason = %"stub_code", "Zero reward");
+            return;
         }
 
         // Add standard typing imports
@@ -178,75 +924,457 @@ impl RewardEvaluator {
         //     generated code must contain: class Solution with def twoSum(...)
         //     test class: Solution().two_sum([1, 2], 3)
         //
+        // Example 3 - Class instance:
+        //     entry_point: "Solution()"
+        //     generated code must contain: class Solution
+        //     test calls: candidate.twoSum(nums=[1, 2], target=3)
+        //
+        // Example 4 - Multi-method class (multi_method_mode):
+        //     entry_point: "LRUCache().get"  ->  "LRUCache"
+        //     generated code must contain: class LRUCache
+        //     test calls: cache = candidate(2); cache.put(1, 1); cache.get(1)
+        //     (the test constructs the instance; only its method calls are detected)
+        //
+        // Example 5 - Bare method of a solution class (solution_class_names):
+        //     entry_point: "twoSum"  ->  "Solution().twoSum"
+        //     generated code must contain: class Solution with def twoSum(...), and no
+        //     top-level def twoSum
+        //
+        // Example 6 - Another solution class (solution_class_names=["Solution", "Main"]):
+        //     entry_point: "Solution().twoSum"  ->  "Main().twoSum"
+        //     generated code must contain: class Main with def twoSum(...), and no
+        //     class Solution
+        //
         // This validation prevents false positives where the model generates code
         // but with wrong function/class names.
-        if !entry_point.is_empty() && entry_point != "null" {
-            // Extract method name: "Solution().twoSum" -> "twoSum", "add" -> "add"
-            let method_name = if entry_point.contains('.') {
-                entry_point.split('.').last().unwrap_or(entry_point)
-            } else {
-                entry_point
+        let entry_point =
+            entry_point.map(|entry_point| self.resolve_solution_method(&code, entry_point));
+        let entry_point = entry_point.as_deref();
+        let multi_method = self.config.multi_method_mode
+            && entry_point.is_some()
+            && is_multi_method_test(test, &self.config.check_function_names);
+        let entry_point = if multi_method {
+            entry_point.map(multi_method_class)
+        } else {
+            entry_point
+        };
+        if let Some(entry_point) = entry_point {
+            // Split "Solution().twoSum" into the receiver "Solution()" and the target "twoSum"
+            let (receiver, target) = match entry_point.rsplit_once('.') {
+                Some((receiver, target)) => (Some(receiver), target),
+                None => (None, entry_point),
             };
 
-            // Verify method/function definition exists
-            if !code_with_imports.contains(&format!("def {}", method_name)) {
-                return 0.0;
+            // Verify method/function definition exists (constructor calls are classes)
+            let target_class = if multi_method {
+                Some(target)
+            } else {
+                constructor_class(target)
+            };
+            if target_class.is_none() && !code_with_imports.contains(&format!("def {}", target)) {
+                tracing::debug!(
+                    completion_idx,
+                    reason = %"entry_point_not_found",
+                    method = %target,
+                    "Zero reward"
+                );
+                return;
             }
 
             // For class-based entry points, verify the class exists
-            if entry_point.contains("Solution().") && !code_with_imports.contains("class Solution")
+            if let Some(class) = target_class.or_else(|| receiver.and_then(constructor_class))
+                && !code_with_imports.contains(&format!("class {}", class))
             {
-                return 0.0;
+                tracing::debug!(
+                    completion_idx,
+                    reason = %"class_not_found",
+                    class = %class,
+                    "Zero reward"
+                );
+                return;
             }
         }
 
-        // Wrap test code to run all tests
-        let wrapped_tests = wrap_tests_for_complete_execution(test, entry_point);
+        record.entry_point_found = true;
+        if dry_run {
+            record.reward = 1.0;
+            return;
+        }
+
+        // Wrap test code to run all tests (a missing entry point is passed as `None`)
+        let entry_point = entry_point.unwrap_or("None");
+        let wrapped_tests = if self.config.pre_wrapped_tests {
+            if is_unwrapped_check_test(test, &self.config.check_function_names) {
+                tracing::warn!(
+                    completion_idx,
+                    "Test defines a check function but never prints TESTS_PASSED; \
+                     pre_wrapped_tests expects already wrapped tests"
+                );
+            }
+            Cow::Borrowed(test)
+        } else {
+            Cow::Owned(wrap_tests_with_check_names(
+                test,
+                entry_point,
+                &self.config.check_function_names,
+            ))
+        };
 
         // Combine solution and tests
         let full_code = format!("{}\n\n{}", code_with_imports, wrapped_tests);
 
-        // Execute in sandbox and return result
-        match run_sandboxed_tests(
-            &full_code,
-            self.config.timeout_seconds,
-            self.config.memory_limit_mb,
-            self.config.cpu_time_limit,
-        ) {
-            Ok((all_passed, _tests_passed, _tests_total)) => {
-                if all_passed {
-                    1.0
-                } else {
-                    0.0
-                }
-            }
+        // Execute in sandbox and record the result
+        let result = execute_sandboxed(&full_code, &self.execution_options(test));
+        self.record_sandbox_result(completion_idx, result, record);
+    }
+
+    /// Evaluate a single Rust completion: the code is compiled with `rustc` and run against
+    /// the tests (see [`wrap_rust_tests_for_complete_execution`]) inside the sandbox.
+    fn evaluate_single_rust_execution(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+    ) -> f64 {
+        let mut record = EvaluationRecord::default();
+        self.run_single_rust_execution(completion_idx, completion, test, entry_point, &mut record);
+        record.reward
+    }
+
+    /// Body of [`evaluate_single_rust_execution`](Self::evaluate_single_rust_execution),
+    /// with the checks of [`run_single_execution`](Self::run_single_execution) that apply
+    /// to Rust.
+    fn run_single_rust_execution(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        test: Option<&str>,
+        entry_point: Option<&str>,
+        record: &mut EvaluationRecord,
+    ) {
+        let test = non_blank(test);
+        let entry_point = non_blank(entry_point);
+        let Some(test) = test else {
+            tracing::debug!(completion_idx, reason = %"missing_test", "Zero reward");
+            return;
+        };
+        if self.exceeds_max_completion_chars(completion_idx, completion) {
+            tracing::debug!(completion_idx, reason = %"completion_too_long", "Zero reward");
+            return;
+        }
+        if self.config.gate_execution_on_format && !Self::has_valid_format(completion) {
+            tracing::debug!(completion_idx, reason = %"invalid_format", "Zero reward");
+            return;
+        }
+
+        let (code, _) = extract_code_with_language(completion);
+        record.code_extracted = code.clone();
+        if code.trim().is_empty() {
+            tracing::debug!(completion_idx, reason = %"extraction_failed", "Zero reward");
+            return;
+        }
+        if self.below_min_code_lines(&code) {
+            tracing::debug!(completion_idx, reason = %"too_few_code_lines", "Zero reward");
+            return;
+        }
+
+        let entry_point = entry_point.map_or("", str::trim);
+        let defines_entry_point = entry_point.is_empty()
+            || code.lines().any(|line| {
+                line.trim_start()
+                    .trim_start_matches("pub ")
+                    .strip_prefix("fn ")
+                    .and_then(|rest| rest.trim_start().strip_prefix(entry_point))
+                    .is_some_and(|rest| rest.trim_start().starts_with(['(', '<']))
+            });
+        if !defines_entry_point {
+            tracing::debug!(
+                completion_idx,
+                reason = %"entry_point_not_found",
+                method = %entry_point,
+                "Zero reward"
+            );
+            return;
+        }
+
+        record.entry_point_found = true;
+        if self.config.dry_run {
+            record.reward = 1.0;
+            return;
+        }
+
+        let wrapped_tests = wrap_rust_tests_for_complete_execution(test, entry_point);
+        let code =
+            RUST_MAIN_PATTERN.replace_all(&code, "#[allow(dead_code)]\n${1}__solution_main${2}");
+        let full_code = format!("#![allow(unused)]\n\n{}\n\n{}", code, wrapped_tests);
+
+        let result = execute_rust_sandboxed(&full_code, &self.execution_options(test));
+        self.record_sandbox_result(completion_idx, result, record);
+    }
+
+    /// Sandbox options for running `test`, with the timeouts scaled by the estimated test
+    /// complexity if [`EvaluatorConfig::adaptive_timeout`] is set.
+    fn execution_options(&self, test: &str) -> SandboxOptions {
+        let mut options = self.config.sandbox_options();
+        if self.config.adaptive_timeout {
+            let multiplier = estimate_test_complexity(test).timeout_multiplier();
+            options.timeout = (options.timeout as f64 * multiplier).ceil() as u64;
+            options.cpu_time_limit = (options.cpu_time_limit as f64 * multiplier).ceil() as u64;
+        }
+        options
+    }
+
+    /// Fill in `record` from the outcome of a sandboxed execution.
+    fn record_sandbox_result(
+        &self,
+        completion_idx: usize,
+        result: pyo3::PyResult<SandboxResult>,
+        record: &mut EvaluationRecord,
+    ) {
+        let result = match result {
+            Ok(result) => result,
             Err(e) => {
-                eprintln!("Execution error: {}", e);
-                0.0
+                tracing::error!(completion_idx, error = %e, "Execution error");
+                tracing::debug!(completion_idx, reason = %"execution_error", "Zero reward");
+                return;
             }
+        };
+        if let Some(level) = self.config.debug
+            && !result.stderr.is_empty()
+        {
+            log_stderr(level, completion_idx, &result.stderr);
+        }
+        record.timed_out = result.timed_out;
+        record.tests_passed = result.tests_passed;
+        record.tests_total = result.tests_total;
+        record.stderr_snippet = stderr_snippet(&result.stderr);
+        record.exception_type = result.exception_type.clone();
+
+        if result.all_passed {
+            record.reward = 1.0;
+        } else if result.timed_out {
+            tracing::debug!(completion_idx, reason = %"timeout", "Zero reward");
+        } else {
+            tracing::debug!(
+                completion_idx,
+                reason = %"tests_failed",
+                tests_passed = result.tests_passed,
+                tests_total = result.tests_total,
+                exception = %result.exception_type.as_deref().unwrap_or("none"),
+                "Zero reward"
+            );
         }
     }
 
-    /// Evaluate sandboxed code execution for a batch in parallel.
-    ///
-    /// Uses Rayon to process completions (LLM outputs) in parallel across the thread pool.
-    /// Each completion is evaluated independently with no shared state.
-    ///
-    /// # Arguments
-    /// - `completions`: LLM outputs to evaluate
-    /// - `tests`: Test code for each completion
+    /// Resolve `entry_point` against the [`EvaluatorConfig::solution_class_names`] classes:
+    /// a bare method name that `code` defines only inside a class becomes `Class().method`
+    /// for the first listed class that `code` defines, and a constructor entry point for a
+    /// listed class that `code` does not define (`Solution().twoSum` for a `class Main`)
+    /// is rewritten to that class. Anything else is returned unchanged.
+    fn resolve_solution_method<'a>(&self, code: &str, entry_point: &'a str) -> Cow<'a, str> {
+        let defined: Vec<&str> = code.lines().filter_map(class_name).collect();
+        let Some(solution_class) = self
+            .config
+            .solution_class_names
+            .iter()
+            .find(|name| defined.contains(&name.as_str()))
+        else {
+            return Cow::Borrowed(entry_point);
+        };
+
+        let is_bare_name =
+            !entry_point.is_empty() && entry_point.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !is_bare_name {
+            let receiver = entry_point.split('.').next().unwrap_or(entry_point);
+            let undefined_solution_class = constructor_class(receiver).filter(|class| {
+                !defined.contains(class)
+                    && self
+                        .config
+                        .solution_class_names
+                        .iter()
+                        .any(|name| name == class)
+            });
+            return match undefined_solution_class
+                .and_then(|class| entry_point.trim_start().strip_prefix(class))
+            {
+                Some(rest) => Cow::Owned(format!("{}{}", solution_class, rest)),
+                None => Cow::Borrowed(entry_point),
+            };
+        }
+
+        let (mut top_level, mut in_class) = (false, false);
+        for line in code.lines() {
+            if function_name(line) == Some(entry_point) {
+                if line.starts_with(char::is_whitespace) {
+                    in_class = true;
+                } else {
+                    top_level = true;
+                }
+            }
+        }
+
+        if in_class && !top_level {
+            Cow::Owned(format!("{}().{}", solution_class, entry_point))
+        } else {
+            Cow::Borrowed(entry_point)
+        }
+    }
+
+    /// Pre-run a trivial evaluation on every thread of the pool to absorb cold-start costs.
+    ///
+    /// Initializes the thread pool and the compiled regexes, and loads the sandbox and
+    /// Python binaries into the OS page cache, so the first real batch is not slowed down.
+    /// Failures are only logged: they will recur (and be reported) on real evaluations.
+    pub fn warmup(&self) {
+        let completion = "<answer>def warmup():\n    return 1</answer>";
+        let test = "def check(candidate):\n    assert candidate() == 1";
+
+        let failures = rayon::broadcast(|context| {
+            self.evaluate_single_execution(context.index(), completion, Some(test), Some("warmup"))
+                != 1.0
+        })
+        .into_iter()
+        .filter(|&failed| failed)
+        .count();
+
+        if failures > 0 {
+            tracing::warn!(failures, "Warmup evaluation failed on some threads");
+        }
+    }
+
+    /// Evaluate sandboxed code execution for a batch in parallel.
+    ///
+    /// Uses Rayon to process completions (LLM outputs) in parallel across the thread pool.
+    /// Each completion is evaluated independently with no shared state.
+    ///
+    /// # Arguments
+    /// - `completions`: LLM outputs to evaluate
+    /// - `tests`: Test code for each completion
     /// - `entry_points`: Function/method to test for each completion (e.g., "add" or "Solution().method")
     ///
     /// # Returns
-    /// Vector of rewards (1.0 = all tests passed, 0.0 = failed or error)
+    /// Vector of rewards (1.0 = all tests passed, 0.0 = failed or error). The order always
+    /// matches the input order: the zipped slices form an indexed parallel iterator, whose
+    /// `collect` places each result at its input position regardless of completion order.
     ///
     /// # Panics
     /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
     pub fn evaluate_execution_batch(
         &self,
         completions: &[String],
-        tests: &[String],
-        entry_points: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<f64> {
+        self.evaluate_execution_batch_with_progress(completions, tests, entry_points, || {})
+    }
+
+    /// Same as [`evaluate_execution_batch`](Self::evaluate_execution_batch), evaluating at
+    /// most `chunk_size` completions at a time.
+    ///
+    /// Chunks run one after another (each in parallel), bounding the temp files and sandbox
+    /// processes alive at once for very large batches.
+    ///
+    /// # Panics
+    /// Panics if the input slices have different lengths or `chunk_size` is 0.
+    pub fn evaluate_execution_batch_chunked(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        chunk_size: usize,
+    ) -> Vec<f64> {
+        self.evaluate_execution_stream(completions, tests, entry_points, chunk_size)
+            .collect()
+    }
+
+    /// Lazily evaluated execution rewards, in input order.
+    ///
+    /// Like [`evaluate_execution_batch_chunked`](Self::evaluate_execution_batch_chunked),
+    /// but each chunk of `chunk_size` completions is only evaluated (in parallel) once the
+    /// rewards of the previous chunk have been consumed, so a whole epoch of completions can
+    /// be scored without collecting every reward first.
+    ///
+    /// # Panics
+    /// Panics if the input slices have different lengths or `chunk_size` is 0.
+    pub fn evaluate_execution_stream<'a>(
+        &'a self,
+        completions: &'a [String],
+        tests: &'a [Option<String>],
+        entry_points: &'a [Option<String>],
+        chunk_size: usize,
+    ) -> impl Iterator<Item = f64> + 'a {
+        assert!(chunk_size > 0, "chunk_size must be at least 1");
+        assert_eq!(
+            completions.len(),
+            tests.len(),
+            "Completions and tests must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            entry_points.len(),
+            "Completions and entry_points must have same length"
+        );
+
+        (0..completions.len())
+            .step_by(chunk_size)
+            .flat_map(move |start| {
+                let end = (start + chunk_size).min(completions.len());
+                self.evaluate_execution_chunk(
+                    start,
+                    &completions[start..end],
+                    &tests[start..end],
+                    &entry_points[start..end],
+                )
+            })
+    }
+
+    /// Execution rewards for one chunk of a larger batch, in parallel; `start` is the batch
+    /// index of the chunk's first completion (used in log entries).
+    pub(crate) fn evaluate_execution_chunk(
+        &self,
+        start: usize,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<f64> {
+        completions
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .enumerate()
+            .map(|(offset, ((completion, test), entry_point))| {
+                let reward = self.evaluate_single_execution(
+                    start + offset,
+                    completion,
+                    test.as_deref(),
+                    entry_point.as_deref(),
+                );
+                if self.config.clamp_rewards {
+                    clamp_reward(reward)
+                } else {
+                    reward
+                }
+            })
+            .collect()
+    }
+
+    /// Execution rewards for a batch that mixes programming languages, dispatching each
+    /// completion to the runtime for its language (parallelized like
+    /// [`evaluate_execution_batch`](Self::evaluate_execution_batch)).
+    ///
+    /// [`Language::Python`] completions (and [`Language::Unknown`] ones, for untagged items)
+    /// are evaluated as usual and [`Language::Rust`] ones are compiled with `rustc`, while
+    /// other languages get 0.0 (logged at DEBUG level with reason `unsupported_language`).
+    ///
+    /// # Panics
+    /// Panics if the input slices have different lengths.
+    pub fn evaluate_execution_batch_mixed(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        languages: &[Language],
     ) -> Vec<f64> {
         assert_eq!(
             completions.len(),
@@ -258,14 +1386,909 @@ impl RewardEvaluator {
             entry_points.len(),
             "Completions and entry_points must have same length"
         );
+        assert_eq!(
+            completions.len(),
+            languages.len(),
+            "Completions and languages must have the same length"
+        );
 
         completions
             .par_iter()
             .zip(tests.par_iter())
             .zip(entry_points.par_iter())
-            .map(|((completion, test), entry_point)| {
-                self.evaluate_single_execution(completion, test, entry_point)
+            .zip(languages.par_iter())
+            .enumerate()
+            .map(|(idx, (((completion, test), entry_point), language))| {
+                let reward = match language {
+                    Language::Python | Language::Unknown => self.evaluate_single_execution(
+                        idx,
+                        completion,
+                        test.as_deref(),
+                        entry_point.as_deref(),
+                    ),
+                    Language::Rust => self.evaluate_single_rust_execution(
+                        idx,
+                        completion,
+                        test.as_deref(),
+                        entry_point.as_deref(),
+                    ),
+                    other => {
+                        tracing::debug!(
+                            completion_idx = idx,
+                            reason = %"unsupported_language",
+                            language = %other,
+                            "Zero reward"
+                        );
+                        0.0
+                    }
+                };
+                if self.config.clamp_rewards {
+                    clamp_reward(reward)
+                } else {
+                    reward
+                }
             })
             .collect()
     }
+
+    /// Same as [`evaluate_execution_batch`](Self::evaluate_execution_batch), calling
+    /// `on_complete` after each completion is evaluated (from the worker threads, in
+    /// completion order) so callers can report progress.
+    pub fn evaluate_execution_batch_with_progress<F>(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        on_complete: F,
+    ) -> Vec<f64>
+    where
+        F: Fn() + Sync,
+    {
+        self.execution_records(completions, tests, entry_points, on_complete)
+            .into_iter()
+            .map(|record| record.reward)
+            .collect()
+    }
+
+    /// Same as [`evaluate_execution_batch`](Self::evaluate_execution_batch), returning an
+    /// [`EvaluationRecord`] per completion that explains its reward.
+    ///
+    /// # Panics
+    /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_execution_batch_with_metadata(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<EvaluationRecord> {
+        self.execution_records(completions, tests, entry_points, || {})
+    }
+
+    /// Shared implementation of the execution batch methods.
+    fn execution_records<F>(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        on_complete: F,
+    ) -> Vec<EvaluationRecord>
+    where
+        F: Fn() + Sync,
+    {
+        assert_eq!(
+            completions.len(),
+            tests.len(),
+            "Completions and tests must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            entry_points.len(),
+            "Completions and entry_points must have same length"
+        );
+
+        completions
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .enumerate()
+            .map(|(idx, ((completion, test), entry_point))| {
+                let mut record = self.evaluate_single_execution_record(
+                    idx,
+                    completion,
+                    test.as_deref(),
+                    entry_point.as_deref(),
+                );
+                if self.config.clamp_rewards {
+                    record.reward = clamp_reward(record.reward);
+                }
+                on_complete();
+                record
+            })
+            .collect()
+    }
+
+    /// The completions that would not get a 0.0 execution reward before even reaching the
+    /// sandbox: those with a non-blank test, extractable code and a defined entry point
+    /// (the checks of `dry_run`), so that only they are sent for execution.
+    ///
+    /// The caller puts 0.0 at every batch index missing from [`ValidCompletions::indices`].
+    /// Rejected completions are logged at DEBUG level like during evaluation.
+    ///
+    /// # Panics
+    /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
+    pub fn filter_valid_completions(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> ValidCompletions {
+        assert_eq!(
+            completions.len(),
+            tests.len(),
+            "Completions and tests must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            entry_points.len(),
+            "Completions and entry_points must have same length"
+        );
+
+        let valid: Vec<bool> = completions
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .enumerate()
+            .map(|(idx, ((completion, test), entry_point))| {
+                if non_blank(test.as_deref()).is_none() {
+                    tracing::debug!(completion_idx = idx, reason = %"missing_test", "Zero reward");
+                    return false;
+                }
+                let mut record = EvaluationRecord::default();
+                self.run_single_execution(
+                    idx,
+                    completion,
+                    test.as_deref(),
+                    entry_point.as_deref(),
+                    true,
+                    &mut record,
+                );
+                record.reward > 0.0
+            })
+            .collect();
+
+        let mut filtered = ValidCompletions::default();
+        for (idx, _) in valid.iter().enumerate().filter(|(_, valid)| **valid) {
+            filtered.indices.push(idx);
+            filtered.completions.push(completions[idx].clone());
+            filtered.tests.push(tests[idx].clone());
+            filtered.entry_points.push(entry_points[idx].clone());
+        }
+        filtered
+    }
+
+    /// Report the rewards that are NaN, infinite or outside `[0.0, 1.0]`, without
+    /// modifying them (for debugging reward functions).
+    pub fn validate_rewards(rewards: &[f64]) -> Vec<ValidationIssue> {
+        rewards
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &value)| {
+                let kind = if value.is_nan() {
+                    ValidationIssueKind::NaN
+                } else if value.is_infinite() {
+                    ValidationIssueKind::Infinite
+                } else if !(0.0..=1.0).contains(&value) {
+                    ValidationIssueKind::OutOfRange
+                } else {
+                    return None;
+                };
+                Some(ValidationIssue { index, value, kind })
+            })
+            .collect()
+    }
+
+    /// Evaluate stdout-based problems for a batch in parallel.
+    ///
+    /// Runs the extracted code in the sandbox without test wrapping and compares its stdout
+    /// against the expected output, normalizing whitespace if
+    /// [`EvaluatorConfig::output_normalize_whitespace`] is set.
+    ///
+    /// # Returns
+    /// Vector of rewards (1.0 = exit code 0 and output matches, 0.0 otherwise)
+    ///
+    /// # Panics
+    /// Panics if `completions` and `expected_outputs` have different lengths.
+    pub fn evaluate_output_batch(
+        &self,
+        completions: &[String],
+        expected_outputs: &[String],
+    ) -> Vec<f64> {
+        self.evaluate_output_batch_with(
+            completions,
+            expected_outputs,
+            self.config.output_normalize_whitespace,
+        )
+    }
+
+    /// Same as [`evaluate_output_batch`](Self::evaluate_output_batch), with whitespace
+    /// normalization chosen per call instead of from the configuration.
+    pub fn evaluate_output_batch_with(
+        &self,
+        completions: &[String],
+        expected_outputs: &[String],
+        normalize_whitespace: bool,
+    ) -> Vec<f64> {
+        assert_eq!(
+            completions.len(),
+            expected_outputs.len(),
+            "Completions and expected_outputs must have the same length"
+        );
+
+        completions
+            .par_iter()
+            .zip(expected_outputs.par_iter())
+            .enumerate()
+            .map(|(idx, (completion, expected_output))| {
+                self.evaluate_single_output(
+                    idx,
+                    completion,
+                    None,
+                    expected_output,
+                    normalize_whitespace,
+                )
+            })
+            .collect()
+    }
+
+    /// Compare the printed output of each completion against its expected output.
+    ///
+    /// Same as [`evaluate_output_batch_with`](Self::evaluate_output_batch_with), named for
+    /// competitive programming style checks.
+    ///
+    /// # Panics
+    /// Panics if `completions` and `expected_outputs` have different lengths.
+    pub fn evaluate_output_match_batch(
+        &self,
+        completions: &[String],
+        expected_outputs: &[String],
+        whitespace_normalize: bool,
+    ) -> Vec<f64> {
+        self.evaluate_output_batch_with(completions, expected_outputs, whitespace_normalize)
+    }
+
+    /// Evaluate stdin/stdout problems (e.g., competitive programming) for a batch in parallel.
+    ///
+    /// Like [`evaluate_output_batch`](Self::evaluate_output_batch), but each script also
+    /// receives its entry of `stdin_inputs` on stdin.
+    ///
+    /// # Panics
+    /// Panics if `completions`, `stdin_inputs`, and `expected_outputs` have different lengths.
+    pub fn evaluate_stdin_batch(
+        &self,
+        completions: &[String],
+        stdin_inputs: &[String],
+        expected_outputs: &[String],
+    ) -> Vec<f64> {
+        assert_eq!(
+            completions.len(),
+            stdin_inputs.len(),
+            "Completions and stdin_inputs must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            expected_outputs.len(),
+            "Completions and expected_outputs must have the same length"
+        );
+
+        completions
+            .par_iter()
+            .zip(stdin_inputs.par_iter())
+            .zip(expected_outputs.par_iter())
+            .enumerate()
+            .map(|(idx, ((completion, stdin_input), expected_output))| {
+                self.evaluate_single_output(
+                    idx,
+                    completion,
+                    Some(stdin_input),
+                    expected_output,
+                    self.config.output_normalize_whitespace,
+                )
+            })
+            .collect()
+    }
+
+    /// Evaluate a single LLM output by comparing the stdout of the extracted code
+    /// against `expected_output`.
+    ///
+    /// `stdin_input` overrides [`EvaluatorConfig::stdin_input`] for this execution.
+    pub(crate) fn evaluate_single_output(
+        &self,
+        completion_idx: usize,
+        completion: &str,
+        stdin_input: Option<&str>,
+        expected_output: &str,
+        normalize_whitespace: bool,
+    ) -> f64 {
+        if self.exceeds_max_completion_chars(completion_idx, completion) {
+            return 0.0;
+        }
+
+        let code = extract_code_from_completion(completion);
+        if code.trim().is_empty() || self.below_min_code_lines(&code) {
+            return 0.0;
+        }
+
+        let mut options = self.config.sandbox_options();
+        if let Some(stdin_input) = stdin_input {
+            options.stdin_input = Some(stdin_input.to_string());
+        }
+
+        let result = match execute_sandboxed(&code, &options) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::error!(completion_idx, error = %e, "Execution error");
+                return 0.0;
+            }
+        };
+
+        if result.exit_code != Some(0) {
+            return 0.0;
+        }
+
+        let matches = if normalize_whitespace {
+            normalize_output(&result.stdout) == normalize_output(expected_output)
+        } else {
+            result.stdout == expected_output
+        };
+
+        if matches { 1.0 } else { 0.0 }
+    }
+
+    /// Evaluate format compliance and sandboxed execution for a batch in a single parallel pass.
+    ///
+    /// The cheap regex format check runs first and gates the expensive sandbox execution:
+    /// completions with invalid format get an `execution_score` of 0.0 without being run.
+    ///
+    /// # Panics
+    /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_combined(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<CombinedReward> {
+        assert_eq!(
+            completions.len(),
+            tests.len(),
+            "Completions and tests must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            entry_points.len(),
+            "Completions and entry_points must have same length"
+        );
+
+        completions
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .enumerate()
+            .map(|(idx, ((completion, test), entry_point))| {
+                if !Self::has_valid_format(completion) {
+                    return CombinedReward::default();
+                }
+
+                CombinedReward {
+                    format_score: 1.0,
+                    execution_score: self.evaluate_single_execution(
+                        idx,
+                        completion,
+                        test.as_deref(),
+                        entry_point.as_deref(),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Weighted combined reward (`format_weight * format_score + exec_weight * execution_score`)
+    /// for a batch, computed with [`evaluate_combined`](Self::evaluate_combined): completions
+    /// that fail the format check get no execution reward and are never run.
+    ///
+    /// # Panics
+    /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_combined_gated(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        format_weight: f64,
+        exec_weight: f64,
+    ) -> Vec<f64> {
+        self.evaluate_combined(completions, tests, entry_points)
+            .iter()
+            .map(|reward| reward.weighted(format_weight, exec_weight))
+            .collect()
+    }
+
+    /// Execution reward and static code-quality signals for a batch, combined into a
+    /// composite score with [`EvaluatorConfig::quality_weights`].
+    ///
+    /// # Panics
+    /// Panics if `completions`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_code_quality_batch(
+        &self,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<CodeQualityScore> {
+        let weights = self.config.quality_weights;
+        let rewards = self.evaluate_execution_batch(completions, tests, entry_points);
+
+        completions
+            .par_iter()
+            .zip(rewards)
+            .map(|(completion, execution)| {
+                let code = extract_code_from_completion(completion);
+                let has_type_hints = has_type_hints(&code);
+                let has_docstring = has_docstring(&code);
+                let has_comments = has_comments(&code);
+                let signal = |present: bool| if present { 1.0 } else { 0.0 };
+                let composite = (weights.execution * execution
+                    + weights.type_hints * signal(has_type_hints)
+                    + weights.docstring * signal(has_docstring)
+                    + weights.comments * signal(has_comments))
+                    / weights.total();
+
+                CodeQualityScore {
+                    execution,
+                    has_type_hints,
+                    has_docstring,
+                    has_comments,
+                    code_length_chars: code.chars().count(),
+                    composite,
+                }
+            })
+            .collect()
+    }
+
+    /// Compute Pass@K for each problem from multiple sampled completions.
+    ///
+    /// Problems are evaluated in parallel (outer Rayon loop), and so are the samples of each
+    /// problem (inner loop). For a problem with `n` samples of which `c` pass, the unbiased
+    /// estimator `1 - C(n-c, k) / C(n, k)` is applied.
+    ///
+    /// # Arguments
+    /// - `completions_per_problem`: Sampled LLM outputs, grouped by problem
+    /// - `tests`: Test code for each problem
+    /// - `entry_points`: Function/method to test for each problem
+    /// - `k`: Number of samples considered per problem
+    ///
+    /// # Returns
+    /// Vector of Pass@K values in `[0.0, 1.0]`, one per problem
+    ///
+    /// # Panics
+    /// Panics if the input lengths differ, if `k` is 0, or if a problem has fewer than `k` samples.
+    pub fn batch_evaluate_pass_at_k(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        k: usize,
+    ) -> Vec<f64> {
+        assert_eq!(
+            completions_per_problem.len(),
+            tests.len(),
+            "Problems and tests must have the same length"
+        );
+        assert_eq!(
+            completions_per_problem.len(),
+            entry_points.len(),
+            "Problems and entry_points must have same length"
+        );
+        assert!(k > 0, "k must be at least 1");
+
+        completions_per_problem
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .map(|((completions, test), entry_point)| {
+                assert!(
+                    completions.len() >= k,
+                    "Each problem needs at least k={} samples, got {}",
+                    k,
+                    completions.len()
+                );
+
+                let num_correct = completions
+                    .par_iter()
+                    .enumerate()
+                    .filter(|(idx, completion)| {
+                        self.evaluate_single_execution(
+                            *idx,
+                            completion,
+                            test.as_deref(),
+                            entry_point.as_deref(),
+                        ) == 1.0
+                    })
+                    .count();
+
+                pass_at_k_estimate(completions.len(), num_correct, k)
+            })
+            .collect()
+    }
+
+    /// Best-of-N reward: the maximum execution reward across each problem's completions.
+    ///
+    /// Problems with no completions get 0.0.
+    ///
+    /// # Panics
+    /// Panics if `completions_per_problem`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_best_of_n(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<f64> {
+        self.evaluate_execution_grouped(completions_per_problem, tests, entry_points)
+            .into_iter()
+            .map(|rewards| rewards.into_iter().fold(0.0, f64::max))
+            .collect()
+    }
+
+    /// Mean execution reward across each problem's completions.
+    ///
+    /// Problems with no completions get 0.0.
+    ///
+    /// # Panics
+    /// Panics if `completions_per_problem`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_mean_of_n(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<f64> {
+        self.evaluate_execution_grouped(completions_per_problem, tests, entry_points)
+            .into_iter()
+            .map(|rewards| {
+                if rewards.is_empty() {
+                    0.0
+                } else {
+                    rewards.iter().sum::<f64>() / rewards.len() as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Fraction of each problem's completions that pass all tests (reward of 1.0).
+    ///
+    /// Problems with no completions get 0.0.
+    ///
+    /// # Panics
+    /// Panics if `completions_per_problem`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_majority_of_n(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<f64> {
+        self.evaluate_execution_grouped(completions_per_problem, tests, entry_points)
+            .into_iter()
+            .map(|rewards| {
+                if rewards.is_empty() {
+                    0.0
+                } else {
+                    let passed = rewards.iter().filter(|&&reward| reward == 1.0).count();
+                    passed as f64 / rewards.len() as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Execution rewards for grouped completions (e.g. K rollouts per problem).
+    ///
+    /// The groups are flattened into a single `evaluate_execution_batch` call, so all
+    /// completions share the thread pool, then the rewards are reshaped back into their
+    /// groups. The `completion_idx` in DEBUG logs is the index in the flattened batch.
+    ///
+    /// # Panics
+    /// Panics if `completions_per_problem`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_execution_grouped(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<Vec<f64>> {
+        self.evaluate_execution_grouped_with_metadata(completions_per_problem, tests, entry_points)
+            .into_iter()
+            .map(|records| records.into_iter().map(|record| record.reward).collect())
+            .collect()
+    }
+
+    /// Same as [`evaluate_execution_grouped`](Self::evaluate_execution_grouped), returning an
+    /// [`EvaluationRecord`] per completion. The groups are laid out one after another in the
+    /// flattened batch.
+    ///
+    /// # Panics
+    /// Panics if `completions_per_problem`, `tests`, and `entry_points` have different lengths.
+    pub fn evaluate_execution_grouped_with_metadata(
+        &self,
+        completions_per_problem: &[Vec<String>],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+    ) -> Vec<Vec<EvaluationRecord>> {
+        assert_eq!(
+            completions_per_problem.len(),
+            tests.len(),
+            "Problems and tests must have the same length"
+        );
+        assert_eq!(
+            completions_per_problem.len(),
+            entry_points.len(),
+            "Problems and entry_points must have same length"
+        );
+
+        let total: usize = completions_per_problem.iter().map(Vec::len).sum();
+        let mut flat_completions = Vec::with_capacity(total);
+        let mut flat_tests = Vec::with_capacity(total);
+        let mut flat_entry_points = Vec::with_capacity(total);
+
+        for ((completions, test), entry_point) in
+            completions_per_problem.iter().zip(tests).zip(entry_points)
+        {
+            flat_completions.extend(completions.iter().cloned());
+            flat_tests.extend(std::iter::repeat_n(test.clone(), completions.len()));
+            flat_entry_points.extend(std::iter::repeat_n(entry_point.clone(), completions.len()));
+        }
+
+        let flat_records = self.evaluate_execution_batch_with_metadata(
+            &flat_completions,
+            &flat_tests,
+            &flat_entry_points,
+        );
+
+        let mut records = flat_records.into_iter();
+        completions_per_problem
+            .iter()
+            .map(|completions| records.by_ref().take(completions.len()).collect())
+            .collect()
+    }
+}
+
+/// Aggregate statistics of a batch of rewards, e.g. from
+/// [`RewardEvaluator::evaluate_execution_batch`]. A reward of 1.0 (or more) counts as passed.
+///
+/// All fields are 0 for an empty batch.
+pub fn evaluation_summary(rewards: &[f64]) -> EvaluationSummary {
+    if rewards.is_empty() {
+        return EvaluationSummary::default();
+    }
+
+    let total = rewards.len();
+    let passed = rewards.iter().filter(|&&reward| reward >= 1.0).count();
+    let mean_reward = rewards.iter().sum::<f64>() / total as f64;
+    let variance = rewards
+        .iter()
+        .map(|reward| (reward - mean_reward).powi(2))
+        .sum::<f64>()
+        / total as f64;
+
+    EvaluationSummary {
+        pass_rate: passed as f64 / total as f64,
+        total,
+        passed,
+        failed: total - passed,
+        mean_reward,
+        std_reward: variance.sqrt(),
+    }
+}
+
+/// [`evaluation_summary`] of the records' rewards, with a breakdown of failures, e.g. from
+/// [`RewardEvaluator::evaluate_execution_batch_with_metadata`].
+///
+/// All fields are 0 for an empty batch.
+pub fn evaluation_summary_detailed(records: &[EvaluationRecord]) -> DetailedSummary {
+    if records.is_empty() {
+        return DetailedSummary::default();
+    }
+
+    let rewards: Vec<f64> = records.iter().map(|record| record.reward).collect();
+    let rate = |predicate: fn(&EvaluationRecord) -> bool| {
+        records.iter().filter(|record| predicate(record)).count() as f64 / records.len() as f64
+    };
+
+    DetailedSummary {
+        summary: evaluation_summary(&rewards),
+        timeout_rate: rate(|record| record.timed_out),
+        syntax_error_rate: rate(|record| {
+            matches!(
+                record.exception_type.as_deref(),
+                Some("SyntaxError" | "IndentationError" | "TabError")
+            )
+        }),
+        extraction_failure_rate: rate(|record| record.code_extracted.trim().is_empty()),
+        not_executed_rate: rate(|record| !record.entry_point_found),
+        mean_wall_ms: records
+            .iter()
+            .map(|record| record.wall_ms as f64)
+            .sum::<f64>()
+            / records.len() as f64,
+    }
+}
+
+/// Normalize program output for comparison: strip trailing whitespace on every line,
+/// collapse runs of blank lines into one and drop leading/trailing blank lines.
+fn normalize_output(output: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in output.lines().map(str::trim_end) {
+        if !(line.is_empty() && lines.last().is_some_and(|last| last.is_empty())) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// A test or entry point, or `None` if it is missing: `None` itself or a blank string
+/// (e.g. an empty dataset cell).
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+/// Class instantiated by a constructor call in an entry point (`"Solution()"` -> `"Solution"`),
+/// or `None` if `expr` is not a call.
+fn constructor_class(expr: &str) -> Option<&str> {
+    let (callee, _) = expr.split_once('(')?;
+    let class = callee.rsplit('.').next()?.trim();
+    (!class.is_empty()).then_some(class)
+}
+
+/// Name of the top-level class defined on `line` (`class Name:` or `class Name(Base):`),
+/// if any.
+fn class_name(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("class ")?.trim_start();
+    let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+    (end > 0).then(|| &rest[..end])
+}
+
+/// Name of the function defined on `line` (`def name(` or `async def name(`), if any.
+fn function_name(line: &str) -> Option<&str> {
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix("async ").map_or(rest, str::trim_start);
+    let rest = rest.strip_prefix("def ")?.trim_start();
+    let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+    Some(&rest[..end])
+}
+
+/// The class of a multi-method entry point: `LRUCache().get`, `LRUCache()` and `LRUCache`
+/// all give `LRUCache`.
+fn multi_method_class(entry_point: &str) -> &str {
+    let receiver = entry_point.split('.').next().unwrap_or(entry_point);
+    constructor_class(receiver).unwrap_or(receiver).trim()
+}
+
+/// Compile `pattern`, reusing the compiled regex if it is in [`REGEX_CACHE`].
+fn cached_regex(pattern: &str) -> Result<Regex> {
+    let mut cache = REGEX_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(position) = cache.iter().position(|(cached, _)| cached == pattern) {
+        let entry = cache.remove(position).unwrap();
+        let regex = entry.1.clone();
+        cache.push_front(entry);
+        return Ok(regex);
+    }
+
+    let regex =
+        Regex::new(pattern).with_context(|| format!("Invalid regex pattern '{}'", pattern))?;
+    cache.push_front((pattern.to_string(), regex.clone()));
+    cache.truncate(REGEX_CACHE_CAPACITY);
+    Ok(regex)
+}
+
+/// Log the stderr of execution `completion_idx` at `level` (the `tracing` macros need the
+/// level as a constant).
+fn log_stderr(level: Level, completion_idx: usize, stderr: &str) {
+    match level {
+        Level::ERROR => tracing::error!(completion_idx, stderr, "Sandbox stderr"),
+        Level::WARN => tracing::warn!(completion_idx, stderr, "Sandbox stderr"),
+        Level::INFO => tracing::info!(completion_idx, stderr, "Sandbox stderr"),
+        Level::DEBUG => tracing::debug!(completion_idx, stderr, "Sandbox stderr"),
+        _ => tracing::trace!(completion_idx, stderr, "Sandbox stderr"),
+    }
+}
+
+/// Replace a NaN reward with 0.0 and clamp others to `[0.0, 1.0]`.
+fn clamp_reward(reward: f64) -> f64 {
+    if reward.is_nan() {
+        0.0
+    } else {
+        reward.clamp(0.0, 1.0)
+    }
+}
+
+/// The last [`STDERR_SNIPPET_CHARS`] characters of `stderr`, or `None` if it is blank.
+fn stderr_snippet(stderr: &str) -> Option<String> {
+    let stderr = stderr.trim_end();
+    if stderr.is_empty() {
+        return None;
+    }
+    let skip = stderr.chars().count().saturating_sub(STDERR_SNIPPET_CHARS);
+    Some(stderr.chars().skip(skip).collect())
+}
+
+// Like the integration tests, these link against libpython, so they only build with the
+// `extension-module` feature disabled (`cargo test --no-default-features`)
+#[cfg(all(test, not(feature = "extension-module")))]
+mod tests {
+    use super::*;
+
+    const BLANK_VARIANTS: [&str; 4] = ["", " ", "\n", "\t \n"];
+
+    /// An evaluator that runs every check but stops before the sandbox.
+    fn dry_run_evaluator() -> RewardEvaluator {
+        RewardEvaluator::new(EvaluatorConfig {
+            dry_run: true,
+            num_threads: None,
+            ..EvaluatorConfig::default()
+        })
+        .expect("valid configuration")
+    }
+
+    #[test]
+    fn blank_values_are_missing() {
+        assert_eq!(non_blank(None), None);
+        for blank in BLANK_VARIANTS {
+            assert_eq!(non_blank(Some(blank)), None, "{:?}", blank);
+        }
+        // Serialized nulls are strings like any other
+        for value in ["null", "None", "none", " add "] {
+            assert_eq!(non_blank(Some(value)), Some(value));
+        }
+    }
+
+    #[test]
+    fn missing_tests_score_zero() {
+        let evaluator = dry_run_evaluator();
+        let completion = "<answer>def add(a, b):\n    return a + b</answer>";
+        let test = "def check(candidate):\n    assert candidate(2, 3) == 5";
+
+        assert_eq!(
+            evaluator.evaluate_execution_single(completion, Some(test), Some("add")),
+            1.0
+        );
+        assert_eq!(
+            evaluator.evaluate_execution_single(completion, None, Some("add")),
+            0.0
+        );
+        for blank in BLANK_VARIANTS {
+            assert_eq!(
+                evaluator.evaluate_execution_single(completion, Some(blank), Some("add")),
+                0.0,
+                "{:?}",
+                blank
+            );
+        }
+    }
+
+    #[test]
+    fn missing_entry_points_skip_the_entry_point_check() {
+        let evaluator = dry_run_evaluator();
+        let completion = "<answer>def add(a, b):\n    return a + b</answer>";
+        let test = "def check(candidate):\n    assert add(2, 3) == 5";
+
+        assert_eq!(
+            evaluator.evaluate_execution_single(completion, Some(test), Some("sub")),
+            0.0
+        );
+        assert_eq!(
+            evaluator.evaluate_execution_single(completion, Some(test), None),
+            1.0
+        );
+        for blank in BLANK_VARIANTS {
+            assert_eq!(
+                evaluator.evaluate_execution_single(completion, Some(test), Some(blank)),
+                1.0,
+                "{:?}",
+                blank
+            );
+        }
+    }
 }
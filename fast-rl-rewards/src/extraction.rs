@@ -6,11 +6,20 @@
 //! `<think>...</think>` `<answer>...</answer>` format used in reasoning models.
 //!
 //! # Extraction strategy:
-//! 1. Try to extract from `<answer>...</answer>` tags
-//! 2. Fallback to markdown code blocks (```python```)
+//! 1. Try to extract from `<answer>...</answer>` tags outside of `<think>` blocks
+//!    (answers drafted while thinking are ignored)
+//! 2. Fallback to markdown code blocks (```python```), also outside of `<think>` blocks
 //! 3. Return entire text as last resort.
 //!
-//! Markdown fences inside answer tags are automatically stripped.
+//! Markdown fences inside answer tags are automatically stripped, HTML entities
+//! (`&lt;`, `&gt;`, `&amp;`, `&quot;`, `&#39;`) returned by some LLM APIs are unescaped, and
+//! indentation common to all lines is removed (see [`normalize_indentation`]).
+//!
+//! [`extract_code_with_language`] additionally reports the language, taken from an
+//! `<answer lang="...">` attribute or the markdown fence tag, and
+//! [`extract_code_from_json_completion`] accepts raw JSON API responses.
+//! [`extract_think_block`] and [`extract_answer_block`] return the raw blocks for custom
+//! rewards.
 //!
 //! # Examples
 //! ```python
@@ -24,35 +33,676 @@
 use once_cell::sync::Lazy;
 use pyo3::prelude::*;
 use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+
+// Regex pattern for content within <answer>...</answer> tags (case-insensitive),
+// with an optional `lang` attribute captured in group 1 and the content in group 2
+static ANSWER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<answer(?:\s+lang\s*=\s*["']?([^"'\s>]*)["']?)?\s*>(.*?)</answer>"#).unwrap()
+});
 
-// Regex pattern for content within <answer>...</answer> tags (case-insensitive)
-static ANSWER_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?is)<answer>(.*?)</answer>").unwrap());
+// Regex pattern for <think>...</think> blocks (content in group 1), whose draft answers
+// must be skipped
+static THINK_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<think>(.*?)</think>").unwrap());
 
-// Regex pattern for markdown code blocks with Python language specifier
+// Regex pattern for markdown code blocks with Python language specifier (case-insensitive)
 static CODE_BLOCK_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?s)```python\s*\n(.*?)\n```").unwrap());
+    Lazy::new(|| Regex::new(r"(?is)```python\s*\n(.*?)\n```").unwrap());
+
+// Regex pattern for keywords that add a decision point to the control flow graph
+static BRANCH_KEYWORD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(?:if|elif|for|while|except|and|or)\b").unwrap());
+
+// Regex pattern for markdown code blocks with any (or no) language specifier
+static ANY_CODE_BLOCK_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)```([\w+#.-]*)[ \t]*\n(.*?)\n```").unwrap());
 
-// Patterns for cleaning markdown code blocks inside answer tags
-static MARKDOWN_START_PYTHON: Lazy<Regex> = Lazy::new(|| Regex::new(r"^```python\s*\n").unwrap());
-static MARKDOWN_START_PLAIN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^```\s*\n").unwrap());
+// Patterns for cleaning markdown code blocks inside answer tags (group 1 is the language tag)
+static MARKDOWN_START: Lazy<Regex> = Lazy::new(|| Regex::new(r"^```([\w+#.-]*)\s*\n").unwrap());
 static MARKDOWN_END: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n```\s*$").unwrap());
 
+// Regex pattern for a single-line function header, capturing the indentation (group 1)
+// and any statement after the colon (group 2)
+static FUNCTION_HEADER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\s*)(?:async\s+)?def\s+\w+\s*\(.*\)\s*(?:->[^:]+)?:\s*(.*)$").unwrap()
+});
+
+// Regex pattern for a docstring at the start of a function body
+static DOCSTRING_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(?s)\s*[rRuU]?(?:""".*?"""|'{3}.*?'{3}|"[^"\n]*"|'[^'\n]*')"#).unwrap()
+});
+
+// Regex pattern for a single-line function signature, capturing the parameters (group 1)
+// and the return annotation arrow (group 2)
+static SIGNATURE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:async\s+)?def\s+\w+\s*\((.*)\)\s*(->)?").unwrap());
+
+// Regex pattern for an annotated parameter (`x: int`, `*args: str`)
+static ANNOTATED_PARAMETER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*\*{0,2}\w+\s*:").unwrap());
+
+// Regex pattern for the opening quotes of a triple-quoted string
+static TRIPLE_QUOTE_START: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^[rRuU]?(?:"{3}|'{3})"#).unwrap());
+
+// Statements that do nothing in a placeholder function body
+const STUB_STATEMENTS: [&str; 4] = ["pass", "...", "return None", "return"];
+
+// HTML entities to unescape, with `&amp;` last so that `&amp;lt;` becomes `&lt;` (not `<`)
+const HTML_ENTITIES: [(&str, &str); 5] = [
+    ("&lt;", "<"),
+    ("&gt;", ">"),
+    ("&quot;", "\""),
+    ("&#39;", "'"),
+    ("&amp;", "&"),
+];
+
+/// Programming language of extracted code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Language {
+    Python,
+    JavaScript,
+    Java,
+    Rust,
+    /// Any other language tag (lowercased)
+    Other(String),
+    /// No language tag found
+    Unknown,
+}
+
+impl Language {
+    /// Parse a fence tag or `lang` attribute (case-insensitive, common aliases included).
+    pub fn from_tag(tag: &str) -> Self {
+        match tag.trim().to_ascii_lowercase().as_str() {
+            "" => Self::Unknown,
+            "python" | "python3" | "py" => Self::Python,
+            "javascript" | "js" => Self::JavaScript,
+            "java" => Self::Java,
+            "rust" | "rs" => Self::Rust,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Python => f.write_str("python"),
+            Self::JavaScript => f.write_str("javascript"),
+            Self::Java => f.write_str("java"),
+            Self::Rust => f.write_str("rust"),
+            Self::Other(tag) => f.write_str(tag),
+            Self::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
 #[pyfunction]
 pub fn extract_code_from_completion(completion: &str) -> String {
-    if let Some(captures) = ANSWER_PATTERN.captures(completion) {
-        let code = captures[1].trim();
+    // Drop reasoning first so a draft `<answer>` inside `<think>` is not mistaken for the final one
+    let outside_think = THINK_PATTERN.replace_all(completion, "");
 
-        let code = MARKDOWN_START_PYTHON.replace(code, "");
-        let code = MARKDOWN_START_PLAIN.replace(&code, "");
-        let code = MARKDOWN_END.replace(&code, "");
+    if let Some(captures) = ANSWER_PATTERN.captures(&outside_think) {
+        return answer_code(&captures[2]);
+    }
 
-        return code.into_owned();
+    if let Some(captures) = CODE_BLOCK_PATTERN.captures(&outside_think) {
+        return normalize_indentation(trim_keep_indent(&captures[1]));
     }
 
-    if let Some(captures) = CODE_BLOCK_PATTERN.captures(completion) {
-        return captures[1].trim().to_string();
+    normalize_indentation(trim_keep_indent(completion))
+}
+
+/// Remove the leading whitespace common to all non-blank lines (like Python's
+/// `textwrap.dedent`), e.g. when a whole answer is indented by 4 extra spaces.
+///
+/// Only identical prefixes are removed: a tab and four spaces have nothing in common.
+/// Whitespace-only lines become empty.
+#[pyfunction]
+pub fn normalize_indentation(code: &str) -> String {
+    let common = code
+        .split('\n')
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(common_prefix)
+        .unwrap_or("");
+    if common.is_empty() {
+        return code.to_string();
     }
 
-    completion.trim().to_string()
+    code.split('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[common.len()..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Longest common prefix of two strings.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .take_while(|((_, x), y)| x == y)
+        .last()
+        .map_or(0, |((i, c), _)| i + c.len_utf8());
+    &a[..len]
+}
+
+/// `text` without leading blank lines and trailing whitespace, keeping the indentation of
+/// its first line (which [`normalize_indentation`] needs to see).
+fn trim_keep_indent(text: &str) -> &str {
+    let text = text.trim_end();
+    let first_char = text.len() - text.trim_start().len();
+    let line_start = text[..first_char]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    &text[line_start..]
+}
+
+/// Code of an `<answer>` block: markdown fences stripped, HTML entities unescaped and
+/// common indentation removed.
+fn answer_code(content: &str) -> String {
+    let content = trim_keep_indent(content);
+    let unindented = content.trim_start();
+    if MARKDOWN_START.is_match(unindented) {
+        normalize_indentation(&clean_answer(unindented))
+    } else {
+        normalize_indentation(&clean_answer(content))
+    }
+}
+
+/// Content of the first `<think>...</think>` block (trimmed), or `None` without one.
+///
+/// For rewards on the reasoning itself, e.g. its length or whether it discusses complexity.
+#[pyfunction]
+pub fn extract_think_block(completion: &str) -> Option<String> {
+    THINK_PATTERN
+        .captures(completion)
+        .map(|captures| captures[1].trim().to_string())
+}
+
+/// Content of the `<answer>...</answer>` block outside `<think>` blocks (trimmed), or `None`
+/// without one.
+///
+/// Unlike [`extract_code_from_completion`], markdown fences and HTML entities are kept.
+#[pyfunction]
+pub fn extract_answer_block(completion: &str) -> Option<String> {
+    let outside_think = THINK_PATTERN.replace_all(completion, "");
+    ANSWER_PATTERN
+        .captures(&outside_think)
+        .map(|captures| captures[2].trim().to_string())
+}
+
+/// Extract code like [`extract_code_from_completion`] and detect its language.
+///
+/// The language comes from the `<answer lang="...">` attribute, else from the markdown
+/// fence tag. Without answer tags, the first ```` ```python ```` block outside `<think>`
+/// blocks is preferred, then the first fenced block of any language.
+pub fn extract_code_with_language(completion: &str) -> (String, Language) {
+    let outside_think = THINK_PATTERN.replace_all(completion, "");
+
+    if let Some(captures) = ANSWER_PATTERN.captures(&outside_think) {
+        let content = captures[2].trim();
+        let tag = match captures.get(1) {
+            Some(lang) if !lang.as_str().is_empty() => lang.as_str(),
+            _ => MARKDOWN_START
+                .captures(content)
+                .and_then(|fence| fence.get(1))
+                .map_or("", |fence_tag| fence_tag.as_str()),
+        };
+        return (answer_code(&captures[2]), Language::from_tag(tag));
+    }
+
+    if let Some(captures) = CODE_BLOCK_PATTERN.captures(&outside_think) {
+        return (
+            normalize_indentation(trim_keep_indent(&captures[1])),
+            Language::Python,
+        );
+    }
+
+    if let Some(captures) = ANY_CODE_BLOCK_PATTERN.captures(&outside_think) {
+        return (
+            normalize_indentation(trim_keep_indent(&captures[2])),
+            Language::from_tag(&captures[1]),
+        );
+    }
+
+    (
+        normalize_indentation(trim_keep_indent(completion)),
+        Language::Unknown,
+    )
+}
+
+/// Python wrapper for [`extract_code_with_language`].
+///
+/// Returns `(code, language)` with the language as a lowercase string (`"python"`,
+/// `"javascript"`, `"java"`, `"rust"`, any other tag, or `"unknown"`).
+#[pyfunction]
+#[pyo3(name = "extract_code_with_language")]
+pub fn py_extract_code_with_language(completion: &str) -> (String, String) {
+    let (code, language) = extract_code_with_language(completion);
+    (code, language.to_string())
+}
+
+/// Extract code like [`extract_code_from_completion`], along with the 1-based first and last
+/// line numbers of that code within `completion` (for debugging extraction issues).
+///
+/// Line numbers refer to the original string: markdown fences and `<think>` blocks before
+/// the answer are accounted for. Unescaped HTML entities do not change line numbers.
+#[pyfunction]
+pub fn extract_code_with_location(completion: &str) -> (String, usize, usize) {
+    let span = code_span(completion);
+    let line_at = |offset: usize| completion[..offset].matches('\n').count() + 1;
+    let end_line = if span.is_empty() {
+        line_at(span.start)
+    } else {
+        line_at(span.end - 1)
+    };
+    (
+        extract_code_from_completion(completion),
+        line_at(span.start),
+        end_line,
+    )
+}
+
+/// Byte range within `completion` of the code that [`extract_code_from_completion`] returns.
+fn code_span(completion: &str) -> Range<usize> {
+    let think_blocks: Vec<Range<usize>> = THINK_PATTERN
+        .find_iter(completion)
+        .map(|block| block.range())
+        .collect();
+    let outside_think = THINK_PATTERN.replace_all(completion, "");
+    // Map the first and last code characters back past the removed `<think>` blocks
+    let to_original = |span: Range<usize>| {
+        if span.is_empty() {
+            let start = offset_before_removal(&think_blocks, span.start);
+            return start..start;
+        }
+        offset_before_removal(&think_blocks, span.start)
+            ..offset_before_removal(&think_blocks, span.end - 1) + 1
+    };
+
+    if let Some(content) = ANSWER_PATTERN
+        .captures(&outside_think)
+        .and_then(|captures| captures.get(2))
+    {
+        let mut span = trimmed_span(&outside_think, content.range());
+        if let Some(fence) = MARKDOWN_START.find(&outside_think[span.clone()]) {
+            span.start += fence.end();
+        }
+        if let Some(fence) = MARKDOWN_END.find(&outside_think[span.clone()]) {
+            span.end = span.start + fence.start();
+        }
+        return to_original(span);
+    }
+
+    if let Some(code) = CODE_BLOCK_PATTERN
+        .captures(&outside_think)
+        .and_then(|captures| captures.get(1))
+    {
+        return to_original(trimmed_span(&outside_think, code.range()));
+    }
+
+    trimmed_span(completion, 0..completion.len())
+}
+
+/// `range` of `text` without its leading and trailing whitespace.
+fn trimmed_span(text: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+    start..end.max(start)
+}
+
+/// Offset in the original string of `offset` in the string with the `removed` ranges
+/// (sorted, non-overlapping, in original coordinates) cut out.
+fn offset_before_removal(removed: &[Range<usize>], offset: usize) -> usize {
+    removed.iter().fold(offset, |offset, range| {
+        if range.start <= offset {
+            offset + range.len()
+        } else {
+            offset
+        }
+    })
+}
+
+/// Extract code from a completion returned as a raw JSON API response.
+///
+/// The completion text is taken from the first choice of a chat completion
+/// (`{"choices": [{"message": {"content": "..."}}]}`) or text completion
+/// (`{"choices": [{"text": "..."}]}`), a top-level `"text"`, or a top-level `"content"`
+/// (string or list of `{"type": "text", "text": "..."}` blocks), then passed through
+/// [`extract_code_from_completion`]. Input that is not JSON, or JSON without completion
+/// text, is treated as a plain completion.
+#[pyfunction]
+pub fn extract_code_from_json_completion(json_str: &str) -> String {
+    let text = serde_json::from_str::<serde_json::Value>(json_str)
+        .ok()
+        .and_then(|response| json_completion_text(&response));
+    extract_code_from_completion(text.as_deref().unwrap_or(json_str))
+}
+
+/// Completion text of a JSON API response (see [`extract_code_from_json_completion`]).
+fn json_completion_text(response: &serde_json::Value) -> Option<String> {
+    if let Some(choice) = response.get("choices").and_then(|choices| choices.get(0)) {
+        return choice
+            .get("message")
+            .and_then(|message| message.get("content"))
+            .and_then(content_text)
+            .or_else(|| choice.get("text").and_then(content_text));
+    }
+    response
+        .get("content")
+        .or_else(|| response.get("text"))
+        .and_then(content_text)
+}
+
+/// A string, or the concatenated `"text"` fields of a list of content blocks.
+fn content_text(content: &serde_json::Value) -> Option<String> {
+    match content {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(blocks) => Some(
+            blocks
+                .iter()
+                .filter_map(|block| block.get("text").and_then(|text| text.as_str()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Strip markdown fences from answer-tag content and unescape HTML entities if needed.
+fn clean_answer(content: &str) -> String {
+    let code = MARKDOWN_START.replace(content, "");
+    let code = MARKDOWN_END.replace(&code, "");
+
+    // Only pay for unescaping when the content looks HTML-escaped
+    if HTML_ENTITIES
+        .iter()
+        .any(|(entity, _)| code.contains(entity))
+    {
+        return html_unescape(&code);
+    }
+
+    code.into_owned()
+}
+
+/// Replace the HTML entities in [`HTML_ENTITIES`] with the characters they encode.
+fn html_unescape(text: &str) -> String {
+    HTML_ENTITIES
+        .iter()
+        .fold(text.to_string(), |acc, (entity, replacement)| {
+            acc.replace(entity, replacement)
+        })
+}
+
+/// Count the lines of code, ignoring blank lines and comment lines (starting with `#`).
+pub fn count_code_lines(code: &str) -> usize {
+    code.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .count()
+}
+
+/// Check whether `code` only defines placeholder functions, e.g. `def add(a, b): pass`.
+///
+/// True if the code defines at least one function and every function body (after an
+/// optional docstring) consists only of `pass`, `...`, `return None` or `return`.
+/// Functions with multi-line signatures are treated as implemented, so ambiguous code is
+/// never reported as a stub.
+pub fn is_stub_code(code: &str) -> bool {
+    let lines: Vec<&str> = code.lines().collect();
+    let mut found_function = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = FUNCTION_HEADER_PATTERN.captures(line) else {
+            if line.trim_start().starts_with("def ") || line.trim_start().starts_with("async def ")
+            {
+                return false; // Multi-line signature
+            }
+            continue;
+        };
+        found_function = true;
+
+        let indent = caps[1].len();
+        let inline_body = caps[2].split('#').next().unwrap_or_default().trim();
+        let body = if inline_body.is_empty() {
+            lines[i + 1..]
+                .iter()
+                .take_while(|body_line| {
+                    body_line.trim().is_empty()
+                        || body_line.len() - body_line.trim_start().len() > indent
+                })
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            inline_body.to_string()
+        };
+
+        let statements = DOCSTRING_PATTERN.replace(&body, "");
+        let is_stub = statements
+            .lines()
+            .flat_map(|line| line.split('#').next().unwrap_or_default().split(';'))
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .all(|statement| STUB_STATEMENTS.contains(&statement));
+        if !is_stub {
+            return false;
+        }
+    }
+
+    found_function
+}
+
+/// Approximate cyclomatic complexity: 1 plus the number of branch keywords
+/// (`if`, `elif`, `for`, `while`, `except`, `and`, `or`).
+///
+/// This is a keyword count rather than an AST analysis: comments are skipped, but keywords
+/// inside string literals are counted.
+pub fn cyclomatic_complexity(code: &str) -> u32 {
+    let branches: usize = code
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .map(|line| BRANCH_KEYWORD_PATTERN.find_iter(line).count())
+        .sum();
+    1 + branches as u32
+}
+
+/// Check whether any function in `code` has a return annotation (`-> int`) or an
+/// annotated parameter (`x: int`).
+///
+/// Only single-line signatures are inspected.
+pub fn has_type_hints(code: &str) -> bool {
+    code.lines()
+        .filter_map(|line| SIGNATURE_PATTERN.captures(line))
+        .any(|caps| {
+            caps.get(2).is_some()
+                || caps[1]
+                    .split(',')
+                    .any(|param| ANNOTATED_PARAMETER_PATTERN.is_match(param))
+        })
+}
+
+/// Check whether `code` has a triple-quoted docstring, either at the start of the module or
+/// right after a `def` or `class` header (including the last line of a multi-line signature).
+pub fn has_docstring(code: &str) -> bool {
+    let mut previous: Option<&str> = None;
+    for line in code.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if TRIPLE_QUOTE_START.is_match(line) {
+            let after_header = previous.is_some_and(|header| {
+                header.ends_with(':')
+                    && (header.starts_with("def ")
+                        || header.starts_with("async def ")
+                        || header.starts_with("class ")
+                        || header.starts_with(')'))
+            });
+            if previous.is_none() || after_header {
+                return true;
+            }
+        }
+        previous = Some(line);
+    }
+    false
+}
+
+/// Check whether `code` has a `#` comment (a shebang line does not count).
+///
+/// Strings are tracked within a line only: a `#` inside a multi-line string counts.
+pub fn has_comments(code: &str) -> bool {
+    code.lines()
+        .filter(|line| !line.starts_with("#!"))
+        .any(|line| {
+            let mut quote = None;
+            let mut escaped = false;
+            for c in line.chars() {
+                match (quote, c) {
+                    (Some(_), _) if escaped => escaped = false,
+                    (Some(_), '\\') => escaped = true,
+                    (Some(open), c) if c == open => quote = None,
+                    (None, '"' | '\'') => quote = Some(c),
+                    (None, '#') => return true,
+                    _ => {}
+                }
+            }
+            false
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_match_in_any_case() {
+        for (think, answer) in [
+            ("think", "answer"),
+            ("THINK", "ANSWER"),
+            ("Think", "AnSwEr"),
+        ] {
+            let draft = format!("draft: <{answer}>x = 1</{answer}>");
+            let completion = format!("<{think}>{draft}</{think}>\n<{answer}>x = 2</{answer}>");
+            assert_eq!(extract_code_from_completion(&completion), "x = 2");
+            assert_eq!(extract_think_block(&completion), Some(draft));
+            assert_eq!(extract_answer_block(&completion).as_deref(), Some("x = 2"));
+        }
+    }
+
+    #[test]
+    fn python_fences_match_in_any_case() {
+        for fence in ["python", "PYTHON", "Python"] {
+            let in_answer = format!("<answer>```{fence}\nprint(1)\n```</answer>");
+            assert_eq!(extract_code_from_completion(&in_answer), "print(1)");
+            assert_eq!(
+                extract_code_with_language(&in_answer),
+                ("print(1)".to_string(), Language::Python)
+            );
+
+            let fallback = format!("Here you go:\n```{fence}\nprint(1)\n```\nDone.");
+            assert_eq!(extract_code_from_completion(&fallback), "print(1)");
+            assert_eq!(
+                extract_code_with_language(&fallback),
+                ("print(1)".to_string(), Language::Python)
+            );
+        }
+    }
+
+    #[test]
+    fn placeholder_bodies_are_stubs() {
+        assert!(is_stub_code("def add(a, b): pass"));
+        assert!(is_stub_code("def add(a, b): ...  # TODO"));
+        assert!(is_stub_code("async def fetch(url):\n    return None"));
+        assert!(is_stub_code(
+            "def add(a: int, b: int) -> int:\n    '''Add.'''\n\n    pass; ..."
+        ));
+        // Methods of a class count like any other function
+        assert!(is_stub_code(
+            "class Stack:\n    def push(self, x):\n        pass\n\n    def pop(self):\n        ..."
+        ));
+    }
+
+    #[test]
+    fn real_bodies_are_not_stubs() {
+        assert!(!is_stub_code("def add(a, b): return a + b"));
+        assert!(!is_stub_code(
+            "def add(a, b):\n    # pass\n    return a + b"
+        ));
+        assert!(!is_stub_code(
+            "class Stack:\n    def push(self, x):\n        self.items.append(x)\n\n    def pop(self):\n        pass"
+        ));
+        assert!(!is_stub_code("")); // No function at all
+    }
+
+    #[test]
+    fn html_escaped_operators_are_unescaped() {
+        let completion =
+            "<answer>def is_small(x):\n    return 0 &lt; x &lt;= 10 &amp;&amp; x != 5</answer>";
+        assert_eq!(
+            extract_code_from_completion(completion),
+            "def is_small(x):\n    return 0 < x <= 10 && x != 5"
+        );
+        assert_eq!(
+            extract_code_from_completion("<answer>print(2 &gt; 1)</answer>"),
+            "print(2 > 1)"
+        );
+        assert_eq!(
+            extract_code_from_completion("<answer>print(&quot;a&quot;, &#39;b&#39;)</answer>"),
+            "print(\"a\", 'b')"
+        );
+    }
+
+    #[test]
+    fn answers_nested_in_think_are_drafts() {
+        let completion = "<think>here is my answer: <answer>def add(a, b): pass</answer></think>\
+                          <answer>def add(a, b): return a + b</answer>";
+        assert_eq!(
+            extract_code_from_completion(completion),
+            "def add(a, b): return a + b"
+        );
+        assert_eq!(
+            extract_answer_block(completion).as_deref(),
+            Some("def add(a, b): return a + b")
+        );
+
+        // A draft answer alone is not an answer
+        let draft_only = "<think><answer>x = 1</answer></think>\n```python\nx = 2\n```";
+        assert_eq!(extract_code_from_completion(draft_only), "x = 2");
+        assert_eq!(extract_answer_block(draft_only), None);
+    }
+
+    #[test]
+    fn fenced_drafts_in_think_are_skipped() {
+        let completion = "<think>Draft:\n```python\ndef add(a, b):\n    pass\n```\n</think>\n\
+                          ```python\ndef add(a, b):\n    return a + b\n```";
+        let code = "def add(a, b):\n    return a + b";
+        assert_eq!(extract_code_from_completion(completion), code);
+        assert_eq!(
+            extract_code_with_language(completion),
+            (code.to_string(), Language::Python)
+        );
+        assert_eq!(
+            extract_code_with_location(completion),
+            (code.to_string(), 8, 9)
+        );
+
+        let rust = "<think>```python\nprint(1)\n```</think>\n```rust\nfn main() {}\n```";
+        assert_eq!(
+            extract_code_with_language(rust),
+            ("fn main() {}".to_string(), Language::Rust)
+        );
+    }
+
+    #[test]
+    fn html_entities_are_unescaped_once() {
+        // `&amp;lt;` is the escaped text `&lt;`, not `<`
+        assert_eq!(html_unescape("s = '&amp;lt;'"), "s = '&lt;'");
+        assert_eq!(html_unescape("a &lt; b"), "a < b");
+        assert_eq!(html_unescape("no entities"), "no entities");
+    }
 }
@@ -23,14 +23,29 @@
 //! - [`bindings`]: PyO3 Python interface
 //! - [`evaluator`]: Core evaluation logic with Rayon parallelism
 //! - [`extraction`]: Code extraction from structured responses
+//! - [`linting`]: ruff/flake8 style checking of extracted code
+//! - [`logging`]: Structured logging setup (`logging` feature)
+//! - [`metrics`]: Pass@K from already evaluated rewards
+//! - [`output_parser`]: Parsing of `TESTS_PASSED:X/Y` test output
 //! - [`test_wrapper`]: Test transformation for run-all-tests mode
+//! - [`registry`]: Named reward function registry
 //! - [`sandbox`]: Firejail sandboxed execution
+//! - [`sandbox_python`]: Execution under a Python subprocess manager (no Firejail)
+//! - [`type_check`]: mypy type checking of extracted code
 
 mod bindings;
-mod evaluator;
-mod extraction;
-mod sandbox;
-mod test_wrapper;
+pub mod evaluator;
+pub mod extraction;
+pub mod linting;
+#[cfg(feature = "logging")]
+mod logging;
+pub mod metrics;
+pub mod output_parser;
+pub mod registry;
+pub mod sandbox;
+pub mod sandbox_python;
+pub mod test_wrapper;
+pub mod type_check;
 
 use pyo3::prelude::*;
 
@@ -38,20 +53,91 @@ use pyo3::prelude::*;
 fn fastrlrewards(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Main evaluator class
     m.add_class::<bindings::PyRewardEvaluator>()?;
+    m.add_class::<bindings::PyEvaluatorConfig>()?;
+    m.add_class::<bindings::PyRewardRegistry>()?;
+    m.add_class::<bindings::PyExecutionRewardStream>()?;
 
     // Convenience functions (module-level API using default PyRewardEvaluator)
     m.add_function(wrap_pyfunction!(bindings::format_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::format_reward_single, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::regex_format_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::complexity_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::type_check_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::linting_reward, m)?)?;
     m.add_function(wrap_pyfunction!(bindings::execution_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::execution_reward_single, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::execution_reward_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::filter_valid_completions, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::execution_reward_callable, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::execution_reward_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::code_quality_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        bindings::execution_reward_batch_of_lists,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(bindings::output_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::output_match_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::combined_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::pass_at_k, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::best_of_n_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::mean_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::majority_reward, m)?)?;
+    m.add_function(wrap_pyfunction!(bindings::evaluation_summary_dict, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        bindings::evaluation_summary_detailed_dict,
+        m
+    )?)?;
 
     // Utility functions
     m.add_function(wrap_pyfunction!(
         extraction::extract_code_from_completion,
         m
     )?)?;
+    m.add_function(wrap_pyfunction!(
+        extraction::py_extract_code_with_language,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        extraction::extract_code_from_json_completion,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(extraction::extract_code_with_location, m)?)?;
+    m.add_function(wrap_pyfunction!(extraction::extract_think_block, m)?)?;
+    m.add_function(wrap_pyfunction!(extraction::extract_answer_block, m)?)?;
+    m.add_function(wrap_pyfunction!(extraction::normalize_indentation, m)?)?;
     m.add_function(wrap_pyfunction!(
         test_wrapper::wrap_tests_for_complete_execution,
         m
     )?)?;
+    m.add_function(wrap_pyfunction!(
+        test_wrapper::wrap_tests_with_timeout_per_assertion,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        test_wrapper::wrap_rust_tests_for_complete_execution,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(test_wrapper::py_validate_test_code, m)?)?;
+    m.add_function(wrap_pyfunction!(test_wrapper::count_assertions, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        test_wrapper::py_estimate_test_complexity,
+        m
+    )?)?;
     m.add_function(wrap_pyfunction!(sandbox::run_sandboxed_tests, m)?)?;
+    m.add_function(wrap_pyfunction!(sandbox::is_sandbox_available, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        sandbox::execute_multi_file_with_tests_firejail,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(metrics::py_compute_pass_at_k, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        metrics::py_compute_pass_at_k_from_flat,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(output_parser::parse_test_output, m)?)?;
+    m.add_function(wrap_pyfunction!(output_parser::parse_exception_type, m)?)?;
+
+    #[cfg(feature = "logging")]
+    m.add_function(wrap_pyfunction!(logging::init_logging, m)?)?;
     Ok(())
 }
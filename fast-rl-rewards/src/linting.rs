@@ -0,0 +1,136 @@
+//! src/linting.rs
+//!
+//! Code style checking of extracted code with ruff or flake8.
+//!
+//! Like mypy in [`crate::type_check`], the linter only parses the code, so it runs as a
+//! plain subprocess (not in the sandbox). The configured tool must be installed and on `PATH`:
+//! ```bash
+//! pip install ruff  # or: pip install flake8
+//! ```
+
+use crate::sandbox::wait_with_output;
+use anyhow::{Context, Result, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fmt;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// One diagnostic per line: `<file>:<line>:<column>: <code> <message>`
+static DIAGNOSTIC_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\S*:\d+:\d+: [A-Z]+\d+").unwrap());
+
+/// Linter used by [`crate::evaluator::RewardEvaluator::evaluate_linting_batch`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LintingTool {
+    /// `ruff check --select E,F`
+    #[default]
+    Ruff,
+    /// `flake8 --select E,F`
+    Flake8,
+    /// No linting: every non-empty completion scores 1.0.
+    None,
+}
+
+impl LintingTool {
+    /// Build the command that lints code read from stdin (`None` for [`LintingTool::None`]).
+    fn command(self) -> Option<Command> {
+        let mut cmd = match self {
+            Self::Ruff => {
+                let mut cmd = Command::new("ruff");
+                cmd.args(["check", "--select", "E,F", "--no-cache", "--quiet"])
+                    .args(["--output-format", "concise", "-"]);
+                cmd
+            }
+            Self::Flake8 => {
+                let mut cmd = Command::new("flake8");
+                cmd.args(["--select", "E,F", "-"]);
+                cmd
+            }
+            Self::None => return None,
+        };
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        Some(cmd)
+    }
+}
+
+impl FromStr for LintingTool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ruff" => Ok(Self::Ruff),
+            "flake8" => Ok(Self::Flake8),
+            "none" => Ok(Self::None),
+            other => Err(format!(
+                "unknown linting tool '{}' (expected 'ruff', 'flake8' or 'none')",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LintingTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Ruff => "ruff",
+            Self::Flake8 => "flake8",
+            Self::None => "none",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Count the style errors (pycodestyle `E` and pyflakes `F` rules) that `tool` reports
+/// for `code`.
+///
+/// # Returns
+/// `Ok(Some(count))`, or `Ok(None)` if the linter exceeds `timeout`. Always `Ok(Some(0))`
+/// for [`LintingTool::None`].
+///
+/// Returns `Err` if the linter cannot be started or fails (exit code other than 0 or 1).
+pub fn count_lint_errors(
+    code: &str,
+    tool: LintingTool,
+    timeout: Duration,
+) -> Result<Option<usize>> {
+    let Some(mut command) = tool.command() else {
+        return Ok(Some(0));
+    };
+
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to spawn {}. Is {} installed?", tool, tool))?;
+
+    // Feed the code in a background thread (dropping the pipe afterwards signals EOF)
+    if let Some(mut stdin) = child.stdin.take() {
+        let code = code.to_string();
+        std::thread::spawn(move || {
+            std::io::Write::write_all(&mut stdin, code.as_bytes()).ok();
+        });
+    }
+
+    let Some(output) = wait_with_output(&mut child, timeout)
+        .with_context(|| format!("Error waiting for {}", tool))?
+    else {
+        // Timeout exceeded - kill the process
+        let _ = child.kill();
+        let _ = child.wait();
+        return Ok(None);
+    };
+
+    // Both linters exit with 1 when they report errors
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(DIAGNOSTIC_PATTERN.find_iter(&stdout).count()))
+}
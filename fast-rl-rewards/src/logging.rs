@@ -0,0 +1,39 @@
+//! src/logging.rs
+//!
+//! Structured logging setup.
+//!
+//! All diagnostics in this crate are emitted through the `tracing` macros and are dropped
+//! until a subscriber is installed. [`init_logging`] installs one that writes JSON lines to
+//! stderr, which can be redirected to a file or parsed by a Python `logging` bridge.
+//!
+//! # Examples
+//! ```python
+//! import fastrlrewards
+//!
+//! fastrlrewards.init_logging("debug")
+//! ```
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+/// Install a global JSON subscriber writing to stderr.
+///
+/// # Arguments:
+/// - `level`: Log level or filter directive (e.g., "warn", "debug", "fastrlrewards=trace")
+///
+/// # Errors
+/// Raises `ValueError` for an invalid level and `RuntimeError` if logging was already initialized.
+#[pyfunction]
+#[pyo3(signature = (level="info"))]
+pub fn init_logging(level: &str) -> PyResult<()> {
+    let filter = EnvFilter::try_new(level)
+        .map_err(|e| PyValueError::new_err(format!("Invalid log level '{}': {}", level, e)))?;
+
+    tracing_subscriber::fmt()
+        .json()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|e| PyRuntimeError::new_err(format!("Logging already initialized: {}", e)))
+}
@@ -0,0 +1,154 @@
+//! src/metrics.rs
+//!
+//! Evaluation metrics computed from rewards that have already been evaluated.
+//!
+//! [`RewardEvaluator::batch_evaluate_pass_at_k`](crate::evaluator::RewardEvaluator::batch_evaluate_pass_at_k)
+//! runs the completions itself; these functions only need the per-completion rewards (e.g.
+//! from `execution_reward`), grouped by problem. A completion counts as correct when its
+//! reward is 1.0 (all tests passed).
+//!
+//! # Examples
+//! ```python
+//! import fastrlrewards
+//!
+//! # Two problems with 4 samples each
+//! rewards = fastrlrewards.execution_reward(completions, test=tests, entry_point=entry_points)
+//! fastrlrewards.compute_pass_at_k_from_flat(rewards, n_samples_per_problem=4, k=1)
+//! # [0.5, 0.0]
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Unbiased Pass@K estimator `1 - C(n-c, k) / C(n, k)` for `n` samples with `c` correct.
+///
+/// The ratio of binomial coefficients equals `prod_{i=n-c+1}^{n} (1 - k / i)`, which is
+/// summed in log space rather than computed from the coefficients, which overflow for
+/// large `n`.
+pub fn pass_at_k_estimate(n: usize, c: usize, k: usize) -> f64 {
+    if n - c < k {
+        return 1.0;
+    }
+
+    let log_failure_probability: f64 = ((n - c + 1)..=n)
+        .map(|i| (-(k as f64) / i as f64).ln_1p())
+        .sum();
+
+    1.0 - log_failure_probability.exp()
+}
+
+/// Pass@K of each problem from the rewards of its samples.
+///
+/// # Returns
+/// One Pass@K value in `[0.0, 1.0]` per problem
+///
+/// # Panics
+/// Panics if `k` is 0 or if a problem has fewer than `k` samples.
+pub fn compute_pass_at_k(rewards_per_problem: &[Vec<f64>], k: usize) -> Vec<f64> {
+    assert!(k > 0, "k must be at least 1");
+
+    rewards_per_problem
+        .iter()
+        .map(|rewards| {
+            assert!(
+                rewards.len() >= k,
+                "Each problem needs at least k={} samples, got {}",
+                k,
+                rewards.len()
+            );
+            let num_correct = rewards.iter().filter(|&&reward| reward == 1.0).count();
+            pass_at_k_estimate(rewards.len(), num_correct, k)
+        })
+        .collect()
+}
+
+/// [`compute_pass_at_k`] for a flat list of rewards with `n_samples_per_problem`
+/// consecutive samples per problem.
+///
+/// # Panics
+/// Panics if `k` is 0, if `n_samples_per_problem` is less than `k`, or if the number of
+/// rewards is not a multiple of `n_samples_per_problem`.
+pub fn compute_pass_at_k_from_flat(
+    rewards: &[f64],
+    n_samples_per_problem: usize,
+    k: usize,
+) -> Vec<f64> {
+    assert!(k > 0, "k must be at least 1");
+    assert!(
+        n_samples_per_problem >= k,
+        "Each problem needs at least k={} samples, got {}",
+        k,
+        n_samples_per_problem
+    );
+    assert!(
+        rewards.len().is_multiple_of(n_samples_per_problem),
+        "{} rewards cannot be split into problems of {} samples",
+        rewards.len(),
+        n_samples_per_problem
+    );
+
+    rewards
+        .chunks(n_samples_per_problem)
+        .map(|rewards| {
+            let num_correct = rewards.iter().filter(|&&reward| reward == 1.0).count();
+            pass_at_k_estimate(n_samples_per_problem, num_correct, k)
+        })
+        .collect()
+}
+
+/// Python wrapper for [`compute_pass_at_k`].
+///
+/// Raises `ValueError` if `k` is 0 or if a problem has fewer than `k` samples.
+#[pyfunction]
+#[pyo3(name = "compute_pass_at_k")]
+pub fn py_compute_pass_at_k(rewards_per_problem: Vec<Vec<f64>>, k: usize) -> PyResult<Vec<f64>> {
+    check_k(k)?;
+    if let Some(rewards) = rewards_per_problem.iter().find(|rewards| rewards.len() < k) {
+        return Err(PyValueError::new_err(format!(
+            "Each problem needs at least k={} samples, got {}",
+            k,
+            rewards.len()
+        )));
+    }
+    Ok(compute_pass_at_k(&rewards_per_problem, k))
+}
+
+/// Python wrapper for [`compute_pass_at_k_from_flat`].
+///
+/// Raises `ValueError` if `k` is 0, if `n_samples_per_problem` is less than `k`, or if the
+/// number of rewards is not a multiple of `n_samples_per_problem`.
+#[pyfunction]
+#[pyo3(name = "compute_pass_at_k_from_flat")]
+pub fn py_compute_pass_at_k_from_flat(
+    rewards: Vec<f64>,
+    n_samples_per_problem: usize,
+    k: usize,
+) -> PyResult<Vec<f64>> {
+    check_k(k)?;
+    if n_samples_per_problem < k {
+        return Err(PyValueError::new_err(format!(
+            "Each problem needs at least k={} samples, got {}",
+            k, n_samples_per_problem
+        )));
+    }
+    if !rewards.len().is_multiple_of(n_samples_per_problem) {
+        return Err(PyValueError::new_err(format!(
+            "{} rewards cannot be split into problems of {} samples",
+            rewards.len(),
+            n_samples_per_problem
+        )));
+    }
+    Ok(compute_pass_at_k_from_flat(
+        &rewards,
+        n_samples_per_problem,
+        k,
+    ))
+}
+
+/// Reject `k = 0`, for which Pass@K is undefined.
+fn check_k(k: usize) -> PyResult<()> {
+    if k == 0 {
+        return Err(PyValueError::new_err("k must be at least 1"));
+    }
+    Ok(())
+}
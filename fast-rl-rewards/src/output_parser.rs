@@ -0,0 +1,62 @@
+//! src/output_parser.rs
+//!
+//! Parsing of test output produced by wrapped test code.
+//!
+//! Code wrapped by [`wrap_tests_for_complete_execution`](crate::test_wrapper) reports its
+//! results on stdout with a single summary line:
+//!
+//! ```text
+//! TESTS_PASSED:1/2
+//! ```
+//!
+//! These helpers are exposed to Python so that custom pipelines which run the code themselves
+//! can reuse the same parsing.
+//!
+//! # Examples
+//! ```python
+//! import fastrlrewards
+//!
+//! fastrlrewards.parse_test_output("TESTS_PASSED:3/4")  # (3, 4)
+//! fastrlrewards.parse_test_output("Traceback ...")      # None
+//! fastrlrewards.parse_exception_type("Traceback ...\nNameError: name 'x' is not defined")
+//! # "NameError"
+//! ```
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+
+/// Regex pattern to extract the test summary line from output
+static TEST_RESULTS_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"TESTS_PASSED:(\d+)/(\d+)").unwrap());
+
+/// Regex pattern for the exception line that ends a Python traceback
+/// (e.g. `NameError: name 'x' is not defined`, `json.decoder.JSONDecodeError: ...`)
+static EXCEPTION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:[\w.]+\.)?(\w*(?:Error|Exception))(?::\s|$)").unwrap());
+
+/// Parse the `TESTS_PASSED:X/Y` summary line from test output.
+///
+/// # Returns
+/// `Some((tests_passed, tests_total))` if a summary line is found, `None` otherwise.
+#[pyfunction]
+pub fn parse_test_output(stdout: &str) -> Option<(i32, i32)> {
+    TEST_RESULTS_PATTERN.captures(stdout).map(|caps| {
+        let passed = caps[1].parse::<i32>().unwrap_or(0);
+        let total = caps[2].parse::<i32>().unwrap_or(0);
+        (passed, total)
+    })
+}
+
+/// Extract the exception class name (without module) from the last non-empty line of stderr.
+///
+/// # Returns
+/// `Some("NameError")` for a traceback ending in `NameError: name 'x' is not defined`,
+/// `None` if the last line does not name an exception.
+#[pyfunction]
+pub fn parse_exception_type(stderr: &str) -> Option<String> {
+    let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty())?;
+    EXCEPTION_PATTERN
+        .captures(last_line.trim_end())
+        .map(|caps| caps[1].to_string())
+}
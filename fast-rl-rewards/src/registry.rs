@@ -0,0 +1,245 @@
+//! src/registry.rs
+//!
+//! Named reward functions.
+//!
+//! A [`RewardRegistry`] maps names to [`RewardFn`] implementations so that reward
+//! functions can be selected by string (e.g., from a training config). The built-in
+//! `"format"`, `"execution"` and `"output"` rewards are pre-registered.
+//!
+//! Python callables can be registered as user-defined rewards (UDFs). They are invoked from
+//! the Rayon worker threads, acquiring the GIL only for the duration of each call.
+//!
+//! # Examples
+//! ```python
+//! from fastrlrewards import RewardRegistry
+//!
+//! registry = RewardRegistry()
+//! registry.register("short", lambda completion, context: float(len(completion) < 500))
+//! scores = registry.evaluate("execution", completions, test=tests, entry_point=entry_points)
+//! ```
+
+use crate::evaluator::{EvaluatorConfig, RewardEvaluator};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+// ==========================================================================================
+
+/// Per-completion inputs available to a reward function.
+#[derive(Clone, Copy)]
+pub struct RewardContext<'a> {
+    /// Position of the completion in the batch.
+    pub completion_idx: usize,
+    /// Test code for the completion (`None` if missing).
+    pub test: Option<&'a str>,
+    /// Entry point for the completion (`None` if missing).
+    pub entry_point: Option<&'a str>,
+    /// Expected stdout for the completion (may be empty).
+    pub expected_output: &'a str,
+    /// Evaluator running the reward (provides sandboxing and configuration).
+    pub evaluator: &'a RewardEvaluator,
+}
+
+impl RewardContext<'_> {
+    /// Configuration of the evaluator running the reward.
+    pub fn config(&self) -> &EvaluatorConfig {
+        self.evaluator.config()
+    }
+}
+
+/// A reward function scoring a single completion.
+pub trait RewardFn: Send + Sync {
+    fn evaluate(&self, completion: &str, context: &RewardContext) -> f64;
+}
+
+/// Built-in `"format"` reward (see [`RewardEvaluator::evaluate_format_batch`]).
+struct FormatReward;
+
+impl RewardFn for FormatReward {
+    fn evaluate(&self, completion: &str, _context: &RewardContext) -> f64 {
+        if RewardEvaluator::has_valid_format(completion) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Built-in `"execution"` reward (see [`RewardEvaluator::evaluate_execution_batch`]).
+struct ExecutionReward;
+
+impl RewardFn for ExecutionReward {
+    fn evaluate(&self, completion: &str, context: &RewardContext) -> f64 {
+        context.evaluator.evaluate_single_execution(
+            context.completion_idx,
+            completion,
+            context.test,
+            context.entry_point,
+        )
+    }
+}
+
+/// Built-in `"output"` reward (see [`RewardEvaluator::evaluate_output_batch`]).
+struct OutputReward;
+
+impl RewardFn for OutputReward {
+    fn evaluate(&self, completion: &str, context: &RewardContext) -> f64 {
+        context.evaluator.evaluate_single_output(
+            context.completion_idx,
+            completion,
+            None,
+            context.expected_output,
+            context.config().output_normalize_whitespace,
+        )
+    }
+}
+
+/// Reward function backed by a Python callable `f(completion: str, context: dict) -> float`.
+///
+/// `context` has the keys `test`, `entry_point` and `expected_output`. Exceptions and
+/// non-float return values are logged and scored as 0.0.
+pub struct PythonRewardFn {
+    callable: Py<PyAny>,
+}
+
+impl PythonRewardFn {
+    pub fn new(callable: Py<PyAny>) -> Self {
+        Self { callable }
+    }
+
+    fn call(&self, py: Python<'_>, completion: &str, context: &RewardContext) -> PyResult<f64> {
+        let context_dict = PyDict::new(py);
+        context_dict.set_item("test", context.test)?;
+        context_dict.set_item("entry_point", context.entry_point)?;
+        context_dict.set_item("expected_output", context.expected_output)?;
+
+        self.callable
+            .call1(py, (completion, context_dict))?
+            .extract::<f64>(py)
+    }
+}
+
+impl RewardFn for PythonRewardFn {
+    fn evaluate(&self, completion: &str, context: &RewardContext) -> f64 {
+        // Only hold the GIL while the Python callable runs
+        Python::attach(|py| {
+            self.call(py, completion, context).unwrap_or_else(|e| {
+                tracing::error!(
+                    completion_idx = context.completion_idx,
+                    error = %e,
+                    "Python reward function failed"
+                );
+                0.0
+            })
+        })
+    }
+}
+
+// ==========================================================================================
+
+/// Registry of named reward functions.
+pub struct RewardRegistry {
+    functions: HashMap<String, Box<dyn RewardFn>>,
+}
+
+impl Default for RewardRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RewardRegistry {
+    /// Create a registry with the built-in `"format"`, `"execution"` and `"output"` rewards.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            functions: HashMap::new(),
+        };
+        registry.register("format", Box::new(FormatReward));
+        registry.register("execution", Box::new(ExecutionReward));
+        registry.register("output", Box::new(OutputReward));
+        registry
+    }
+
+    /// Register `f` under `name`, replacing any existing function with that name.
+    pub fn register(&mut self, name: &str, f: Box<dyn RewardFn>) {
+        self.functions.insert(name.to_string(), f);
+    }
+
+    /// Register a Python callable under `name` (see [`PythonRewardFn`]).
+    pub fn register_python_fn(&mut self, name: &str, py_callable: Py<PyAny>) {
+        self.register(name, Box::new(PythonRewardFn::new(py_callable)));
+    }
+
+    /// Look up the reward function registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn RewardFn> {
+        self.functions.get(name).map(|f| f.as_ref())
+    }
+
+    /// Registered names, sorted alphabetically.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.functions.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Evaluate the reward function `name` for a batch in parallel.
+    ///
+    /// `tests`, `entry_points` and `expected_outputs` provide the [`RewardContext`] of the
+    /// completion at the same index.
+    ///
+    /// # Returns
+    /// `None` if no function is registered under `name`, otherwise one reward per completion.
+    ///
+    /// # Panics
+    /// Panics if the input slices have different lengths.
+    pub fn evaluate_batch(
+        &self,
+        name: &str,
+        evaluator: &RewardEvaluator,
+        completions: &[String],
+        tests: &[Option<String>],
+        entry_points: &[Option<String>],
+        expected_outputs: &[String],
+    ) -> Option<Vec<f64>> {
+        assert_eq!(
+            completions.len(),
+            tests.len(),
+            "Completions and tests must have the same length"
+        );
+        assert_eq!(
+            completions.len(),
+            entry_points.len(),
+            "Completions and entry_points must have same length"
+        );
+        assert_eq!(
+            completions.len(),
+            expected_outputs.len(),
+            "Completions and expected_outputs must have same length"
+        );
+
+        let reward_fn = self.get(name)?;
+
+        let rewards = completions
+            .par_iter()
+            .zip(tests.par_iter())
+            .zip(entry_points.par_iter())
+            .zip(expected_outputs.par_iter())
+            .enumerate()
+            .map(
+                |(completion_idx, (((completion, test), entry_point), expected_output))| {
+                    let context = RewardContext {
+                        completion_idx,
+                        test: test.as_deref(),
+                        entry_point: entry_point.as_deref(),
+                        expected_output,
+                        evaluator,
+                    };
+                    reward_fn.evaluate(completion, &context)
+                },
+            )
+            .collect();
+
+        Some(rewards)
+    }
+}
@@ -1,12 +1,24 @@
 //! src/sandbox.rs
 //!
-//! Sandboxed code execution via Firejail.
+//! Sandboxed code execution via Firejail (or bubblewrap).
 //!
 //! # Safety
 //! Executes untrusted code in a Firejail sandbox with:
 //! - No network access (--net=none)
+//! - All capabilities dropped and no root account (--caps.drop=all, --noroot)
+//! - Seccomp syscall filter (--seccomp by default, see [`SeccompProfile`])
 //! - Isolated filesystem (--private)
-//! - Resource limits (memory, CPU, processes, file size)
+//! - Read-only system directories (--read-only=/usr, --read-only=/lib)
+//! - Private `/tmp` (--private-tmp), so concurrent evaluations cannot read each other's
+//!   scripts or files; the script (or, for multi-file and Rust solutions, the temp
+//!   directory) is whitelisted to stay visible to its own sandbox. (`--private` alone only
+//!   replaces the home directory.)
+//! - No access to the shared RAM tmpfs (--blacklist=/dev/shm): Firejail cannot give each
+//!   sandbox its own `/dev/shm` without root, so scripts are always written to `/tmp` under
+//!   Firejail, whatever [`SandboxOptions::prefer_ram_tmpdir`] says
+//! - No shell spawning inside the sandbox (--shell=none); the script path passed to Firejail
+//!   must be a plain temp path without shell metacharacters
+//! - Resource limits (memory, CPU, processes, file size, open file descriptors)
 //! - Timeout enforcement (kills process after timeout)
 //!
 //! # Requirements
@@ -15,20 +27,434 @@
 //! sudo apt-get update
 //! sudo apt-get install firejail
 //! ```
+//!
+//! With [`SandboxBackend::Auto`] (the default), bubblewrap (`bwrap`) is used when Firejail
+//! is not installed. Without either, execution fails unless a [`SandboxFallbackPolicy`]
+//! other than `Error` is configured, in which case the code runs under a bare `python3`
+//! process with only the timeout enforced.
+//!
+//! Where executing a sandbox binary is not allowed (e.g. on some HPC clusters),
+//! [`SandboxBackend::PythonSubprocess`] runs the code from a Python manager script that
+//! only applies resource limits and the timeout (see [`crate::sandbox_python`]).
+//!
+//! Rust code ([`execute_rust_sandboxed`]) is compiled with `rustc` inside the same sandbox,
+//! under raised memory and time limits, before the resulting binary is run.
 
+use crate::output_parser::{parse_exception_type, parse_test_output};
+use crate::sandbox_python;
 use once_cell::sync::Lazy;
-use pyo3::exceptions::{PyIOError, PyRuntimeError};
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use regex::Regex;
-use std::io::Read;
-use std::process::{Command, Stdio};
-use std::time::Duration;
-use tempfile::Builder;
-use wait_timeout::ChildExt;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, ErrorKind, Read};
+use std::os::fd::AsRawFd;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::str::FromStr;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+use tempfile::{Builder, NamedTempFile};
+
+// ==========================================================================================
+
+/// What to do when the Firejail binary cannot be found.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SandboxFallbackPolicy {
+    /// Fail the execution (every affected completion gets 0.0 reward).
+    #[default]
+    Error,
+    /// Log a warning once, then run the code with bare `python3`.
+    Warn,
+    /// Run the code with bare `python3` without any warning.
+    Silent,
+}
+
+impl FromStr for SandboxFallbackPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "silent" => Ok(Self::Silent),
+            other => Err(format!(
+                "unknown fallback policy '{}' (expected 'error', 'warn' or 'silent')",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SandboxFallbackPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Silent => "silent",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Which sandbox runs the code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SandboxBackend {
+    /// Use the first available of Firejail and bubblewrap (see [`detect_available_sandbox`]).
+    #[default]
+    Auto,
+    /// Firejail (`firejail`).
+    Firejail,
+    /// bubblewrap (`bwrap`). Resource limits are applied with `ulimit` inside the sandbox.
+    Bubblewrap,
+    /// No sandbox: bare `python3` with only the timeout enforced. Explicit opt-out, so the
+    /// fallback policy does not apply.
+    Bare,
+    /// No sandbox, but a Python manager script applies the resource limits with
+    /// `resource.setrlimit` and enforces the timeout (see [`crate::sandbox_python`]). For
+    /// clusters that do not allow executing Firejail; the fallback policy does not apply.
+    PythonSubprocess,
+}
+
+impl SandboxBackend {
+    /// Resolve `Auto` to a concrete backend (other variants are returned unchanged).
+    ///
+    /// If no sandbox is installed, `Auto` resolves to `Firejail` so that spawning fails
+    /// and the [`SandboxFallbackPolicy`] decides what happens.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => match *DETECTED_SANDBOX {
+                Self::Bare => Self::Firejail,
+                detected => detected,
+            },
+            backend => backend,
+        }
+    }
+}
+
+impl FromStr for SandboxBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "firejail" => Ok(Self::Firejail),
+            "bubblewrap" | "bwrap" => Ok(Self::Bubblewrap),
+            "bare" => Ok(Self::Bare),
+            "python_subprocess" => Ok(Self::PythonSubprocess),
+            other => Err(format!(
+                "unknown sandbox backend '{}' (expected 'auto', 'firejail', 'bubblewrap', 'bare' \
+                 or 'python_subprocess')",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SandboxBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Auto => "auto",
+            Self::Firejail => "firejail",
+            Self::Bubblewrap => "bubblewrap",
+            Self::Bare => "bare",
+            Self::PythonSubprocess => "python_subprocess",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Which syscalls Firejail's seccomp filter blocks.
+///
+/// Firejail's default filter can break legitimate packages (e.g. ones that need `clone`
+/// for threads); the other profiles block fewer syscalls. Only applies to Firejail.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SeccompProfile {
+    /// Firejail's default seccomp filter (`--seccomp`).
+    #[default]
+    Strict,
+    /// Block only the syscalls in [`PERMISSIVE_SECCOMP_BLOCKLIST`] (`--seccomp.drop=...`).
+    Permissive,
+    /// Block exactly the given syscalls (`--seccomp.drop=...`).
+    Custom(Vec<String>),
+}
+
+/// Syscalls blocked by [`SeccompProfile::Permissive`]: kernel, mount and tracing
+/// operations that untrusted code never needs.
+pub const PERMISSIVE_SECCOMP_BLOCKLIST: [&str; 14] = [
+    "mount",
+    "umount2",
+    "pivot_root",
+    "ptrace",
+    "process_vm_writev",
+    "kexec_load",
+    "kexec_file_load",
+    "init_module",
+    "finit_module",
+    "delete_module",
+    "reboot",
+    "swapon",
+    "swapoff",
+    "bpf",
+];
+
+impl SeccompProfile {
+    /// Firejail argument that installs this filter.
+    fn firejail_arg(&self) -> String {
+        match self {
+            Self::Strict => "--seccomp".to_string(),
+            Self::Permissive => {
+                format!("--seccomp.drop={}", PERMISSIVE_SECCOMP_BLOCKLIST.join(","))
+            }
+            Self::Custom(syscalls) => format!("--seccomp.drop={}", syscalls.join(",")),
+        }
+    }
+}
+
+impl FromStr for SeccompProfile {
+    type Err = String;
+
+    /// Parse `"strict"`, `"permissive"` or a comma-separated list of syscalls to block.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "permissive" => Ok(Self::Permissive),
+            other => {
+                let syscalls: Vec<String> = other
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .collect();
+                if syscalls.iter().all(|name| is_syscall_name(name)) {
+                    Ok(Self::Custom(syscalls))
+                } else {
+                    Err(format!(
+                        "unknown seccomp profile '{}' (expected 'strict', 'permissive' or a \
+                         comma-separated list of syscall names)",
+                        other
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Whether `name` looks like a syscall name (`[a-z0-9_]+`), so it is safe to pass to Firejail.
+pub fn is_syscall_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl fmt::Display for SeccompProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Strict => f.write_str("strict"),
+            Self::Permissive => f.write_str("permissive"),
+            Self::Custom(syscalls) => f.write_str(&syscalls.join(",")),
+        }
+    }
+}
+
+/// Options controlling a single sandboxed execution.
+#[derive(Clone, Debug)]
+pub struct SandboxOptions {
+    /// Maximum wall-clock execution time in seconds.
+    pub timeout: u64,
+    /// Address space limit in megabytes.
+    pub memory_limit_mb: u64,
+    /// CPU time limit in seconds.
+    pub cpu_time_limit: u64,
+    /// Behavior when Firejail is not installed.
+    pub fallback_policy: SandboxFallbackPolicy,
+    /// Sandbox used to run the code.
+    pub backend: SandboxBackend,
+    /// Text written to the script's stdin (`None` leaves stdin untouched).
+    pub stdin_input: Option<String>,
+    /// Write scripts to a RAM-backed tmpfs (see [`ram_tmpdir`]) instead of `/tmp` when one
+    /// is mounted.
+    ///
+    /// The tmpfs is shared by every process of the host, so it is only used where each
+    /// sandbox still sees nothing but its own script: bubblewrap gives each sandbox a private
+    /// `/dev/shm`, while Firejail cannot without root and keeps writing to `/tmp` (with the
+    /// tmpfs hidden). The bare and `python_subprocess` backends isolate neither directory.
+    pub prefer_ram_tmpdir: bool,
+    /// Additional arguments passed to Firejail before the sandboxed command.
+    pub extra_firejail_args: Vec<String>,
+    /// Maximum number of open file descriptors.
+    pub max_open_files: u32,
+    /// Seccomp filter applied by Firejail.
+    pub seccomp_profile: SeccompProfile,
+}
+
+/// Default [`SandboxOptions::max_open_files`]: enough for the interpreter and a few files,
+/// while a submission opening descriptors in a loop cannot exhaust the host's limit.
+pub const DEFAULT_MAX_OPEN_FILES: u32 = 50;
+
+/// Outcome of a single sandboxed execution.
+#[derive(Clone, Debug, Default)]
+pub struct SandboxResult {
+    /// True if the exit code was 0 and all tests passed.
+    pub all_passed: bool,
+    /// Number of tests that passed (from the `TESTS_PASSED:X/Y` line).
+    pub tests_passed: i32,
+    /// Total number of tests run (from the `TESTS_PASSED:X/Y` line).
+    pub tests_total: i32,
+    /// Process exit code (`None` if the process timed out or was killed by a signal).
+    pub exit_code: Option<i32>,
+    /// True if the process was killed after exceeding the wall-clock timeout.
+    pub timed_out: bool,
+    /// Captured stdout (lossily decoded as UTF-8).
+    pub stdout: String,
+    /// Captured stderr (lossily decoded as UTF-8).
+    pub stderr: String,
+    /// Class name of the uncaught exception that ended the script (e.g. `"SyntaxError"`),
+    /// parsed from the last line of stderr.
+    pub exception_type: Option<String>,
+}
+
+/// Initial interval between checks for process exit while draining its output. It doubles
+/// after each check up to [`MAX_POLL_INTERVAL`], so short scripts are picked up quickly
+/// while long-running ones cause few wakeups.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Upper bound for the poll interval (see [`MIN_POLL_INTERVAL`]).
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(16);
 
-/// Regex pattern to extract test results from output
-static TEST_RESULTS_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"TESTS_PASSED:(\d+)/(\d+)").unwrap());
+/// Minimum memory limit for compiling Rust code (see [`execute_rust_sandboxed`]).
+pub const RUST_COMPILE_MEMORY_MB: u64 = 4096;
+
+/// Minimum wall-clock and CPU time limit for compiling Rust code, in seconds.
+pub const RUST_COMPILE_TIMEOUT_SECS: u64 = 60;
+
+/// Bytes of stderr kept per process: the end of the output, where the traceback names the
+/// exception, so a script flooding stderr cannot use unbounded memory.
+pub const MAX_STDERR_BYTES: usize = 4096;
+
+/// Ensures the missing-Firejail warning is logged only once per process.
+static FALLBACK_WARNING: Once = Once::new();
+
+/// Sandbox detected on first use of [`SandboxBackend::Auto`] (detection spawns processes).
+static DETECTED_SANDBOX: Lazy<SandboxBackend> = Lazy::new(detect_available_sandbox);
+
+/// RAM-backed tmpfs detected on first use of [`SandboxOptions::prefer_ram_tmpdir`].
+static RAM_TMPDIR: Lazy<Option<PathBuf>> = Lazy::new(detect_ram_tmpdir);
+
+/// Mount points checked for a RAM-backed tmpfs, in order of preference.
+const RAM_TMPDIR_CANDIDATES: [&str; 2] = ["/dev/shm", "/run/shm"];
+
+/// Script paths accepted by the Firejail command: a temp root followed by plain path
+/// components, so no shell metacharacters reach the command line.
+static SCRIPT_PATH_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^/(?:tmp|dev/shm|run/shm)(?:/[a-zA-Z0-9._-]+)+$").unwrap());
+
+// ==========================================================================================
+
+/// Check whether Firejail is installed and runnable (`firejail --version` succeeds).
+pub fn is_firejail_available() -> bool {
+    command_succeeds("firejail")
+}
+
+/// Check whether bubblewrap is installed and runnable (`bwrap --version` succeeds).
+pub fn is_bubblewrap_available() -> bool {
+    command_succeeds("bwrap")
+}
+
+/// Run `<program> --version` and report whether it exited successfully.
+fn command_succeeds(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Detect the best available sandbox: Firejail, then bubblewrap, then `Bare`.
+pub fn detect_available_sandbox() -> SandboxBackend {
+    if is_firejail_available() {
+        SandboxBackend::Firejail
+    } else if is_bubblewrap_available() {
+        SandboxBackend::Bubblewrap
+    } else {
+        SandboxBackend::Bare
+    }
+}
+
+/// Check whether a sandbox (Firejail or bubblewrap) is available on this machine.
+///
+/// When this returns `False`, executions fail unless a fallback policy other than
+/// `"error"` is configured.
+#[pyfunction]
+pub fn is_sandbox_available() -> bool {
+    *DETECTED_SANDBOX != SandboxBackend::Bare
+}
+
+/// Find a writable `tmpfs` mounted at `/dev/shm` or `/run/shm` (from `/proc/mounts`).
+fn detect_ram_tmpdir() -> Option<PathBuf> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    RAM_TMPDIR_CANDIDATES
+        .into_iter()
+        .find(|candidate| {
+            mounts.lines().any(|line| {
+                // Format: <device> <mount point> <fs type> <options> ...
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                fields.len() >= 4
+                    && fields[1] == *candidate
+                    && fields[2] == "tmpfs"
+                    && fields[3].split(',').any(|option| option == "rw")
+            })
+        })
+        .map(PathBuf::from)
+}
+
+/// The RAM-backed tmpfs scripts are written to when preferred, if one is mounted.
+pub fn ram_tmpdir() -> Option<&'static Path> {
+    RAM_TMPDIR.as_deref()
+}
+
+/// Directory for temporary scripts: the RAM tmpfs if preferred and available, else `/tmp`.
+///
+/// Firejail hides the RAM tmpfs from its sandboxes (see [`firejail_command`]), so its
+/// scripts always go to `/tmp`.
+pub(crate) fn temp_root(options: &SandboxOptions) -> &'static Path {
+    match ram_tmpdir() {
+        Some(dir)
+            if options.prefer_ram_tmpdir
+                && options.backend.resolve() != SandboxBackend::Firejail =>
+        {
+            dir
+        }
+        _ => Path::new("/tmp"),
+    }
+}
+
+/// File extensions accepted by [`safe_temp_file`].
+pub const ALLOWED_TEMP_SUFFIXES: [&str; 5] = [".py", ".js", ".rs", ".java", ".go"];
+
+/// Create a temporary file in `dir` with the extension `suffix`, holding `content`.
+///
+/// `suffix` is checked against [`ALLOWED_TEMP_SUFFIXES`] first, so a suffix derived from a
+/// user-supplied language string cannot smuggle path separators or `..` into the file name.
+///
+/// # Errors
+/// `InvalidInput` for any other suffix, or the I/O error from creating or writing the file.
+pub fn safe_temp_file(content: &str, suffix: &str, dir: &Path) -> io::Result<NamedTempFile> {
+    if !ALLOWED_TEMP_SUFFIXES.contains(&suffix) {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("temp file suffix '{}' is not allowed", suffix),
+        ));
+    }
+
+    let mut temp_file = Builder::new().suffix(suffix).tempfile_in(dir)?;
+    io::Write::write_all(&mut temp_file, content.as_bytes())?;
+    Ok(temp_file)
+}
+
+// ==========================================================================================
 
 /// Execute Python code with tests in a Firejail sandbox.
 ///
@@ -40,6 +466,13 @@ static TEST_RESULTS_PATTERN: Lazy<Regex> =
 /// - `timeout`: Maximum execution time in seconds (default: 10)
 /// - `memory_limit_mb`: Memory limit in megabytes (default: 512)
 /// - `cpu_time_limit`: CPU time limit in seconds (default: 12)
+/// - `fallback_policy`: `"error"`, `"warn"` or `"silent"` (default: `"error"`)
+/// - `sandbox_backend`: `"auto"`, `"firejail"`, `"bubblewrap"`, `"bare"` or
+///   `"python_subprocess"` (default: `"auto"`)
+/// - `stdin_input`: Text written to the script's stdin (default: None)
+/// - `prefer_ram_tmpdir`: Write the script to `/dev/shm` (or `/run/shm`) instead of `/tmp`
+///   when a tmpfs is mounted there (default: True). Ignored with Firejail, which cannot give
+///   each sandbox a private `/dev/shm`; bubblewrap does, so isolation is unchanged
 ///
 /// # Returns
 /// `Ok((all_passed, tests_passed, tests_total))` where:
@@ -49,96 +482,575 @@ static TEST_RESULTS_PATTERN: Lazy<Regex> =
 ///
 /// Returns `Err` if sandbox setup or execution fails.
 #[pyfunction]
-#[pyo3(signature = (code, timeout=10, memory_limit_mb=512, cpu_time_limit=12))]
+#[pyo3(signature = (
+    code,
+    timeout=10,
+    memory_limit_mb=512,
+    cpu_time_limit=12,
+    fallback_policy="error",
+    sandbox_backend="auto",
+    stdin_input=None,
+    prefer_ram_tmpdir=true,
+))]
+#[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
 pub fn run_sandboxed_tests(
     code: &str,
     timeout: u64,
     memory_limit_mb: u64,
     cpu_time_limit: u64,
+    fallback_policy: &str,
+    sandbox_backend: &str,
+    stdin_input: Option<String>,
+    prefer_ram_tmpdir: bool,
 ) -> PyResult<(bool, i32, i32)> {
+    let options = SandboxOptions {
+        timeout,
+        memory_limit_mb,
+        cpu_time_limit,
+        fallback_policy: fallback_policy.parse().map_err(PyValueError::new_err)?,
+        backend: sandbox_backend.parse().map_err(PyValueError::new_err)?,
+        stdin_input,
+        prefer_ram_tmpdir,
+        extra_firejail_args: Vec::new(),
+        max_open_files: DEFAULT_MAX_OPEN_FILES,
+        seccomp_profile: SeccompProfile::default(),
+    };
+    let result = execute_sandboxed(code, &options)?;
+    Ok((result.all_passed, result.tests_passed, result.tests_total))
+}
+
+/// Rust entry point for sandboxed execution (see [`run_sandboxed_tests`]).
+///
+/// Returns the full [`SandboxResult`], including captured stdout.
+pub fn execute_sandboxed(code: &str, options: &SandboxOptions) -> PyResult<SandboxResult> {
     // Early return for empty code
     if code.trim().is_empty() {
-        return Ok((false, 0, 0));
+        return Ok(SandboxResult::default());
     }
 
-    // Create temporary Python file in /tmp
-    let mut temp_file = Builder::new()
-        .suffix(".py")
-        .tempfile_in("/tmp")
+    // Write the code to a temporary Python file in /tmp (or the RAM tmpfs)
+    let temp_file = safe_temp_file(code, ".py", temp_root(options))
         .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to create temp file: {}", e)))?;
 
-    // Write code to temp file
-    std::io::Write::write_all(&mut temp_file, code.as_bytes())
-        .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to write to temp file: {}", e)))?;
+    run_script(temp_file.path(), None, options)
+}
+
+/// Execute a multi-file Python solution with tests in a Firejail sandbox.
+///
+/// All `files` (filename -> content) are written to one temporary directory, and
+/// `entry_file` is executed from it, so it can import the other files as modules.
+///
+/// # Arguments:
+/// - `files`: Mapping of relative filenames to file contents
+/// - `entry_file`: Filename (key of `files`) to execute
+/// - `timeout`, `memory_limit_mb`, `cpu_time_limit`, `fallback_policy`, `sandbox_backend`,
+///   `prefer_ram_tmpdir`: as in `run_sandboxed_tests`
+///
+/// # Returns
+/// `Ok((all_passed, tests_passed, tests_total))`, as in `run_sandboxed_tests`.
+///
+/// Raises `ValueError` for unsafe filenames or a missing `entry_file`.
+#[pyfunction]
+#[pyo3(signature = (
+    files,
+    entry_file,
+    timeout=10,
+    memory_limit_mb=512,
+    cpu_time_limit=12,
+    fallback_policy="error",
+    sandbox_backend="auto",
+    prefer_ram_tmpdir=true,
+))]
+#[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+pub fn execute_multi_file_with_tests_firejail(
+    files: HashMap<String, String>,
+    entry_file: &str,
+    timeout: u64,
+    memory_limit_mb: u64,
+    cpu_time_limit: u64,
+    fallback_policy: &str,
+    sandbox_backend: &str,
+    prefer_ram_tmpdir: bool,
+) -> PyResult<(bool, i32, i32)> {
+    let options = SandboxOptions {
+        timeout,
+        memory_limit_mb,
+        cpu_time_limit,
+        fallback_policy: fallback_policy.parse().map_err(PyValueError::new_err)?,
+        backend: sandbox_backend.parse().map_err(PyValueError::new_err)?,
+        stdin_input: None,
+        prefer_ram_tmpdir,
+        extra_firejail_args: Vec::new(),
+        max_open_files: DEFAULT_MAX_OPEN_FILES,
+        seccomp_profile: SeccompProfile::default(),
+    };
+    let result = run_sandboxed_tests_multi_file(&files, entry_file, &options)?;
+    Ok((result.all_passed, result.tests_passed, result.tests_total))
+}
+
+/// Rust entry point for multi-file execution (see [`execute_multi_file_with_tests_firejail`]).
+pub fn run_sandboxed_tests_multi_file(
+    files: &HashMap<String, String>,
+    entry_file: &str,
+    options: &SandboxOptions,
+) -> PyResult<SandboxResult> {
+    if !files.contains_key(entry_file) {
+        return Err(PyValueError::new_err(format!(
+            "entry_file '{}' is not one of the provided files",
+            entry_file
+        )));
+    }
+
+    // Create temporary directory in /tmp (or the RAM tmpfs)
+    let temp_dir = Builder::new()
+        .tempdir_in(temp_root(options))
+        .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to create temp dir: {}", e)))?;
+
+    for (name, content) in files {
+        // Only plain relative paths: no absolute paths or `..` escaping the directory
+        let relative = Path::new(name);
+        let is_safe = !name.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_safe {
+            return Err(PyValueError::new_err(format!(
+                "Unsafe filename: '{}'",
+                name
+            )));
+        }
+
+        let path = temp_dir.path().join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                PyErr::new::<PyIOError, _>(format!("Failed to create directory: {}", e))
+            })?;
+        }
+        std::fs::write(&path, content).map_err(|e| {
+            PyErr::new::<PyIOError, _>(format!("Failed to write '{}': {}", name, e))
+        })?;
+    }
+
+    run_script(
+        &temp_dir.path().join(entry_file),
+        Some(temp_dir.path()),
+        options,
+    )
+}
+
+/// Compile a Rust program with `rustc` and run the binary, both inside the sandbox.
+///
+/// Compilation gets at least [`RUST_COMPILE_MEMORY_MB`] of memory and
+/// [`RUST_COMPILE_TIMEOUT_SECS`] of wall-clock and CPU time, since `rustc` needs far more
+/// than the program it builds. If compilation fails, its result is returned with
+/// `exception_type` set to `"CompileError"` and the compiler messages in `stderr`.
+pub fn execute_rust_sandboxed(code: &str, options: &SandboxOptions) -> PyResult<SandboxResult> {
+    if code.trim().is_empty() {
+        return Ok(SandboxResult::default());
+    }
+
+    // rustc writes the binary next to the source, so the whole directory is made visible
+    let temp_dir = Builder::new()
+        .tempdir_in(temp_root(options))
+        .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to create temp dir: {}", e)))?;
+    let source = temp_dir.path().join("main.rs");
+    let binary = temp_dir.path().join("main");
+    std::fs::write(&source, code).map_err(|e| {
+        PyErr::new::<PyIOError, _>(format!("Failed to write '{}': {}", source.display(), e))
+    })?;
+
+    let compile_options = SandboxOptions {
+        timeout: options.timeout.max(RUST_COMPILE_TIMEOUT_SECS),
+        cpu_time_limit: options.cpu_time_limit.max(RUST_COMPILE_TIMEOUT_SECS),
+        memory_limit_mb: options.memory_limit_mb.max(RUST_COMPILE_MEMORY_MB),
+        stdin_input: None,
+        ..options.clone()
+    };
+    let compile = [
+        OsStr::new("rustc"),
+        OsStr::new("--edition=2021"),
+        OsStr::new("-O"),
+        OsStr::new("-Ccodegen-units=1"), // Fewer compiler threads under the process limit
+        OsStr::new("-o"),
+        binary.as_os_str(),
+        source.as_os_str(),
+    ];
+    let compiled = run_program(
+        &compile,
+        temp_dir.path(),
+        Some(temp_dir.path()),
+        &compile_options,
+    )?;
+    if compiled.timed_out || compiled.exit_code != Some(0) {
+        return Ok(SandboxResult {
+            all_passed: false,
+            tests_passed: 0,
+            tests_total: 0,
+            exception_type: Some("CompileError".to_string()),
+            ..compiled
+        });
+    }
+
+    run_program(
+        &[binary.as_os_str()],
+        temp_dir.path(),
+        Some(temp_dir.path()),
+        options,
+    )
+}
+
+/// Run `script` with `python3` in the sandbox and collect its results.
+///
+/// If `bind_dir` is given, that whole directory (rather than only `script`) is made visible
+/// inside the sandbox: whitelisted by Firejail, bind-mounted by bubblewrap.
+fn run_script(
+    script: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> PyResult<SandboxResult> {
+    let program = [OsStr::new("python3"), OsStr::new("-u"), script.as_os_str()];
+    run_program(&program, script, bind_dir, options)
+}
+
+/// Run the command line `program` in the sandbox and collect its results.
+///
+/// `path` is the file (or, with `bind_dir`, the directory) the program needs to see.
+fn run_program(
+    program: &[&OsStr],
+    path: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> PyResult<SandboxResult> {
+    let output = match options.backend {
+        SandboxBackend::PythonSubprocess => {
+            sandbox_python::run_managed(program, options).map_err(|e| {
+                PyErr::new::<PyRuntimeError, _>(format!("Python subprocess sandbox failed: {}", e))
+            })?
+        }
+        _ => spawn_and_wait(program, path, bind_dir, options)?,
+    };
+    let Some(output) = output else {
+        return Ok(SandboxResult {
+            timed_out: true,
+            ..SandboxResult::default()
+        });
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    let exit_code = output.status.code();
+
+    // Parse test results from stdout
+    let (tests_passed, tests_total) = parse_test_output(&stdout).unwrap_or((0, 0));
+
+    let all_passed = exit_code == Some(0) && tests_passed == tests_total && tests_total > 0;
+    Ok(SandboxResult {
+        all_passed,
+        tests_passed,
+        tests_total,
+        exit_code,
+        timed_out: false,
+        stdout,
+        exception_type: parse_exception_type(&stderr),
+        stderr,
+    })
+}
+
+/// Spawn `program` in the sandbox and wait for it to exit, killing it on timeout.
+///
+/// # Returns
+/// The output as from [`wait_with_output`], or `None` if the timeout was exceeded.
+fn spawn_and_wait(
+    program: &[&OsStr],
+    path: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> PyResult<Option<Output>> {
+    // Spawn the sandboxed process, falling back to an unsandboxed one if allowed
+    let mut child = spawn_process(program, path, bind_dir, options)?;
+    feed_stdin(&mut child, options);
+
+    // Wait for process with timeout, collecting its output
+    let timeout_duration = Duration::from_secs(options.timeout);
+    let output = wait_with_output(&mut child, timeout_duration).map_err(|e| {
+        PyErr::new::<PyRuntimeError, _>(format!("Error waiting for process: {}", e))
+    })?;
+    if output.is_none() {
+        // Timeout exceeded - kill the process
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    Ok(output)
+}
+
+/// Write [`SandboxOptions::stdin_input`] to the stdin pipe of `child`, if any, in a
+/// background thread (dropping the pipe afterwards signals EOF).
+pub(crate) fn feed_stdin(child: &mut Child, options: &SandboxOptions) {
+    if let (Some(input), Some(mut stdin)) = (options.stdin_input.clone(), child.stdin.take()) {
+        std::thread::spawn(move || {
+            std::io::Write::write_all(&mut stdin, input.as_bytes()).ok();
+        });
+    }
+}
+
+/// Wait up to `timeout` for `child` to exit, collecting its stdout and stderr.
+///
+/// The pipes are switched to non-blocking mode and drained on the calling thread between
+/// polls of the exit status, so a chatty process cannot fill a pipe and stall, without
+/// spawning reader threads.
+///
+/// # Returns
+/// The exit status and output (stderr cut to its last [`MAX_STDERR_BYTES`]), or `None` if
+/// the timeout was exceeded (the process is left running for the caller to kill).
+pub fn wait_with_output(child: &mut Child, timeout: Duration) -> io::Result<Option<Output>> {
+    let mut stdout_pipe = child.stdout.take().expect("Failed to take stdout");
+    let mut stderr_pipe = child.stderr.take().expect("Failed to take stderr");
+    set_nonblocking(&stdout_pipe)?;
+    set_nonblocking(&stderr_pipe)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut poll_interval = MIN_POLL_INTERVAL;
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    loop {
+        drain_pipe(&mut stdout_pipe, &mut stdout)?;
+        drain_pipe_tail(&mut stderr_pipe, &mut stderr, MAX_STDERR_BYTES)?;
+
+        if let Some(status) = child.try_wait()? {
+            // Collect anything written between the last drain and the exit
+            drain_pipe(&mut stdout_pipe, &mut stdout)?;
+            drain_pipe_tail(&mut stderr_pipe, &mut stderr, MAX_STDERR_BYTES)?;
+            return Ok(Some(Output {
+                status,
+                stdout,
+                stderr,
+            }));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        std::thread::sleep(poll_interval.min(deadline.saturating_duration_since(Instant::now())));
+        poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Set `O_NONBLOCK` on a pipe so reads return `WouldBlock` instead of waiting for data.
+fn set_nonblocking(pipe: &impl AsRawFd) -> io::Result<()> {
+    let fd = pipe.as_raw_fd();
+    // SAFETY: `fd` is an open pipe owned by `pipe`; F_GETFL/F_SETFL only change its flags
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Like [`drain_pipe`], keeping only the last `limit` bytes in `buf`.
+fn drain_pipe_tail(pipe: &mut impl Read, buf: &mut Vec<u8>, limit: usize) -> io::Result<()> {
+    drain_pipe(pipe, buf)?;
+    if buf.len() > limit {
+        buf.drain(..buf.len() - limit);
+    }
+    Ok(())
+}
+
+/// Append everything currently readable from a non-blocking `pipe` to `buf`.
+fn drain_pipe(pipe: &mut impl Read, buf: &mut Vec<u8>) -> io::Result<()> {
+    let mut chunk = [0u8; 8192];
+    loop {
+        match pipe.read(&mut chunk) {
+            Ok(0) => return Ok(()), // EOF
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Spawn `program` (e.g. `python3 -u <path>`) inside the configured sandbox, applying the
+/// fallback policy if the sandbox binary is missing.
+fn spawn_process(
+    program: &[&OsStr],
+    path: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> PyResult<Child> {
+    let (sandbox, mut command) = match options.backend.resolve() {
+        SandboxBackend::Bubblewrap => (
+            "bwrap",
+            bubblewrap_command(program, path, bind_dir, options),
+        ),
+        SandboxBackend::Bare => return spawn_bare(program, options),
+        _ => {
+            check_script_path(path)?;
+            (
+                "firejail",
+                firejail_command(program, path, bind_dir, options),
+            )
+        }
+    };
+    if options.stdin_input.is_some() {
+        command.stdin(Stdio::piped());
+    }
+
+    let error = match command.spawn() {
+        Ok(child) => return Ok(child),
+        Err(e) => e,
+    };
+
+    let message = error.to_string();
+    let sandbox_missing = error.kind() == ErrorKind::NotFound
+        || message.contains("not found")
+        || message.contains("No such file or directory");
+
+    match options.fallback_policy {
+        SandboxFallbackPolicy::Warn if sandbox_missing => {
+            FALLBACK_WARNING.call_once(|| {
+                tracing::warn!(
+                    error = %message,
+                    sandbox,
+                    "sandbox not found; falling back to unsandboxed execution"
+                );
+            });
+        }
+        SandboxFallbackPolicy::Silent if sandbox_missing => {}
+        _ => {
+            return Err(PyErr::new::<PyRuntimeError, _>(format!(
+                "Failed to spawn {} process: {}. Is {} installed?",
+                sandbox, message, sandbox
+            )));
+        }
+    }
+
+    spawn_bare(program, options)
+}
 
-    let temp_path = temp_file.path();
+/// Reject script paths that do not match [`SCRIPT_PATH_PATTERN`] before handing them to
+/// Firejail.
+fn check_script_path(path: &Path) -> PyResult<()> {
+    match path.to_str() {
+        Some(path) if SCRIPT_PATH_PATTERN.is_match(path) => Ok(()),
+        _ => Err(PyValueError::new_err(format!(
+            "Refusing to run script with unsafe path '{}'",
+            path.display()
+        ))),
+    }
+}
+
+/// Spawn the unsandboxed process.
+fn spawn_bare(program: &[&OsStr], options: &SandboxOptions) -> PyResult<Child> {
+    let mut command = bare_command(program);
+    if options.stdin_input.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    command.spawn().map_err(|e| {
+        PyErr::new::<PyRuntimeError, _>(format!(
+            "Failed to spawn {} process: {}",
+            program[0].to_string_lossy(),
+            e
+        ))
+    })
+}
 
-    // Build firejail command
-    let memory_limit_bytes = memory_limit_mb * 1_000_000;
+/// Build the Firejail invocation for running `program`.
+fn firejail_command(
+    program: &[&OsStr],
+    path: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> Command {
+    let memory_limit_bytes = options.memory_limit_mb * 1_000_000;
     let mut cmd = Command::new("firejail");
-    cmd.arg("--quiet")
-        .arg("--private") // Isolated filesystem
-        .arg("--private-dev")
+    cmd.arg("--quiet");
+    cmd.arg("--private") // Isolated filesystem
+        .arg("--private-tmp"); // Empty /tmp per sandbox
+    let mount = bind_dir.unwrap_or(path);
+    if mount.starts_with("/tmp") {
+        // Keep our own script (or solution directory) in the private /tmp
+        cmd.arg(format!("--whitelist={}", mount.display()));
+    }
+    // `--private-dev` keeps the host's /dev/shm, which would be shared by all sandboxes
+    for ram_tmpdir in RAM_TMPDIR_CANDIDATES {
+        cmd.arg(format!("--blacklist={}", ram_tmpdir));
+    }
+    cmd.arg("--private-dev")
+        // Shared libraries cannot be replaced to affect later sandboxes
+        .arg("--read-only=/usr")
+        .arg("--read-only=/lib")
         .arg("--net=none") // No network access
         .arg("--x11=none") // No X11
         .arg("--nodbus") // No D-Bus
+        .arg("--shell=none") // No shell inside the sandbox: the program is executed directly
+        .arg(options.seccomp_profile.firejail_arg()) // Syscall filter
+        .arg("--caps.drop=all") // No Linux capabilities (CAP_NET_ADMIN, CAP_SYS_PTRACE, ...)
+        .arg("--noroot") // No root user in the namespace: blocks setuid escalation
         .arg(format!("--rlimit-as={}", memory_limit_bytes))
-        .arg(format!("--rlimit-cpu={}", cpu_time_limit)) // Limits actual CPU usage
+        .arg(format!("--rlimit-cpu={}", options.cpu_time_limit)) // Limits actual CPU usage
         .arg("--rlimit-nproc=10")
         .arg("--rlimit-fsize=10000000")
-        .arg("python3")
-        .arg("-u") // Unbuffered output
-        .arg(temp_path)
+        .arg(format!("--rlimit-nofile={}", options.max_open_files))
+        .args(&options.extra_firejail_args)
+        .args(program)
         .stdout(Stdio::piped())
-        .stderr(Stdio::null()) // Ignore stderr (reduces noise)
+        .stderr(Stdio::piped()) // Tracebacks identify the exception type
         .env("PYTHONPATH", ""); // Clean environment
+    cmd
+}
 
-    // Spawn the sandboxed process
-    let mut child = cmd.spawn().map_err(|e| {
-        PyErr::new::<PyRuntimeError, _>(format!(
-            "Failed to spawn firejail process: {}. Is firejail installed?",
-            e
-        ))
-    })?;
+/// Build the bubblewrap invocation for running `program`.
+///
+/// The host filesystem is mounted read-only with a private `/tmp` and `/dev/shm` (created by
+/// `--dev`, plus a tmpfs over `/run/shm` when that is a separate directory), into which the
+/// script (or `bind_dir`) is bind-mounted. bubblewrap has no resource limit flags, so the limits
+/// are set with `ulimit` in a shell that then `exec`s the program.
+fn bubblewrap_command(
+    program: &[&OsStr],
+    path: &Path,
+    bind_dir: Option<&Path>,
+    options: &SandboxOptions,
+) -> Command {
+    let memory_limit_kb = options.memory_limit_mb * 1_000;
+    let file_size_blocks = 10_000_000 / 512; // `ulimit -f` counts 512-byte blocks
+    let mount = bind_dir.unwrap_or(path);
 
-    // Read stdout in background thread to avoid blocking
-    let mut stdout = child.stdout.take().expect("Failed to take stdout");
-    let stdout_thread = std::thread::spawn(move || {
-        let mut buf = Vec::new();
-        stdout.read_to_end(&mut buf).ok();
-        buf
-    });
-
-    // Wait for process with timeout
-    let timeout_duration = Duration::from_secs(timeout);
-    let status = match child
-        .wait_timeout(timeout_duration)
-        .map_err(|e| PyErr::new::<PyRuntimeError, _>(format!("Error waiting for process: {}", e)))?
+    let mut cmd = Command::new("bwrap");
+    cmd.args(["--ro-bind", "/", "/"])
+        .args(["--dev", "/dev"])
+        .args(["--proc", "/proc"])
+        .args(["--tmpfs", "/tmp"]);
+    if Path::new("/run/shm")
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.is_dir())
     {
-        Some(status) => status,
-        None => {
-            // Timeout exceeded - kill the process
-            let _ = child.kill();
-            let _ = child.wait();
-            return Ok((false, 0, 0));
-        }
-    };
-
-    // Get output from background thread
-    let stdout_bytes = stdout_thread.join().expect("stdout thread panicked");
-    let stdout_str = String::from_utf8_lossy(&stdout_bytes);
-    let exit_code = status.code().unwrap_or(-1);
-
-    // Parse test results from stdout
-    let (tests_passed, tests_total) = TEST_RESULTS_PATTERN
-        .captures(&stdout_str)
-        .map(|caps| {
-            let passed = caps[1].parse::<i32>().unwrap_or(0);
-            let total = caps[2].parse::<i32>().unwrap_or(0);
-            (passed, total)
-        })
-        .unwrap_or((0, 0));
+        cmd.args(["--tmpfs", "/run/shm"]);
+    }
+    cmd.arg("--bind")
+        .arg(mount)
+        .arg(mount)
+        .arg("--unshare-all") // Includes the network namespace: no network access
+        .arg("--die-with-parent")
+        .arg("--new-session")
+        .arg("sh")
+        .arg("-c")
+        .arg(format!(
+            "ulimit -v {} -t {} -f {} -n {} && exec \"$@\"",
+            memory_limit_kb, options.cpu_time_limit, file_size_blocks, options.max_open_files
+        ))
+        .arg("sh") // $0
+        .args(program)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("PYTHONPATH", "");
+    cmd
+}
 
-    let all_passed = exit_code == 0 && tests_passed == tests_total && tests_total > 0;
-    Ok((all_passed, tests_passed, tests_total))
+/// Build an unsandboxed invocation (fallback only, timeout still applies).
+fn bare_command(program: &[&OsStr]) -> Command {
+    let mut cmd = Command::new(program[0]);
+    cmd.args(&program[1..])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("PYTHONPATH", "");
+    cmd
 }
@@ -0,0 +1,167 @@
+//! src/sandbox_python.rs
+//!
+//! Execution under a Python subprocess manager
+//! ([`SandboxBackend::PythonSubprocess`](crate::sandbox::SandboxBackend::PythonSubprocess)).
+//!
+//! Some cluster environments do not allow executing Firejail (or bubblewrap), but do allow
+//! running `python3`. There, a small manager script ([`MANAGER_SCRIPT`]) starts the program
+//! with `subprocess.run`, applies the resource limits with `resource.setrlimit` in the child
+//! before it executes the program, enforces the timeout, and writes the outcome to a temp
+//! JSON file that is read back once the manager exits.
+//!
+//! # Safety
+//! This is NOT a sandbox: the code runs as the current user, with the user's filesystem and
+//! network access. Only the resource limits (memory, CPU time, file size, open files) and
+//! the timeout apply, so it is less secure than Firejail and meant for trusted clusters.
+//! The process limit is not set: `RLIMIT_NPROC` counts every process of the user, which on
+//! a shared cluster node would keep the program from starting threads.
+
+use crate::sandbox::{
+    MAX_STDERR_BYTES, SandboxOptions, feed_stdin, safe_temp_file, temp_root, wait_with_output,
+};
+use std::ffi::OsStr;
+use std::io;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::time::Duration;
+use tempfile::Builder;
+
+/// Manager script, run as
+/// `python3 -u manager.py <result.json> <timeout> <memory_bytes> <cpu_seconds>
+/// <max_open_files> <max_file_bytes> <program>...`.
+///
+/// Writes `{"timed_out", "returncode", "stdout", "stderr"}` to `result.json`; a negative
+/// `returncode` is the signal that killed the program.
+pub const MANAGER_SCRIPT: &str = r#""""Run a program under resource limits and report its outcome as JSON."""
+import json
+import resource
+import subprocess
+import sys
+
+result_path, timeout, memory_bytes, cpu_seconds, max_open_files, max_file_bytes = sys.argv[1:7]
+program = sys.argv[7:]
+
+LIMITS = [
+    (resource.RLIMIT_AS, int(memory_bytes)),
+    (resource.RLIMIT_CPU, int(cpu_seconds)),
+    (resource.RLIMIT_NOFILE, int(max_open_files)),
+    (resource.RLIMIT_FSIZE, int(max_file_bytes)),
+]
+
+
+def set_limits():
+    for limit, value in LIMITS:
+        hard = resource.getrlimit(limit)[1]
+        if hard != resource.RLIM_INFINITY:
+            value = min(value, hard)
+        resource.setrlimit(limit, (value, value))
+
+
+result = {"timed_out": False, "returncode": None, "stdout": "", "stderr": ""}
+try:
+    completed = subprocess.run(
+        program, capture_output=True, timeout=float(timeout), preexec_fn=set_limits
+    )
+    result["returncode"] = completed.returncode
+    result["stdout"] = completed.stdout.decode(errors="replace")
+    result["stderr"] = completed.stderr.decode(errors="replace")
+except subprocess.TimeoutExpired:
+    result["timed_out"] = True
+
+with open(result_path, "w") as f:
+    json.dump(result, f)
+"#;
+
+/// Extra wall-clock time given to the manager beyond the program's timeout, to start up
+/// and write its report. A manager still running after that is killed.
+const MANAGER_GRACE_SECS: u64 = 5;
+
+/// Maximum size of a file written by the program, as with the other backends.
+const MAX_FILE_BYTES: u64 = 10_000_000;
+
+/// Run `program` under the manager script and collect its output.
+///
+/// The manager runs in its own process group, which is killed once it exits, so processes
+/// left behind by the program do not outlive the evaluation.
+///
+/// # Returns
+/// The exit status and output of `program` (stderr cut to its last [`MAX_STDERR_BYTES`]),
+/// or `None` if the timeout was exceeded.
+///
+/// # Errors
+/// The I/O error from creating the temp files or spawning `python3`, or an error with the
+/// manager's stderr if it exited without writing a report.
+pub(crate) fn run_managed(
+    program: &[&OsStr],
+    options: &SandboxOptions,
+) -> io::Result<Option<Output>> {
+    let dir = temp_root(options);
+    let manager = safe_temp_file(MANAGER_SCRIPT, ".py", dir)?;
+    let report_file = Builder::new().suffix(".json").tempfile_in(dir)?;
+
+    let mut command = Command::new("python3");
+    command
+        .arg("-u")
+        .arg(manager.path())
+        .arg(report_file.path())
+        .arg(options.timeout.to_string())
+        .arg((options.memory_limit_mb * 1_000_000).to_string())
+        .arg(options.cpu_time_limit.to_string())
+        .arg(options.max_open_files.to_string())
+        .arg(MAX_FILE_BYTES.to_string())
+        .args(program)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("PYTHONPATH", "")
+        .process_group(0);
+    if options.stdin_input.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let mut child = command.spawn()?;
+    feed_stdin(&mut child, options);
+
+    let timeout = Duration::from_secs(options.timeout + MANAGER_GRACE_SECS);
+    let manager_output = wait_with_output(&mut child, timeout)?;
+    // SAFETY: sends SIGKILL to the manager's own process group (the manager's pid)
+    unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) };
+    let Some(manager_output) = manager_output else {
+        let _ = child.wait();
+        return Ok(None);
+    };
+
+    let report = std::fs::read_to_string(report_file.path())?;
+    if report.is_empty() {
+        return Err(io::Error::other(format!(
+            "sandbox manager exited without a report: {}",
+            String::from_utf8_lossy(&manager_output.stderr).trim()
+        )));
+    }
+    let report: serde_json::Value = serde_json::from_str(&report).map_err(io::Error::other)?;
+    if report["timed_out"].as_bool() == Some(true) {
+        return Ok(None);
+    }
+
+    let status = match report["returncode"].as_i64() {
+        Some(code) if code < 0 => ExitStatus::from_raw(-code as i32), // Killed by signal -code
+        Some(code) => ExitStatus::from_raw((code as i32 & 0xff) << 8),
+        None => return Err(io::Error::other("sandbox manager reported no exit code")),
+    };
+    let stdout = report["stdout"]
+        .as_str()
+        .unwrap_or_default()
+        .as_bytes()
+        .to_vec();
+    let mut stderr = report["stderr"]
+        .as_str()
+        .unwrap_or_default()
+        .as_bytes()
+        .to_vec();
+    if stderr.len() > MAX_STDERR_BYTES {
+        stderr.drain(..stderr.len() - MAX_STDERR_BYTES);
+    }
+    Ok(Some(Output {
+        status,
+        stdout,
+        stderr,
+    }))
+}
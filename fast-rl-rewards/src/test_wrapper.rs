@@ -15,45 +15,563 @@
 //!     try:
 //!         assert candidate(1, 2) == 3
 //!         _results.append(True)
-//!     except:
+//!     except Exception:
 //!         _results.append(False)
 //!     try:
 //!         assert candidate(0, 0) == 0
 //!         _results.append(True)
-//!     except:
+//!     except Exception:
 //!         _results.append(False)
 //!     return _results
 //!
-//! _test_results = check(add)
+//! import sys
+//! try:
+//!     _test_results = check(add)
+//! except Exception:
+//!     print("TESTS_PASSED:0/2")
+//!     sys.exit(1)
 //! _passed = sum(_test_results)
 //! _total = len(_test_results)
-//! print(f"TEST_PASSED:{_passed}/{_total}")
-//! exit(0 if _passed == _total else 1)
+//! print(f"TESTS_PASSED:{_passed}/{_total}")
+//! sys.exit(0 if _passed == _total else 1)
 //! ```
+//!
+//! If `check` itself raises before returning (e.g. setup code calling an undefined helper),
+//! every assertion counts as failed.
+//!
+//! Rust tests get the same treatment from [`wrap_rust_tests_for_complete_execution`]: each
+//! `assert!`-style macro is wrapped in `catch_unwind`, and a generated `main` prints the
+//! `TESTS_PASSED` line.
+//!
+//! Only `Exception` subclasses count as failed assertions: `SystemExit` and
+//! `KeyboardInterrupt` propagate, and the script then ends without a `TESTS_PASSED` line
+//! (which scores 0.0).
 
 use once_cell::sync::Lazy;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 static ASSERT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\s*)(assert\s+.+)").unwrap());
-static CHECK_DEF_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"def\s+check\s*\(").unwrap());
+static FUNCTION_DEF_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:async\s+)?def\s+(\w+)\s*\(").unwrap());
 static INDENT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)").unwrap());
+static MAIN_GUARD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^if\s+__name__\s*==\s*['"]__main__['"]\s*:"#).unwrap());
+// Assignment to `_results` (plain, annotated or augmented, not a comparison)
+static RESULTS_ASSIGNMENT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*_results\s*(?::|[-+*/|&]?=(?:[^=]|$))").unwrap());
+// First parameter of a function definition, e.g. `candidate` in `def check(candidate):`
+static CHECK_PARAMETER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bdef\s+\w+\s*\(\s*([A-Za-z_]\w*)").unwrap());
+static METHOD_CALL_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b([A-Za-z_]\w*)\.([A-Za-z_]\w*)\s*\(").unwrap());
+
+/// Variable collecting the assertion results inside the check function.
+const RESULTS_VAR: &str = "_results";
+/// Replacement for [`RESULTS_VAR`] when the test code assigns a `_results` of its own.
+const FALLBACK_RESULTS_VAR: &str = "_test_results_internal_";
+
+/// Names recognized as the check function, in order of preference.
+pub const DEFAULT_CHECK_NAMES: [&str; 4] = ["check", "test", "validate", "verify"];
+
+// Patterns for detecting test styles other than `def check(...)`
+static UNITTEST_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*class\s+\w+\s*\(.*TestCase.*\)\s*:").unwrap());
+static PYTEST_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*def\s+test_\w*\s*\(").unwrap());
+static DOCTEST_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*>>>\s").unwrap());
+
+// Patterns for estimating test complexity
+static LOOP_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:async\s+)?(?:for|while)\b").unwrap());
+static COMPREHENSION_FOR_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bfor\b.+?\bin\b").unwrap());
+
+// Patterns for wrapping Rust tests
+static RUST_ASSERT_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*assert(?:_eq|_ne)?!\s*[(\[{]").unwrap());
+static RUST_FN_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?fn\s+(\w+)\s*\(").unwrap());
+static RUST_MOD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*\{").unwrap());
+static RUST_CANDIDATE_USE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bcandidate\b").unwrap());
+static RUST_CANDIDATE_BINDING_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\blet\s+(?:mut\s+)?candidate\b").unwrap());
+
+/// Literal lists with more elements than this count as large test inputs.
+pub const LARGE_LITERAL_ELEMENTS: usize = 100;
 
+// ==========================================================================================
+
+/// Style of a test code snippet. Only `Check` is supported by the wrapper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestStyle {
+    /// HumanEval-style `def check(candidate): assert ...` (or another of [`DEFAULT_CHECK_NAMES`])
+    Check,
+    /// Pytest-style `def test_*():` functions
+    Pytest,
+    /// `unittest.TestCase` subclasses
+    Unittest,
+    /// Doctest `>>>` examples
+    Doctest,
+    /// No recognizable test structure
+    Unknown,
+}
+
+impl fmt::Display for TestStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Check => "check",
+            Self::Pytest => "pytest",
+            Self::Unittest => "unittest",
+            Self::Doctest => "doctest",
+            Self::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Result of [`validate_test_code`].
+#[derive(Clone, Debug)]
+pub struct ValidationResult {
+    /// True if the test code can be wrapped and will report meaningful results.
+    pub is_valid: bool,
+    /// Detected test style.
+    pub detected_style: TestStyle,
+    /// Number of `assert` statements found.
+    pub assertion_count: usize,
+    /// Human-readable descriptions of the problems found.
+    pub warnings: Vec<String>,
+}
+
+/// Check test code before wrapping it.
+///
+/// Detects the test style and counts assertions so that unsupported or empty tests can be
+/// caught before spending sandbox resources on them.
+pub fn validate_test_code(test_code: &str) -> ValidationResult {
+    let check_name = find_check_function(test_code, &DEFAULT_CHECK_NAMES);
+
+    // `Check` takes precedence: a check function may use helpers that look like other styles
+    let detected_style = if check_name.is_some() {
+        TestStyle::Check
+    } else if UNITTEST_PATTERN.is_match(test_code) {
+        TestStyle::Unittest
+    } else if PYTEST_PATTERN.is_match(test_code) {
+        TestStyle::Pytest
+    } else if DOCTEST_PATTERN.is_match(test_code) {
+        TestStyle::Doctest
+    } else {
+        TestStyle::Unknown
+    };
+
+    let assertion_count = count_assertions(test_code);
+    let mut warnings = Vec::new();
+
+    if assertion_count == 0 {
+        warnings.push("No assertions found".to_string());
+    }
+    if detected_style != TestStyle::Check {
+        warnings.push(format!(
+            "Unsupported test format '{}': expected a `def check(candidate):` function",
+            detected_style
+        ));
+    }
+    if let Some(name) = check_name
+        && defined_functions(test_code)
+            .filter(|def| *def == name)
+            .count()
+            > 1
+    {
+        warnings.push(format!("Multiple `{}` functions found", name));
+    }
+
+    ValidationResult {
+        is_valid: detected_style == TestStyle::Check && assertion_count > 0,
+        detected_style,
+        assertion_count,
+        warnings,
+    }
+}
+
+/// Number of `assert` statements in `test_code` (the `tests_total` to expect when every
+/// assertion is in the check function and runs once).
+///
+/// Statements are found as when wrapping: comment lines and the `__main__` guard are
+/// skipped, and an assertion continued over several lines counts once.
+///
+/// Useful for dataset quality checks: problems with fewer than 2 assertions are weak tests.
+#[pyfunction]
+pub fn count_assertions(test_code: &str) -> usize {
+    let mut count = 0;
+    let mut lines = strip_main_guard(test_code).into_iter();
+    while let Some(line) = lines.next() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        if let Some(caps) = ASSERT_PATTERN.captures(line) {
+            take_continuation_lines(&caps[2], &mut lines);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Python wrapper for [`validate_test_code`].
+///
+/// # Returns
+/// Dict with keys `is_valid` (bool), `detected_style` (one of "check", "pytest", "unittest",
+/// "doctest", "unknown"), `assertion_count` (int), and `warnings` (list of str).
+#[pyfunction]
+#[pyo3(name = "validate_test_code")]
+pub fn py_validate_test_code<'py>(
+    py: Python<'py>,
+    test_code: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let result = validate_test_code(test_code);
+
+    let dict = PyDict::new(py);
+    dict.set_item("is_valid", result.is_valid)?;
+    dict.set_item("detected_style", result.detected_style.to_string())?;
+    dict.set_item("assertion_count", result.assertion_count)?;
+    dict.set_item("warnings", result.warnings)?;
+    Ok(dict)
+}
+
+/// Expected running time of a test, from [`estimate_test_complexity`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TestComplexity {
+    /// None of the slow patterns
+    Low,
+    /// One slow pattern
+    Medium,
+    /// Several slow patterns
+    High,
+}
+
+impl TestComplexity {
+    /// Factor applied to the timeouts with [`adaptive_timeout`](crate::evaluator::EvaluatorConfig::adaptive_timeout).
+    pub fn timeout_multiplier(self) -> f64 {
+        match self {
+            Self::Low => 1.0,
+            Self::Medium => 1.5,
+            Self::High => 2.0,
+        }
+    }
+}
+
+impl fmt::Display for TestComplexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Estimate how long `test_code` will take to run, before executing it.
+///
+/// Counts three patterns that usually mean large inputs or expensive computations: a
+/// literal list with more than [`LARGE_LITERAL_ELEMENTS`] elements, nested loops
+/// (including comprehensions with several `for` clauses), and a recursive helper function.
+/// None gives `Low`, one gives `Medium` and more give `High`. This is a heuristic on the
+/// source text: it says nothing about the solution under test.
+pub fn estimate_test_complexity(test_code: &str) -> TestComplexity {
+    let signals = [
+        has_large_literal(test_code),
+        has_nested_loops(test_code),
+        has_recursion(test_code),
+    ];
+    match signals.iter().filter(|&&signal| signal).count() {
+        0 => TestComplexity::Low,
+        1 => TestComplexity::Medium,
+        _ => TestComplexity::High,
+    }
+}
+
+/// Python wrapper for [`estimate_test_complexity`].
+///
+/// # Returns
+/// One of "low", "medium", "high".
+#[pyfunction]
+#[pyo3(name = "estimate_test_complexity")]
+pub fn py_estimate_test_complexity(test_code: &str) -> String {
+    estimate_test_complexity(test_code).to_string()
+}
+
+/// True if a `[...]` literal has more than [`LARGE_LITERAL_ELEMENTS`] top-level elements.
+/// Brackets inside string literals are ignored.
+fn has_large_literal(test_code: &str) -> bool {
+    // Element counts of the currently open brackets (`None` for `(` and `{`)
+    let mut open: Vec<Option<usize>> = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+
+    for c in test_code.chars() {
+        if let Some(open_quote) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == open_quote => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '[' => open.push(Some(1)),
+            '(' | '{' => open.push(None),
+            ',' => {
+                if let Some(Some(count)) = open.last_mut() {
+                    *count += 1;
+                }
+            }
+            ']' | ')' | '}' => {
+                if let Some(Some(count)) = open.pop()
+                    && count > LARGE_LITERAL_ELEMENTS
+                {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// True if a `for`/`while` loop is nested in another, or a line has several `for` clauses.
+fn has_nested_loops(test_code: &str) -> bool {
+    // Indentation of the enclosing loops
+    let mut loop_indents: Vec<usize> = Vec::new();
+
+    for line in test_code.lines() {
+        let content = line.split('#').next().unwrap_or_default();
+        if content.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        while loop_indents.last().is_some_and(|&outer| outer >= indent) {
+            loop_indents.pop();
+        }
+
+        if LOOP_PATTERN.is_match(content) {
+            if !loop_indents.is_empty() {
+                return true;
+            }
+            loop_indents.push(indent);
+        }
+        if COMPREHENSION_FOR_PATTERN.find_iter(content).count() >= 2 {
+            return true;
+        }
+    }
+    false
+}
+
+/// True if a function defined in `test_code` calls itself in its body.
+fn has_recursion(test_code: &str) -> bool {
+    let lines: Vec<&str> = test_code.lines().collect();
+    lines.iter().enumerate().any(|(i, line)| {
+        let Some(caps) = FUNCTION_DEF_PATTERN.captures(line) else {
+            return false;
+        };
+        let indent = line.len() - line.trim_start().len();
+        let call = Regex::new(&format!(r"\b{}\s*\(", regex::escape(&caps[1]))).unwrap();
+        lines[i + 1..]
+            .iter()
+            .take_while(|body_line| {
+                body_line.trim().is_empty()
+                    || body_line.len() - body_line.trim_start().len() > indent
+            })
+            .any(|body_line| call.is_match(body_line.split('#').next().unwrap_or_default()))
+    })
+}
+
+// ==========================================================================================
+
+/// The check function is the first of `check_names` (default: [`DEFAULT_CHECK_NAMES`]) that
+/// the test code defines, so helpers with another listed name are left alone. It is called
+/// with the entry point as its only positional argument, whatever the parameter is named.
+///
 /// # Arguments:
 /// - `test_code`: Original test function (usually "def check(candidate): ...")
 /// - `entry_point`: How to call the function (e.g., "add" or "Solution().method")
+/// - `check_names`: Accepted names of the check function (default: check, test, validate, verify)
 ///
 /// # Returns:
 /// Transformed test code that runs all tests and prints "TEST_PASSED:X/Y"
 #[pyfunction]
-pub fn wrap_tests_for_complete_execution(test_code: &str, entry_point: &str) -> String {
-    // Early return if no assertions to wrap
-    if !ASSERT_PATTERN.is_match(test_code) {
+#[pyo3(signature = (test_code, entry_point, check_names=None))]
+pub fn wrap_tests_for_complete_execution(
+    test_code: &str,
+    entry_point: &str,
+    check_names: Option<Vec<String>>,
+) -> String {
+    match check_names {
+        Some(names) => wrap_tests(test_code, entry_point, None, &names),
+        None => wrap_tests(test_code, entry_point, None, &DEFAULT_CHECK_NAMES),
+    }
+}
+
+/// Rust entry point for [`wrap_tests_for_complete_execution`] with explicit check names.
+pub fn wrap_tests_with_check_names<S: AsRef<str>>(
+    test_code: &str,
+    entry_point: &str,
+    check_names: &[S],
+) -> String {
+    wrap_tests(test_code, entry_point, None, check_names)
+}
+
+/// Like [`wrap_tests_for_complete_execution`], but each assertion also gets its own timeout.
+///
+/// A `SIGALRM` timer (`signal.setitimer`, so sub-second values work) is armed before each
+/// assertion and cancelled after it; an assertion that runs too long (e.g., an infinite
+/// loop in the candidate) raises `TimeoutError` and counts as failed, and the remaining
+/// assertions still run. A `per_assert_timeout_ms` of 0 disables the per-assertion timeout.
+///
+/// Only works on Unix, in the main thread of the process running the tests.
+///
+/// # Arguments:
+/// - `test_code`: Original test function (usually "def check(candidate): ...")
+/// - `entry_point`: How to call the function (e.g., "add" or "Solution().method")
+/// - `per_assert_timeout_ms`: Timeout for each assertion in milliseconds
+#[pyfunction]
+pub fn wrap_tests_with_timeout_per_assertion(
+    test_code: &str,
+    entry_point: &str,
+    per_assert_timeout_ms: u64,
+) -> String {
+    let timeout = (per_assert_timeout_ms > 0).then_some(per_assert_timeout_ms);
+    wrap_tests(test_code, entry_point, timeout, &DEFAULT_CHECK_NAMES)
+}
+
+/// Names of all functions defined in `test_code`, in order.
+fn defined_functions(test_code: &str) -> impl Iterator<Item = &str> {
+    test_code.split('\n').filter_map(|line| {
+        FUNCTION_DEF_PATTERN
+            .captures(line)
+            .map(|caps| caps.get(1).unwrap().as_str())
+    })
+}
+
+/// The first of `check_names` that `test_code` defines as a function.
+fn find_check_function<'a, S: AsRef<str>>(
+    test_code: &str,
+    check_names: &'a [S],
+) -> Option<&'a str> {
+    let defined: Vec<&str> = defined_functions(test_code).collect();
+    check_names
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|name| defined.contains(name))
+}
+
+/// True if `test_code` defines one of `check_names` as a function but never prints a
+/// `TESTS_PASSED` line, i.e. it still needs wrapping before it can report its results.
+pub fn is_unwrapped_check_test<S: AsRef<str>>(test_code: &str, check_names: &[S]) -> bool {
+    find_check_function(test_code, check_names).is_some() && !test_code.contains("TESTS_PASSED")
+}
+
+/// True if `test_code` is the output of a previous wrap: it collects assertion results in
+/// one of the results variables and prints a `TESTS_PASSED` line. Wrapping it again would
+/// nest the result collection and print two lines.
+fn is_already_wrapped(test_code: &str) -> bool {
+    test_code.contains("TESTS_PASSED:")
+        && [RESULTS_VAR, FALLBACK_RESULTS_VAR]
+            .iter()
+            .any(|var| test_code.contains(&format!("{} = []", var)))
+}
+
+/// True if the check function (the first of `check_names` that `test_code` defines) calls
+/// two or more distinct methods on the same object created by its parameter, e.g.
+/// `cache = candidate(2)`, then `cache.put(1, 1)` and `cache.get(1)`: the pattern of tests
+/// for classes like `LRUCache` whose methods must be exercised together on one instance.
+///
+/// Calls on other objects, such as `math.isclose(...)` and `math.floor(...)`, do not count.
+pub fn is_multi_method_test<S: AsRef<str>>(test_code: &str, check_names: &[S]) -> bool {
+    let Some(check_name) = find_check_function(test_code, check_names) else {
+        return false;
+    };
+    let lines: Vec<&str> = test_code.lines().collect();
+    let Some(def_index) = lines.iter().position(|line| {
+        FUNCTION_DEF_PATTERN
+            .captures(line)
+            .is_some_and(|caps| &caps[1] == check_name)
+    }) else {
+        return false;
+    };
+    let Some(parameter) = CHECK_PARAMETER_PATTERN
+        .captures(lines[def_index])
+        .map(|caps| caps.get(1).unwrap().as_str())
+    else {
+        return false;
+    };
+
+    let indent = lines[def_index].len() - lines[def_index].trim_start().len();
+    let body: Vec<&str> = lines[def_index + 1..]
+        .iter()
+        .take_while(|line| line.trim().is_empty() || line.len() - line.trim_start().len() > indent)
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .collect();
+
+    // Objects constructed by the check function's parameter, e.g. `cache = candidate(2)`
+    let instance_assignment = Regex::new(&format!(
+        r"\b([A-Za-z_]\w*)\s*=\s*{}\s*\(",
+        regex::escape(parameter)
+    ))
+    .unwrap();
+    let instances: HashSet<&str> = body
+        .iter()
+        .flat_map(|line| instance_assignment.captures_iter(line))
+        .map(|caps| caps.get(1).unwrap().as_str())
+        .collect();
+
+    let mut methods_by_instance: HashMap<&str, HashSet<&str>> = HashMap::new();
+    body.iter()
+        .flat_map(|line| METHOD_CALL_PATTERN.captures_iter(line))
+        .any(|caps| {
+            let receiver = caps.get(1).unwrap().as_str();
+            if !instances.contains(receiver) {
+                return false;
+            }
+            let methods = methods_by_instance.entry(receiver).or_default();
+            methods.insert(caps.get(2).unwrap().as_str());
+            methods.len() >= 2
+        })
+}
+
+/// Shared implementation of the wrappers, with an optional per-assertion timeout in ms.
+fn wrap_tests<S: AsRef<str>>(
+    test_code: &str,
+    entry_point: &str,
+    assertion_timeout_ms: Option<u64>,
+    check_names: &[S],
+) -> String {
+    // Early return if no assertions to wrap, or if they are wrapped already
+    if !ASSERT_PATTERN.is_match(test_code) || is_already_wrapped(test_code) {
         return test_code.to_string();
     }
 
-    let lines: Vec<&str> = test_code.split('\n').collect();
-    let assert_count = ASSERT_PATTERN.find_iter(test_code).count();
+    let check_name = find_check_function(test_code, check_names).unwrap_or("check");
+
+    // Do not clobber a `_results` variable the tests use for something else
+    let results_var = if RESULTS_ASSIGNMENT_PATTERN.is_match(test_code) {
+        tracing::warn!(
+            results_var = FALLBACK_RESULTS_VAR,
+            "Test code assigns `_results`; collecting assertion results in another variable"
+        );
+        FALLBACK_RESULTS_VAR
+    } else {
+        RESULTS_VAR
+    };
+    let return_results = format!("return {}", results_var);
+
+    let lines = strip_main_guard(test_code);
+    let assert_count = count_assertions(test_code);
 
     // Pre-allocate capacity for better performance.
     //
@@ -64,7 +582,7 @@ pub fn wrap_tests_for_complete_execution(test_code: &str, entry_point: &str) ->
     //   try:                                # +1
     //       assert candidate(1, 2) == 3     # (replaces original)
     //       _results.append(True)           # +1
-    //   except:                             # +1
+    //   except Exception:                   # +1
     //       _results.append(False)          # +1
     //   Total: +4 lines per assertion
     //
@@ -72,10 +590,33 @@ pub fn wrap_tests_for_complete_execution(test_code: &str, entry_point: &str) ->
     let mut wrapped_lines: Vec<String> = Vec::with_capacity(lines.len() + assert_count * 4 + 10);
     let mut in_check_function = false;
     let mut check_function_indent = String::new();
+    // Blank and comment lines inside check, held until the next code line
+    let mut held_lines: Vec<&str> = Vec::new();
+    // Indentation width of a function nested inside check (its returns are left alone)
+    let mut nested_def_indent: Option<usize> = None;
+
+    // Install the SIGALRM handler that turns an expired assertion timer into an exception
+    if assertion_timeout_ms.is_some() {
+        wrapped_lines.push("import signal as _signal".to_string());
+        wrapped_lines.push(String::new());
+        wrapped_lines.push("def _assertion_timeout(_signum, _frame):".to_string());
+        wrapped_lines.push(r#"    raise TimeoutError("assertion timed out")"#.to_string());
+        wrapped_lines.push(String::new());
+        wrapped_lines.push("_signal.signal(_signal.SIGALRM, _assertion_timeout)".to_string());
+        wrapped_lines.push(String::new());
+    }
 
-    for line in lines {
-        // 1. Detect check function definition
-        if CHECK_DEF_PATTERN.is_match(line) {
+    let mut lines = lines.into_iter();
+    while let Some(line) = lines.next() {
+        // 1. Detect check function definition (a function nested inside check is only a
+        // helper, even if it reuses the name)
+        let nested_in_check =
+            in_check_function && line.len() - line.trim_start().len() > check_function_indent.len();
+        if !nested_in_check
+            && FUNCTION_DEF_PATTERN
+                .captures(line)
+                .is_some_and(|caps| &caps[1] == check_name)
+        {
             in_check_function = true;
 
             // Extract indentation level
@@ -84,47 +625,118 @@ pub fn wrap_tests_for_complete_execution(test_code: &str, entry_point: &str) ->
             }
 
             wrapped_lines.push(line.to_string());
-            wrapped_lines.push(format!("{}    _results = []", check_function_indent));
+            wrapped_lines.push(format!("{}    {} = []", check_function_indent, results_var));
+            nested_def_indent = None;
             continue;
         }
 
-        // 2. Wrap assertions in try/except blocks
-        if let Some(caps) = ASSERT_PATTERN.captures(line) {
-            if in_check_function {
-                let indent = &caps[1];
-                let assertion = &caps[2];
+        if in_check_function {
+            let trimmed = line.trim();
 
-                wrapped_lines.push(format!("{}try:", indent));
-                wrapped_lines.push(format!("{}    {}", indent, assertion));
-                wrapped_lines.push(format!("{}    _results.append(True)", indent));
-                wrapped_lines.push(format!("{}except:", indent));
-                wrapped_lines.push(format!("{}    _results.append(False)", indent));
+            // 2. Hold blank and comment lines: they do not end the function on their own
+            // (blank lines often separate closures or setup code from the assertions), and
+            // comments directly above an assertion move into its `try:` block
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                held_lines.push(line);
                 continue;
             }
-        }
 
-        // 3. Detect end of check function (dedent or empty line)
-        if in_check_function {
-            let trimmed = line.trim();
-
-            // Function ends when we dedent or hit empty line
-            let function_ended = trimmed.is_empty()
-                || (!trimmed.is_empty()
-                    && !line.starts_with(&format!("{} ", check_function_indent))
-                    && !line.starts_with(&format!("{}\t", check_function_indent)));
+            // 3. Detect end of check function (dedent to or past the `def check` level)
+            let function_ended = !line.starts_with(&format!("{} ", check_function_indent))
+                && !line.starts_with(&format!("{}\t", check_function_indent));
 
             if function_ended {
-                // Add return statement before exiting function
-                wrapped_lines.push(format!("{}    return _results", check_function_indent));
+                // Add return statement before exiting function (trailing comments stay inside)
+                flush_held_comments(&mut wrapped_lines, &mut held_lines);
+                wrapped_lines.push(format!("{}    {}", check_function_indent, return_results));
                 wrapped_lines.push(String::new());
                 in_check_function = false;
 
-                // Preserve current line if not empty
-                if !trimmed.is_empty() {
+                wrapped_lines.push(line.to_string());
+                continue;
+            }
+
+            // Still inside the function body - restore the held lines, except for the
+            // comments directly above an assertion
+            let assertion = ASSERT_PATTERN.captures(line);
+            let comments_above = if assertion.is_some() {
+                held_lines
+                    .iter()
+                    .rev()
+                    .take_while(|held| !held.trim().is_empty())
+                    .count()
+            } else {
+                0
+            };
+            let assertion_comments = held_lines.split_off(held_lines.len() - comments_above);
+            wrapped_lines.extend(held_lines.drain(..).map(str::to_string));
+
+            // 4. Rewrite early returns in the check body so collected results are kept
+            let indent_width = line.len() - line.trim_start().len();
+            if nested_def_indent.is_some_and(|nested| indent_width <= nested) {
+                nested_def_indent = None;
+            }
+            if nested_def_indent.is_none() {
+                if trimmed.starts_with("def ") || trimmed.starts_with("async def ") {
+                    nested_def_indent = Some(indent_width);
+                } else if is_return_statement(trimmed) && trimmed != return_results {
+                    wrapped_lines.push(format!("{}{}", &line[..indent_width], return_results));
+                    continue;
+                }
+            }
+
+            // 5. `with` blocks (e.g. `with patch(...):` from unittest.mock): assertions in
+            // the block are wrapped at their own indentation like any other, but a header
+            // continued over several lines is kept verbatim (its continuation lines may be
+            // less indented than the block and must not end the function), and an assertion
+            // on the header line itself is moved into the block so that it can be wrapped
+            if is_with_statement(trimmed) {
+                let header_end = with_header_end(line);
+                let body = header_end.map_or("", |end| line[end + 1..].trim());
+                if let Some(end) = header_end
+                    && body.starts_with("assert")
+                    && ASSERT_PATTERN.is_match(body)
+                {
+                    wrapped_lines.push(line[..=end].to_string());
+                    let indent = format!("{}    ", &line[..indent_width]);
+                    let continuation = take_continuation_lines(body, &mut lines);
+                    push_wrapped_assertion(
+                        &mut wrapped_lines,
+                        &indent,
+                        body,
+                        &continuation,
+                        &assertion_comments,
+                        results_var,
+                        assertion_timeout_ms,
+                    );
+                } else {
+                    wrapped_lines.extend(assertion_comments.iter().map(|line| line.to_string()));
                     wrapped_lines.push(line.to_string());
+                    if header_end.is_none() {
+                        let continuation = take_continuation_lines(line, &mut lines);
+                        wrapped_lines.extend(continuation.iter().map(|line| line.to_string()));
+                    }
                 }
                 continue;
             }
+
+            // 6. Wrap assertions in try/except blocks (with their continuation lines, e.g. a
+            // long expected list split over several lines)
+            if let Some(caps) = assertion {
+                let indent = &caps[1];
+                let assertion = &caps[2];
+                let continuation = take_continuation_lines(assertion, &mut lines);
+                push_wrapped_assertion(
+                    &mut wrapped_lines,
+                    indent,
+                    assertion,
+                    &continuation,
+                    &assertion_comments,
+                    results_var,
+                    assertion_timeout_ms,
+                );
+                continue;
+            }
         }
 
         // Regular line - pass through unchanged
@@ -133,18 +745,731 @@ pub fn wrap_tests_for_complete_execution(test_code: &str, entry_point: &str) ->
 
     // If function never explicitly ended, close it
     if in_check_function {
-        wrapped_lines.push(format!("{}    return _results", check_function_indent));
+        flush_held_comments(&mut wrapped_lines, &mut held_lines);
+        wrapped_lines.push(format!("{}    {}", check_function_indent, return_results));
         wrapped_lines.push(String::new());
     }
 
-    // 4. Add execution and reporting code
-    wrapped_lines.push(format!("_test_results = check({})", entry_point));
+    // 7. Add execution and reporting code
+    // An error in the check function before its first assertion (e.g. calling an undefined
+    // helper) still reports the expected number of tests, all failed.
+    wrapped_lines.push("import sys".to_string());
+    wrapped_lines.push("try:".to_string());
+    wrapped_lines.push(format!(
+        "    _test_results = {}({})",
+        check_name, entry_point
+    ));
+    wrapped_lines.push("except Exception:".to_string());
+    wrapped_lines.push(format!("    print(\"TESTS_PASSED:0/{}\")", assert_count));
+    wrapped_lines.push("    sys.exit(1)".to_string());
     wrapped_lines.push(String::new());
     wrapped_lines.push("# Report test results".to_string());
     wrapped_lines.push("_passed = sum(_test_results)".to_string());
     wrapped_lines.push("_total = len(_test_results)".to_string());
     wrapped_lines.push(r#"print(f"TESTS_PASSED:{_passed}/{_total}")"#.to_string());
-    wrapped_lines.push("exit(0 if _passed == _total else 1)".to_string());
+    wrapped_lines.push("sys.exit(0 if _passed == _total else 1)".to_string());
 
     wrapped_lines.join("\n")
 }
+
+/// Append `assertion` (followed by its `continuation` lines) wrapped in a try/except block
+/// at `indent` that records whether it passed in `results_var`; the `comments` directly
+/// above the assertion move into the `try:` block.
+fn push_wrapped_assertion(
+    wrapped_lines: &mut Vec<String>,
+    indent: &str,
+    assertion: &str,
+    continuation: &[&str],
+    comments: &[&str],
+    results_var: &str,
+    assertion_timeout_ms: Option<u64>,
+) {
+    wrapped_lines.push(format!("{}try:", indent));
+    for comment in comments {
+        wrapped_lines.push(format!("{}    {}", indent, comment.trim()));
+    }
+    if let Some(timeout_ms) = assertion_timeout_ms {
+        wrapped_lines.push(format!(
+            "{}    _signal.setitimer(_signal.ITIMER_REAL, {})",
+            indent,
+            timeout_ms as f64 / 1000.0
+        ));
+        wrapped_lines.push(format!("{}    {}", indent, assertion));
+        wrapped_lines.extend(continuation.iter().map(|line| line.to_string()));
+        wrapped_lines.push(format!(
+            "{}    _signal.setitimer(_signal.ITIMER_REAL, 0)",
+            indent
+        ));
+    } else {
+        wrapped_lines.push(format!("{}    {}", indent, assertion));
+        wrapped_lines.extend(continuation.iter().map(|line| line.to_string()));
+    }
+    wrapped_lines.push(format!("{}    {}.append(True)", indent, results_var));
+    wrapped_lines.push(format!("{}except Exception:", indent));
+    if assertion_timeout_ms.is_some() {
+        wrapped_lines.push(format!(
+            "{}    _signal.setitimer(_signal.ITIMER_REAL, 0)",
+            indent
+        ));
+    }
+    wrapped_lines.push(format!("{}    {}.append(False)", indent, results_var));
+}
+
+/// Lines of `test_code` without a top-level `if __name__ == "__main__":` block.
+///
+/// Such blocks usually call the check function themselves (`check(add)`), which would run
+/// the tests a second time alongside the call appended by the wrapper.
+fn strip_main_guard(test_code: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut in_main_guard = false;
+    for line in test_code.split('\n') {
+        if MAIN_GUARD_PATTERN.is_match(line) {
+            in_main_guard = true;
+            continue;
+        }
+        // The block ends at the next non-blank line back at column 0
+        if in_main_guard {
+            if line.trim().is_empty() || line.starts_with([' ', '\t']) {
+                continue;
+            }
+            in_main_guard = false;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lines continuing the statement started by `first_line`: taken from `lines` while
+/// brackets are unbalanced or the previous line ends with a backslash.
+///
+/// The continuation lines are kept verbatim: inside brackets (or after a backslash) their
+/// indentation does not matter to Python.
+fn take_continuation_lines<'a>(
+    first_line: &str,
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    let mut continuation = Vec::new();
+    let mut balance = bracket_balance(first_line);
+    let mut continued = first_line.trim_end().ends_with('\\');
+    while balance > 0 || continued {
+        let Some(line) = lines.next() else {
+            break;
+        };
+        balance += bracket_balance(line);
+        continued = line.trim_end().ends_with('\\');
+        continuation.push(line);
+    }
+    continuation
+}
+
+/// Net number of `(`, `[` and `{` left open by a line, ignoring brackets in string
+/// literals and comments.
+fn bracket_balance(line: &str) -> i32 {
+    let mut balance = 0;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next(); // Escaped character
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '#' => break,
+                '(' | '[' | '{' => balance += 1,
+                ')' | ']' | '}' => balance -= 1,
+                _ => {}
+            },
+        }
+    }
+    balance
+}
+
+/// Emit the held comment lines (dropping held blank lines) before the function is closed.
+fn flush_held_comments(wrapped_lines: &mut Vec<String>, held_lines: &mut Vec<&str>) {
+    wrapped_lines.extend(
+        held_lines
+            .drain(..)
+            .filter(|held| !held.trim().is_empty())
+            .map(str::to_string),
+    );
+}
+
+/// Whether a stripped line starts a `with` (or `async with`) statement.
+fn is_with_statement(trimmed: &str) -> bool {
+    let rest = trimmed
+        .strip_prefix("async ")
+        .map_or(trimmed, str::trim_start);
+    rest.strip_prefix("with")
+        .is_some_and(|rest| rest.starts_with([' ', '(', '\t']))
+}
+
+/// Byte offset of the colon ending the `with` header on `line` (the first `:` outside
+/// brackets and string literals), or `None` if the header continues on the next lines.
+fn with_header_end(line: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = line.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next(); // Escaped character
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '#' => return None,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ':' if depth == 0 => return Some(idx),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+/// Whether a stripped line is a `return` statement (bare or with a value).
+fn is_return_statement(trimmed: &str) -> bool {
+    trimmed
+        .strip_prefix("return")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '(', '\t', ';', '#']))
+}
+
+// ==========================================================================================
+
+/// Counters and assertion runner prepended to wrapped Rust tests.
+const RUST_TEST_PRELUDE: &str = "\
+static __FASTRL_PASSED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static __FASTRL_TOTAL: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn __fastrl_check<F: FnOnce()>(assertion: F) {
+    __FASTRL_TOTAL.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    if std::panic::catch_unwind(std::panic::AssertUnwindSafe(assertion)).is_ok() {
+        __FASTRL_PASSED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+";
+
+/// Wrap Rust test code so that every assertion runs and the results are printed as
+/// `TESTS_PASSED:X/Y`, like [`wrap_tests_for_complete_execution`] does for Python.
+///
+/// Two layouts are supported:
+/// - Assertions in `fn main()`, as in MultiPL-E (`let candidate = add;` followed by
+///   `assert_eq!(candidate(1, 2), 3);`). If the tests use `candidate` without binding it,
+///   `let candidate = <entry_point>;` is added at the start of `main`.
+/// - `#[test]` functions, at the top level or in a module such as `#[cfg(test)] mod tests`.
+///   The attributes are removed and a generated `main` calls each test function.
+///
+/// Every `assert!`, `assert_eq!` and `assert_ne!` statement runs under `catch_unwind` and
+/// counts as one test. A panic outside an assertion stops the function it happens in, and
+/// the assertions that never ran count as failed. Code without assertions is returned
+/// unchanged.
+#[pyfunction]
+pub fn wrap_rust_tests_for_complete_execution(test_code: &str, entry_point: &str) -> String {
+    let assert_count = RUST_ASSERT_PATTERN.find_iter(test_code).count();
+    if assert_count == 0 {
+        return test_code.to_string();
+    }
+    let bind_candidate = !entry_point.trim().is_empty()
+        && RUST_CANDIDATE_USE_PATTERN.is_match(test_code)
+        && !RUST_CANDIDATE_BINDING_PATTERN.is_match(test_code);
+
+    let mut wrapped_lines = vec![RUST_TEST_PRELUDE.to_string()];
+    let mut test_functions = Vec::new();
+    let mut has_main = false;
+    let mut is_test = false;
+    // Open modules, with the brace depth of their bodies
+    let mut modules: Vec<(&str, i32)> = Vec::new();
+    let mut depth = 0;
+
+    let mut lines = test_code.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        let indent = &line[..line.len() - line.trim_start().len()];
+
+        // Test-only items would be compiled out of the binary
+        if trimmed == "#[cfg(test)]" {
+            continue;
+        }
+        if trimmed == "#[test]" {
+            is_test = true;
+            continue;
+        }
+
+        if RUST_ASSERT_PATTERN.is_match(line) {
+            let mut balance = rust_brace_balance(line).1;
+            wrapped_lines.push(format!("{}crate::__fastrl_check(|| {{", indent));
+            wrapped_lines.push(format!("    {}", line));
+            while balance > 0 {
+                let Some(continuation) = lines.next() else {
+                    break;
+                };
+                balance += rust_brace_balance(continuation).1;
+                wrapped_lines.push(format!("    {}", continuation));
+            }
+            wrapped_lines.push(format!("{}}});", indent));
+            continue;
+        }
+
+        let mut line = line.to_string();
+        // Modules and test functions are made public for the generated `main` to call
+        if let Some(caps) = RUST_MOD_PATTERN.captures(trimmed) {
+            modules.push((caps.get(1).unwrap().as_str(), depth + 1));
+            if !trimmed.starts_with("pub") {
+                line = format!("{}pub {}", indent, trimmed);
+            }
+        }
+        if let Some(caps) = RUST_FN_PATTERN.captures(trimmed) {
+            let name = caps[1].to_string();
+            if is_test {
+                let path = modules
+                    .iter()
+                    .map(|(module, _)| *module)
+                    .chain([name.as_str()]);
+                test_functions.push(path.collect::<Vec<_>>().join("::"));
+                if !trimmed.starts_with("pub") {
+                    line = format!("{}pub {}", indent, trimmed);
+                }
+            } else if name == "main" && depth == 0 {
+                has_main = true;
+                line = line.replacen("main", "__fastrl_main", 1);
+            }
+            is_test = false;
+        }
+
+        depth += rust_brace_balance(&line).0;
+        modules.retain(|(_, body_depth)| *body_depth <= depth);
+        let opens_main = has_main && depth == 1 && line.contains("fn __fastrl_main");
+        wrapped_lines.push(line);
+        if opens_main && bind_candidate {
+            wrapped_lines.push(format!("{}    let candidate = {};", indent, entry_point));
+        }
+    }
+
+    // Run the tests, reporting the assertions that never ran (after a panic) as failed
+    wrapped_lines.push(String::new());
+    wrapped_lines.push("fn main() {".to_string());
+    wrapped_lines.push("    std::panic::set_hook(Box::new(|_| {}));".to_string());
+    wrapped_lines.push("    let mut completed = true;".to_string());
+    let calls = has_main
+        .then(|| "__fastrl_main".to_string())
+        .into_iter()
+        .chain(test_functions);
+    for call in calls {
+        wrapped_lines.push(format!(
+            "    completed &= std::panic::catch_unwind({}).is_ok();",
+            call
+        ));
+    }
+    wrapped_lines.push(
+        "    let passed = __FASTRL_PASSED.load(std::sync::atomic::Ordering::SeqCst);".to_string(),
+    );
+    wrapped_lines.push(
+        "    let mut total = __FASTRL_TOTAL.load(std::sync::atomic::Ordering::SeqCst);".to_string(),
+    );
+    wrapped_lines.push("    if !completed {".to_string());
+    wrapped_lines.push(format!("        total = total.max({});", assert_count));
+    wrapped_lines.push("    }".to_string());
+    wrapped_lines.push(r#"    println!("TESTS_PASSED:{}/{}", passed, total);"#.to_string());
+    wrapped_lines.push(
+        "    std::process::exit(if completed && passed == total { 0 } else { 1 });".to_string(),
+    );
+    wrapped_lines.push("}".to_string());
+
+    wrapped_lines.join("\n")
+}
+
+/// Net number of braces, and of all brackets, left open by a line of Rust, ignoring
+/// string and character literals and `//` comments.
+fn rust_brace_balance(line: &str) -> (i32, i32) {
+    let (mut braces, mut brackets) = (0, 0);
+    let mut in_string = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next(); // Escaped character
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => break,
+            // A character literal ('{', '\'', '\n'), as opposed to a lifetime ('a)
+            '\'' => {
+                let rest: String = chars.clone().take(3).collect();
+                if rest.starts_with('\\') {
+                    chars.nth(2);
+                } else if rest.chars().nth(1) == Some('\'') {
+                    chars.nth(1);
+                }
+            }
+            '{' => (braces, brackets) = (braces + 1, brackets + 1),
+            '}' => (braces, brackets) = (braces - 1, brackets - 1),
+            '(' | '[' => brackets += 1,
+            ')' | ']' => brackets -= 1,
+            _ => {}
+        }
+    }
+    (braces, brackets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The wrapped check function (everything before the reporting code) for entry point `add`.
+    fn wrapped_check(test_code: &str) -> String {
+        let wrapped = wrap_tests_for_complete_execution(test_code, "add", None);
+        let (check, _) = wrapped
+            .split_once("import sys\n")
+            .expect("wrapped tests end with the reporting code");
+        check.trim_end().to_string()
+    }
+
+    #[test]
+    fn early_returns_keep_the_collected_results() {
+        let test_code = "def check(candidate):
+    if candidate is None:
+        return
+    assert candidate(1, 2) == 3
+    return True";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _results = []
+    if candidate is None:
+        return _results
+    try:
+        assert candidate(1, 2) == 3
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    return _results
+    return _results"
+        );
+    }
+
+    #[test]
+    fn comments_above_an_assertion_move_into_its_try_block() {
+        let test_code = "def check(candidate):
+    # Small numbers
+    assert candidate(1, 2) == 3
+
+    # Zero
+    # (identity)
+    assert candidate(0, 0) == 0
+    # Trailing note";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _results = []
+    try:
+        # Small numbers
+        assert candidate(1, 2) == 3
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+
+    try:
+        # Zero
+        # (identity)
+        assert candidate(0, 0) == 0
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    # Trailing note
+    return _results"
+        );
+    }
+
+    #[test]
+    fn functions_nested_in_check_stay_inside_it() {
+        // Returns of nested functions are their own, and a nested `check` is only a helper
+        let test_code = "def check(candidate):
+    def helper(x):
+        if x < 0:
+            return None
+        return candidate(x, x)
+    assert helper(1) == 2
+    def check(y):
+        return y
+    assert check(2) == 2";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _results = []
+    def helper(x):
+        if x < 0:
+            return None
+        return candidate(x, x)
+    try:
+        assert helper(1) == 2
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    def check(y):
+        return y
+    try:
+        assert check(2) == 2
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    return _results"
+        );
+    }
+
+    #[test]
+    fn helpers_defined_after_check_end_it() {
+        let test_code = "def check(candidate):
+    assert double(candidate, 1) == 2
+
+def double(candidate, x):
+    return candidate(x, x)";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _results = []
+    try:
+        assert double(candidate, 1) == 2
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    return _results
+
+def double(candidate, x):
+    return candidate(x, x)"
+        );
+    }
+
+    #[test]
+    fn main_guard_is_dropped_so_check_runs_once() {
+        for guard in ["if __name__ == \"__main__\":", "if __name__=='__main__':"] {
+            let test_code = format!(
+                "def check(candidate):
+    assert candidate(1, 2) == 3
+
+{guard}
+    check(add)
+
+    print(\"done\")
+CONSTANT = 1"
+            );
+            let wrapped = wrap_tests_for_complete_execution(&test_code, "add", None);
+            assert!(!wrapped.contains("__main__"), "{}", wrapped);
+            assert!(!wrapped.contains("print(\"done\")"), "{}", wrapped);
+            assert_eq!(wrapped.matches("check(add)").count(), 1, "{}", wrapped);
+            // Code after the block is kept
+            assert!(wrapped.contains("\nCONSTANT = 1\n"), "{}", wrapped);
+        }
+    }
+
+    #[test]
+    fn indented_main_guards_are_kept() {
+        // Only a top-level guard is the script entry point
+        let test_code = "def check(candidate):
+    if __name__ == \"__main__\":
+        assert candidate(1, 2) == 3";
+        assert!(
+            wrapped_check(test_code).contains("    if __name__ == \"__main__\":\n        try:")
+        );
+    }
+
+    #[test]
+    fn tests_using_results_get_another_results_variable() {
+        let test_code = "def check(candidate):
+    _results = [candidate(i, i) for i in range(3)]
+    assert _results == [0, 2, 4]";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _test_results_internal_ = []
+    _results = [candidate(i, i) for i in range(3)]
+    try:
+        assert _results == [0, 2, 4]
+        _test_results_internal_.append(True)
+    except Exception:
+        _test_results_internal_.append(False)
+    return _test_results_internal_"
+        );
+
+        for assignment in ["_results: list = []", "_results += [1]", "_results=None"] {
+            let test_code =
+                format!("def check(candidate):\n    {assignment}\n    assert candidate(1, 2) == 3");
+            assert!(
+                wrapped_check(&test_code).contains("_test_results_internal_ = []"),
+                "{}",
+                assignment
+            );
+        }
+    }
+
+    #[test]
+    fn reading_results_keeps_the_default_variable() {
+        let test_code = "def check(candidate):
+    assert candidate(1, 2) == 3
+    assert _results == []";
+        let wrapped = wrapped_check(test_code);
+        assert!(wrapped.contains("    _results = []"), "{}", wrapped);
+        assert!(!wrapped.contains(FALLBACK_RESULTS_VAR), "{}", wrapped);
+    }
+
+    #[test]
+    fn multi_line_expected_values_stay_in_one_try_block() {
+        let test_code = "def check(candidate):
+    assert candidate([1, 2, 3, 4, 5, 6, 7]) == [1, 2, 4, 8,
+        16, 32, 64]
+    assert candidate({'a': 1}) == {
+        'a': 2,
+    }
+    assert candidate((1, 2)) == (2,
+                                 4)
+    assert candidate(0) == \\
+        0";
+        assert_eq!(
+            wrapped_check(test_code),
+            "def check(candidate):
+    _results = []
+    try:
+        assert candidate([1, 2, 3, 4, 5, 6, 7]) == [1, 2, 4, 8,
+        16, 32, 64]
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    try:
+        assert candidate({'a': 1}) == {
+        'a': 2,
+    }
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    try:
+        assert candidate((1, 2)) == (2,
+                                 4)
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    try:
+        assert candidate(0) == \\
+        0
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    return _results"
+        );
+    }
+
+    #[test]
+    fn brackets_in_strings_and_comments_are_ignored() {
+        assert_eq!(bracket_balance("assert f('(') == '['  # ("), 0);
+        assert_eq!(bracket_balance(r#"assert f("\"(") == ["#), 1);
+        assert_eq!(bracket_balance("assert f(x) == 1"), 0);
+        assert_eq!(bracket_balance("    ]) == 2"), -2);
+    }
+
+    #[test]
+    fn wrapping_is_idempotent() {
+        let tests = [
+            "def check(candidate):\n    assert candidate(1, 2) == 3\n    assert candidate(0, 0) == 0",
+            "def check(candidate):\n    _results = candidate(1, 2)\n    assert _results == 3",
+        ];
+        for test_code in tests {
+            let once = wrap_tests_for_complete_execution(test_code, "add", None);
+            let twice = wrap_tests_for_complete_execution(&once, "add", None);
+            assert_eq!(twice, once);
+            assert_eq!(twice.matches("TESTS_PASSED:").count(), 2); // Failure and summary lines
+
+            let with_timeout = wrap_tests_with_timeout_per_assertion(test_code, "add", 500);
+            assert_eq!(
+                wrap_tests_with_timeout_per_assertion(&with_timeout, "add", 500),
+                with_timeout
+            );
+        }
+    }
+
+    #[test]
+    fn tests_printing_their_own_summary_are_still_wrapped() {
+        // A `TESTS_PASSED` line alone does not make the code a previous wrap
+        let test_code = "def check(candidate):
+    assert candidate(1, 2) == 3
+    print(\"TESTS_PASSED:1/1\")";
+        assert!(wrapped_check(test_code).contains("    _results = []"));
+    }
+
+    #[test]
+    fn assertions_in_mock_patch_blocks_are_wrapped_in_place() {
+        let test_code = r#"from unittest.mock import patch
+
+def check(candidate):
+    with patch("random.random", return_value=0.5):
+        assert candidate(1, 2) == 3
+        with patch("time.time") as clock:
+            clock.return_value = 0
+            assert candidate(0, 0) == 0
+    with patch(
+"os.getcwd", return_value="/"):
+        assert candidate(2, 2) == 4
+    with patch("a.b"): assert candidate(3, 3) == 6
+    assert candidate(4, 4) == 8"#;
+        assert_eq!(
+            wrapped_check(test_code),
+            r#"from unittest.mock import patch
+
+def check(candidate):
+    _results = []
+    with patch("random.random", return_value=0.5):
+        try:
+            assert candidate(1, 2) == 3
+            _results.append(True)
+        except Exception:
+            _results.append(False)
+        with patch("time.time") as clock:
+            clock.return_value = 0
+            try:
+                assert candidate(0, 0) == 0
+                _results.append(True)
+            except Exception:
+                _results.append(False)
+    with patch(
+"os.getcwd", return_value="/"):
+        try:
+            assert candidate(2, 2) == 4
+            _results.append(True)
+        except Exception:
+            _results.append(False)
+    with patch("a.b"):
+        try:
+            assert candidate(3, 3) == 6
+            _results.append(True)
+        except Exception:
+            _results.append(False)
+    try:
+        assert candidate(4, 4) == 8
+        _results.append(True)
+    except Exception:
+        _results.append(False)
+    return _results"#
+        );
+    }
+
+    #[test]
+    fn with_statements_are_recognized() {
+        assert!(is_with_statement("with patch(\"a.b\"):"));
+        assert!(is_with_statement("with(open(path)) as f:"));
+        assert!(is_with_statement("async with lock:"));
+        assert!(!is_with_statement("without_patch = 1"));
+        assert!(!is_with_statement("with_ = 1"));
+
+        assert_eq!(with_header_end("with patch('a:b'): pass"), Some(17));
+        assert_eq!(with_header_end("with patch({'a': 1}) as p:"), Some(25));
+        assert_eq!(with_header_end("with patch("), None);
+    }
+}
@@ -0,0 +1,49 @@
+//! src/type_check.rs
+//!
+//! Static type checking of extracted code with mypy.
+//!
+//! mypy is a development tool that only parses the code, so it runs as a plain subprocess
+//! (not in the sandbox). It must be installed and on `PATH`:
+//! ```bash
+//! pip install mypy
+//! ```
+
+use crate::sandbox::safe_temp_file;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+/// Run `mypy --strict` on `code`, written to a temp file in `dir`.
+///
+/// # Returns
+/// `Ok(true)` if mypy reports no errors (exit code 0), `Ok(false)` if it reports errors
+/// or exceeds `timeout`.
+///
+/// Returns `Err` if the temp file cannot be written or mypy cannot be started.
+pub fn run_mypy_strict(code: &str, timeout: Duration, dir: &Path) -> Result<bool> {
+    let temp_file = safe_temp_file(code, ".py", dir).context("Failed to create temp file")?;
+
+    let mut child = Command::new("mypy")
+        .arg("--strict")
+        .arg("--no-error-summary")
+        .arg(temp_file.path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to spawn mypy. Is mypy installed?")?;
+
+    match child
+        .wait_timeout(timeout)
+        .context("Error waiting for mypy")?
+    {
+        Some(status) => Ok(status.success()),
+        None => {
+            // Timeout exceeded - kill the process
+            let _ = child.kill();
+            let _ = child.wait();
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! tests/integration_test.rs
+//!
+//! Tests of the pure Rust API (no Python interpreter is embedded). The execution tests run
+//! `python3` as a subprocess with the bare backend, so no sandbox needs to be installed.
+//!
+//! Like the benchmarks, the tests link against libpython, so they only build with the
+//! `extension-module` feature disabled:
+//! ```bash
+//! cargo test --no-default-features --test integration_test
+//! ```
+#![cfg(not(feature = "extension-module"))]
+
+use fastrlrewards::evaluator::{EvaluatorConfig, RewardEvaluator};
+use fastrlrewards::extraction::{Language, is_stub_code};
+use fastrlrewards::metrics::pass_at_k_estimate;
+use fastrlrewards::output_parser::parse_test_output;
+use fastrlrewards::sandbox::SandboxBackend;
+use fastrlrewards::test_wrapper::{DEFAULT_CHECK_NAMES, count_assertions, is_multi_method_test};
+
+#[test]
+fn stub_code_is_detected() {
+    assert!(is_stub_code("def add(a, b):\n    pass"));
+    assert!(is_stub_code("def add(a, b):\n    ..."));
+    assert!(is_stub_code(
+        "def add(a, b):\n    \"\"\"Add two numbers.\"\"\"\n    return None"
+    ));
+    assert!(is_stub_code(
+        "def add(a, b):\n    pass\n\ndef sub(a, b):\n    return"
+    ));
+}
+
+#[test]
+fn implemented_code_is_not_a_stub() {
+    assert!(!is_stub_code("def add(a, b):\n    return a + b"));
+    assert!(!is_stub_code(
+        "def add(a, b):\n    pass\n\ndef sub(a, b):\n    return a - b"
+    ));
+    assert!(!is_stub_code("x = 1\nprint(x)")); // No function at all
+    assert!(!is_stub_code("def add(\n    a,\n    b,\n):\n    pass")); // Multi-line signature
+}
+
+#[test]
+fn multi_method_tests_need_two_methods_on_a_candidate_instance() {
+    let lru_test = "def check(candidate):
+    cache = candidate(2)
+    cache.put(1, 1)
+    assert cache.get(1) == 1";
+    assert!(is_multi_method_test(lru_test, &DEFAULT_CHECK_NAMES));
+
+    // Module functions are not methods of the solution
+    let module_calls = "import math
+def check(candidate):
+    assert math.isclose(candidate(3), 3.0)
+    assert math.floor(candidate(3)) == 3";
+    assert!(!is_multi_method_test(module_calls, &DEFAULT_CHECK_NAMES));
+
+    // One method per instance
+    let single_method = "def check(candidate):
+    first = candidate(1)
+    second = candidate(2)
+    assert first.get(1) == -1
+    assert second.put(1, 1) is None";
+    assert!(!is_multi_method_test(single_method, &DEFAULT_CHECK_NAMES));
+
+    // The instance must be created inside the check function
+    let no_check_function = "cache = LRUCache(2)\ncache.put(1, 1)\nassert cache.get(1) == 1";
+    assert!(!is_multi_method_test(
+        no_check_function,
+        &DEFAULT_CHECK_NAMES
+    ));
+}
+
+#[test]
+fn assertions_are_counted_per_statement() {
+    let test_code = "def check(candidate):
+    # assert candidate(0) == 0
+    assert candidate(1) == 1
+    assert candidate([
+        2,
+    ]) == 2, \"assert failed\"
+    for x in [3, 4]:
+        assert candidate(x) == x
+
+if __name__ == \"__main__\":
+    assert check(abs) is None";
+    assert_eq!(count_assertions(test_code), 3);
+    assert_eq!(count_assertions("def check(candidate):\n    pass"), 0);
+}
+
+#[test]
+fn pass_at_k_matches_the_closed_form() {
+    assert_eq!(pass_at_k_estimate(5, 0, 1), 0.0);
+    assert_eq!(pass_at_k_estimate(5, 5, 1), 1.0);
+    assert_eq!(pass_at_k_estimate(5, 3, 3), 1.0); // Fewer than k failures: always a success
+    assert!((pass_at_k_estimate(4, 2, 1) - 0.5).abs() < 1e-12);
+    // 1 - C(8, 2) / C(10, 2) = 1 - 28 / 45
+    assert!((pass_at_k_estimate(10, 2, 2) - 17.0 / 45.0).abs() < 1e-12);
+}
+
+#[test]
+fn pass_at_k_does_not_overflow_for_large_n() {
+    let estimate = pass_at_k_estimate(10_000, 1, 1);
+    assert!((estimate - 1e-4).abs() < 1e-12, "{}", estimate);
+    let estimate = pass_at_k_estimate(10_000, 5_000, 100);
+    assert!(estimate.is_finite() && estimate > 0.999_999, "{}", estimate);
+}
+
+#[test]
+fn test_output_summary_is_parsed() {
+    assert_eq!(parse_test_output("TESTS_PASSED:3/4"), Some((3, 4)));
+    assert_eq!(
+        parse_test_output("debug output\nTESTS_PASSED:0/2\n"),
+        Some((0, 2))
+    );
+    assert_eq!(
+        parse_test_output("Traceback (most recent call last):"),
+        None
+    );
+    assert_eq!(parse_test_output(""), None);
+}
+
+#[test]
+fn single_execution_scores_correct_and_wrong_completions() {
+    let evaluator = RewardEvaluator::new(EvaluatorConfig {
+        sandbox_backend: SandboxBackend::Bare,
+        ..EvaluatorConfig::default()
+    })
+    .expect("valid configuration");
+    let test = "def check(candidate):\n    assert candidate(2, 3) == 5";
+
+    let correct = "<answer>def add(a, b):\n    return a + b</answer>";
+    let wrong = "<answer>def add(a, b):\n    return a - b</answer>";
+    let stub = "<answer>def add(a, b):\n    pass</answer>";
+    assert_eq!(
+        evaluator.evaluate_execution_single(correct, Some(test), Some("add")),
+        1.0
+    );
+    assert_eq!(
+        evaluator.evaluate_execution_single(wrong, Some(test), Some("add")),
+        0.0
+    );
+    assert_eq!(
+        evaluator.evaluate_execution_single(stub, Some(test), Some("add")),
+        0.0
+    );
+    // A blank entry point is missing (the test calls the function itself), and a blank
+    // test scores 0.0
+    let direct_test = "def check(candidate):\n    assert add(2, 3) == 5";
+    assert_eq!(
+        evaluator.evaluate_execution_single(correct, Some(direct_test), Some("")),
+        1.0
+    );
+    assert_eq!(
+        evaluator.evaluate_execution_single(correct, Some("  "), Some("add")),
+        0.0
+    );
+    // The entry point must be defined by the completion
+    assert_eq!(
+        evaluator.evaluate_execution_single(correct, Some(test), Some("sub")),
+        0.0
+    );
+}
+
+#[test]
+fn blank_tests_are_missing_for_rust_completions() {
+    // A dry run stops before compiling, so only the checks before the sandbox decide
+    let evaluator = RewardEvaluator::new(EvaluatorConfig {
+        sandbox_backend: SandboxBackend::Bare,
+        dry_run: true,
+        ..EvaluatorConfig::default()
+    })
+    .expect("valid configuration");
+    let completion = "<answer>```rust\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```</answer>";
+    let test = "fn main() {\n    assert_eq!(add(1, 2), 3);\n}\n";
+
+    let rewards = evaluator.evaluate_execution_batch_mixed(
+        &[completion.to_string(), completion.to_string()],
+        &[Some(" \n".to_string()), Some(test.to_string())],
+        &[Some("add".to_string()), Some(String::new())],
+        &[Language::Rust, Language::Rust],
+    );
+    assert_eq!(rewards, [0.0, 1.0]);
+}
+
+#[test]
+fn regex_format_patterns_must_match_the_completions() {
+    let evaluator = RewardEvaluator::new(EvaluatorConfig::default()).expect("valid configuration");
+    let completions = ["<answer>1</answer>".to_string(), "1".to_string()];
+
+    let pattern = ["(?s)<answer>.*</answer>".to_string()];
+    assert_eq!(
+        evaluator
+            .evaluate_format_regex_batch(&completions, &pattern)
+            .unwrap(),
+        [1.0, 0.0]
+    );
+    let per_completion = ["<answer>".to_string(), r"^\d$".to_string()];
+    assert_eq!(
+        evaluator
+            .evaluate_format_regex_batch(&completions, &per_completion)
+            .unwrap(),
+        [1.0, 1.0]
+    );
+
+    // A pattern count that fits neither form, and an invalid pattern, are errors
+    let three = vec!["a".to_string(); 3];
+    assert!(
+        evaluator
+            .evaluate_format_regex_batch(&completions, &three)
+            .is_err()
+    );
+    assert!(
+        evaluator
+            .evaluate_format_regex_batch(&completions, &["(".to_string()])
+            .is_err()
+    );
+}
@@ -0,0 +1,123 @@
+#!/usr/bin/env python3
+"""
+Integration tests running the full pipeline (extract -> wrap -> sandbox -> parse) on
+HumanEval-style problems with known solutions.
+
+Uses Firejail or bubblewrap when installed, bare python3 otherwise.
+"""
+
+import fastrlrewards
+
+evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+
+def evaluate(solution, test, entry_point):
+    """Run one completion through execution_reward_detailed and return its record"""
+    completion = f"<think>Let me solve this.</think>\n<answer>\n```python\n{solution}\n```\n</answer>"
+    return evaluator.execution_reward_detailed([completion], test=[test], entry_point=[entry_point])[0]
+
+def test_fizz_buzz():
+    """Trivial problem: FizzBuzz"""
+    solution = """def fizz_buzz(n: int) -> List[str]:
+    result = []
+    for i in range(1, n + 1):
+        if i % 15 == 0:
+            result.append("FizzBuzz")
+        elif i % 3 == 0:
+            result.append("Fizz")
+        elif i % 5 == 0:
+            result.append("Buzz")
+        else:
+            result.append(str(i))
+    return result"""
+    test = """METADATA = {}
+
+
+def check(candidate):
+    assert candidate(1) == ["1"]
+    assert candidate(5) == ["1", "2", "Fizz", "4", "Buzz"]
+    assert candidate(15)[-1] == "FizzBuzz"
+    assert candidate(0) == []"""
+
+    record = evaluate(solution, test, "fizz_buzz")
+    assert record["reward"] == 1.0, record
+    assert (record["tests_passed"], record["tests_total"]) == (4, 4)
+    print("✓ test_fizz_buzz passed")
+
+def test_sort_array():
+    """Sorting with a custom key (HumanEval/116)"""
+    solution = """def sort_array(arr):
+    return sorted(sorted(arr), key=lambda x: bin(x)[2:].count("1"))"""
+    test = """def check(candidate):
+    assert candidate([1, 5, 2, 3, 4]) == [1, 2, 4, 3, 5]
+    assert candidate([-2, -3, -4, -5, -6]) == [-4, -2, -6, -5, -3]
+    assert candidate([1, 0, 2, 3, 4]) == [0, 1, 2, 4, 3]
+    assert candidate([]) == []
+    assert candidate([2, 4, 8, 16, 32]) == [2, 4, 8, 16, 32]"""
+
+    record = evaluate(solution, test, "sort_array")
+    assert record["reward"] == 1.0, record
+    assert (record["tests_passed"], record["tests_total"]) == (5, 5)
+    print("✓ test_sort_array passed")
+
+def test_recursive_fib():
+    """Recursion (HumanEval/55)"""
+    solution = """def fib(n: int):
+    if n <= 2:
+        return 1
+    return fib(n - 1) + fib(n - 2)"""
+    test = """def check(candidate):
+    assert candidate(10) == 55
+    assert candidate(1) == 1
+    assert candidate(8) == 21
+    assert candidate(11) == 89
+    assert candidate(12) == 144"""
+
+    record = evaluate(solution, test, "fib")
+    assert record["reward"] == 1.0, record
+    assert (record["tests_passed"], record["tests_total"]) == (5, 5)
+    print("✓ test_recursive_fib passed")
+
+def test_solution_class():
+    """LeetCode-style Solution class with a method entry point"""
+    solution = """class Solution:
+    def twoSum(self, nums: List[int], target: int) -> List[int]:
+        seen = {}
+        for i, num in enumerate(nums):
+            if target - num in seen:
+                return [seen[target - num], i]
+            seen[num] = i
+        return []"""
+    test = """def check(candidate):
+    assert candidate(nums=[2, 7, 11, 15], target=9) == [0, 1]
+    assert candidate(nums=[3, 2, 4], target=6) == [1, 2]
+    assert candidate(nums=[3, 3], target=6) == [0, 1]"""
+
+    record = evaluate(solution, test, "Solution().twoSum")
+    assert record["reward"] == 1.0, record
+    assert (record["tests_passed"], record["tests_total"]) == (3, 3)
+    print("✓ test_solution_class passed")
+
+def test_wrong_algorithm():
+    """A wrong solution gets 0.0, with every assertion still run (HumanEval/3)"""
+    solution = """def below_zero(operations: List[int]) -> bool:
+    return sum(operations) < 0  # Only checks the final balance"""
+    test = """def check(candidate):
+    assert candidate([]) == False
+    assert candidate([1, 2, -3, 1, 2, -3]) == False
+    assert candidate([1, 2, -4, 5, 6]) == True
+    assert candidate([1, -1, 2, -2, 5, -5, 4, -4]) == False
+    assert candidate([1, -2, 2, -2, 5, -5, 4, -4]) == True"""
+
+    record = evaluate(solution, test, "below_zero")
+    assert record["reward"] == 0.0, record
+    assert (record["tests_passed"], record["tests_total"]) == (4, 5)
+    print("✓ test_wrong_algorithm passed")
+
+if __name__ == "__main__":
+    print("\nRunning integration tests...\n")
+    test_fizz_buzz()
+    test_sort_array()
+    test_recursive_fib()
+    test_solution_class()
+    test_wrong_algorithm()
+    print("\n✅ All tests passed!\n")
@@ -112,6 +112,57 @@ test_cases = [
     # Case 21: No markdown fence but still in answer tags
     ("<think>Let me solve this</think>\n<answer>x = 42\ny = x * 2</answer>", 
      "x = 42\ny = x * 2"),
+
+    # ===== HTML-ESCAPED ANSWERS =====
+    # Case 22: Escaped comparison operators
+    ("<answer>def is_small(x): return x &lt; 10 and x &gt;= 0</answer>",
+     "def is_small(x): return x < 10 and x >= 0"),
+
+    # Case 23: Escaped ampersand and quotes
+    ("<answer>s = &quot;a&quot; if x &amp; 1 else &#39;b&#39;</answer>",
+     "s = \"a\" if x & 1 else 'b'"),
+
+    # Case 24: Double-escaped entity is only unescaped once
+    ("<answer>html = '&amp;lt;'</answer>",
+     "html = '&lt;'"),
+
+    # Case 25: Any escaped entity triggers unescaping, not only `&lt;` and `&amp;`
+    ("<answer>print('&gt; prompt')</answer>",
+     "print('> prompt')"),
+
+    # ===== ANSWER TAGS INSIDE THINK BLOCKS =====
+    # Case 26: Draft answer inside think is ignored in favour of the final answer
+    ("<think>here is my answer: <answer>def add(a, b): pass</answer></think><answer>def add(a, b): return a + b</answer>",
+     "def add(a, b): return a + b"),
+
+    # Case 27: Multiple think blocks with drafts
+    ("<think><answer>x = 1</answer></think>\n<think>Actually <answer>x = 2</answer></think>\n<answer>x = 3</answer>",
+     "x = 3"),
+
+    # Case 28: Only a draft answer falls back to markdown extraction outside the answer tags
+    ("<think>draft <answer>x = 1</answer></think>\n```python\nx = 2\n```",
+     "x = 2"),
+
+    # ===== TAG AND FENCE CASE =====
+    # Case 29: All-uppercase tags
+    ("<THINK><ANSWER>x = 1</ANSWER></THINK><ANSWER>```PYTHON\nx = 2\n```</ANSWER>",
+     "x = 2"),
+
+    # Case 30: All-lowercase tags
+    ("<think><answer>x = 1</answer></think><answer>```python\nx = 2\n```</answer>",
+     "x = 2"),
+
+    # Case 31: Mixed-case tags
+    ("<Think><Answer>x = 1</Answer></Think><aNsWeR>```Python\nx = 2\n```</AnSwEr>",
+     "x = 2"),
+
+    # Case 32: Title-case fence without answer tags
+    ("Here:\n```Python\nx = 1\n```",
+     "x = 1"),
+
+    # Case 33: Uppercase fence without answer tags
+    ("Here:\n```PYTHON\nx = 1\n```",
+     "x = 1"),
 ]
 
 for i, (input_text, expected) in enumerate(test_cases):
@@ -124,4 +175,121 @@ for i, (input_text, expected) in enumerate(test_cases):
     )
     print(f"✓ Test case {i+1} passed")
 
-print(f"\n✅ All {len(test_cases)} test cases passed!")
\ No newline at end of file
+print(f"\n✅ All {len(test_cases)} test cases passed!")
+language_cases = [
+    # Language from the fence tag inside answer tags
+    ("<answer>```javascript\nconsole.log(1)\n```</answer>", ("console.log(1)", "javascript")),
+    # Language from the lang attribute (takes precedence over the fence tag)
+    ("<answer lang=\"Rust\">```\nfn main() {}\n```</answer>", ("fn main() {}", "rust")),
+    # Python code block without answer tags
+    ("Here:\n```python\nx = 1\n```", ("x = 1", "python")),
+    # Fence tags are case-insensitive
+    ("Here:\n```Python\nx = 1\n```", ("x = 1", "python")),
+    # Other fenced languages without answer tags
+    ("Here:\n```go\nx := 1\n```", ("x := 1", "go")),
+    # No language information
+    ("<answer>x = 1</answer>", ("x = 1", "unknown")),
+]
+
+for i, (input_text, expected) in enumerate(language_cases):
+    extracted = fastrlrewards.extract_code_with_language(input_text)
+    assert extracted == expected, (
+        f"Language case {i+1} failed!\n"
+        f"Expected: {expected}\n"
+        f"Got: {extracted}"
+    )
+    print(f"✓ Language case {i+1} passed")
+
+print(f"\n✅ All {len(language_cases)} language cases passed!")
+
+import json
+
+json_cases = [
+    # Chat completion
+    (json.dumps({"choices": [{"message": {"content": "<answer>x = 1</answer>"}}]}), "x = 1"),
+    # Text completion
+    (json.dumps({"choices": [{"text": "```python\ny = 2\n```"}]}), "y = 2"),
+    # Content blocks
+    (json.dumps({"content": [{"type": "text", "text": "<answer>z = 3</answer>"}]}), "z = 3"),
+    # Not JSON: treated as a plain completion
+    ("<answer>w = 4</answer>", "w = 4"),
+]
+
+for i, (input_text, expected) in enumerate(json_cases):
+    extracted = fastrlrewards.extract_code_from_json_completion(input_text)
+    assert extracted == expected, (
+        f"JSON case {i+1} failed!\n"
+        f"Expected: {expected}\n"
+        f"Got: {extracted}"
+    )
+    print(f"✓ JSON case {i+1} passed")
+
+print(f"\n✅ All {len(json_cases)} JSON cases passed!")
+
+# Test extract_code_with_location: (completion, expected code, start line, end line)
+location_cases = [
+    ("<answer>x = 1</answer>", "x = 1", 1, 1),
+    # Markdown fences are not part of the code
+    ("<think>\nplan\n</think>\n<answer>\n```python\ndef f():\n    return 1\n```\n</answer>", "def f():\n    return 1", 6, 7),
+    # A draft answer inside <think> is skipped
+    ("<think>\n<answer>draft</answer>\n</think>\n\n<answer>\nfinal = 2\n</answer>", "final = 2", 6, 6),
+    # Markdown code block without answer tags
+    ("Here:\n```python\ny = 3\nz = 4\n```\nDone.", "y = 3\nz = 4", 3, 4),
+    # Plain text
+    ("\n\nw = 5\n", "w = 5", 3, 3),
+]
+
+for i, (input_text, expected_code, expected_start, expected_end) in enumerate(location_cases):
+    location = fastrlrewards.extract_code_with_location(input_text)
+    assert location == (expected_code, expected_start, expected_end), (
+        f"Location case {i+1} failed!\n"
+        f"Expected: {(expected_code, expected_start, expected_end)}\n"
+        f"Got: {location}"
+    )
+    print(f"✓ Location case {i+1} passed")
+
+print(f"\n✅ All {len(location_cases)} location cases passed!")
+
+# Test extract_think_block / extract_answer_block: (completion, expected think, expected answer)
+block_cases = [
+    ("<think>\nO(n) scan\n</think>\n<answer>\n```python\nx = 1\n```\n</answer>", "O(n) scan", "```python\nx = 1\n```"),
+    # The draft answer inside <think> is not the answer block
+    ("<think>try <answer>draft</answer></think><answer>final</answer>", "try <answer>draft</answer>", "final"),
+    ("<answer>a &lt; b</answer>", None, "a &lt; b"),
+    ("plain text", None, None),
+]
+
+for i, (input_text, expected_think, expected_answer) in enumerate(block_cases):
+    blocks = (fastrlrewards.extract_think_block(input_text), fastrlrewards.extract_answer_block(input_text))
+    assert blocks == (expected_think, expected_answer), (
+        f"Block case {i+1} failed!\n"
+        f"Expected: {(expected_think, expected_answer)}\n"
+        f"Got: {blocks}"
+    )
+    print(f"✓ Block case {i+1} passed")
+
+print(f"\n✅ All {len(block_cases)} block cases passed!")
+
+# Test indentation normalization: (input, expected code)
+indentation_cases = [
+    # Whole answer indented by 4 extra spaces, two top-level functions
+    ("<answer>\n    def f():\n        return 1\n\n    def g():\n        return 2\n</answer>",
+     "def f():\n    return 1\n\ndef g():\n    return 2"),
+    ("```python\n  x = 1\n  y = 2\n```", "x = 1\ny = 2"),
+    # Tabs and spaces share no common prefix: left unchanged
+    ("<answer>\tx = 1\n    y = 2</answer>", "\tx = 1\n    y = 2"),
+]
+
+for i, (input_text, expected_code) in enumerate(indentation_cases):
+    code = fastrlrewards.extract_code_from_completion(input_text)
+    assert code == expected_code, (
+        f"Indentation case {i+1} failed!\n"
+        f"Expected: {repr(expected_code)}\n"
+        f"Got: {repr(code)}"
+    )
+    print(f"✓ Indentation case {i+1} passed")
+
+assert fastrlrewards.normalize_indentation("    a\n  \n      b") == "a\n\n  b"
+assert fastrlrewards.normalize_indentation("a\n    b") == "a\n    b"
+
+print(f"\n✅ All {len(indentation_cases)} indentation cases passed!")
@@ -3,6 +3,7 @@
 Tests for the complete reward evaluator
 """
 
+import shutil
 import sys
 import fastrlrewards
 
@@ -21,6 +22,18 @@ def test_format_reward_function():
     assert rewards[2] == 1.0  # Has format
     print("✓ test_format_reward_function passed")
 
+def test_single_completion_functions():
+    """Test module-level single-completion convenience functions"""
+    assert fastrlrewards.format_reward_single("<think>Hmm.</think>\n<answer>x = 1</answer>") == 1.0
+    assert fastrlrewards.format_reward_single("x = 1") == 0.0
+
+    completion = "<answer>def add(a, b): return a - b</answer>"
+    test = "def check(candidate):\n    assert candidate(2, 3) == 5"
+    reward = fastrlrewards.execution_reward_single(completion, test, "add")
+    assert reward == fastrlrewards.execution_reward([completion], test=[test], entry_point=["add"])[0]
+    assert fastrlrewards.execution_reward_single(completion, test, "sub") == 0.0
+    print("✓ test_single_completion_functions passed")
+
 def test_execution_reward_function():
     """Test module-level execution_reward function"""
     completions = [
@@ -53,7 +66,7 @@ def test_execution_reward_function():
 
 def test_evaluator_class():
     """Test the evaluator class interface"""
-    evaluator = fastrlrewards.RewardEvaluator(timeout_seconds=10)
+    evaluator = fastrlrewards.RewardEvaluator(timeout_seconds=10, cpu_time_limit=8)
 
     completions = [
         "<think>test</think>\n<answer>def foo(): return 42</answer>"
@@ -88,7 +101,7 @@ def test_trl_dict_format():
 
 def test_multiple_evaluators():
     """Test that multiple evaluator instances work correctly"""
-    eval1 = fastrlrewards.RewardEvaluator(timeout_seconds=5)
+    eval1 = fastrlrewards.RewardEvaluator(timeout_seconds=5, cpu_time_limit=4)
     eval2 = fastrlrewards.RewardEvaluator(timeout_seconds=20)
     
     completions = ["<think>x</think>\n<answer>pass</answer>"]
@@ -99,11 +112,1243 @@ def test_multiple_evaluators():
     assert r1 == r2 == [1.0]
     print("✓ test_multiple_evaluators passed")
 
+def test_parse_test_output():
+    """Test standalone parsing of wrapped test output"""
+    stdout = "debug output\nTESTS_PASSED:1/2\n"
+
+    assert fastrlrewards.parse_test_output(stdout) == (1, 2)
+    assert fastrlrewards.parse_test_output("Traceback (most recent call last):") is None
+    print("✓ test_parse_test_output passed")
+
+def test_parse_exception_type():
+    """Test extracting the exception class name from a traceback"""
+    traceback = (
+        "Traceback (most recent call last):\n"
+        '  File "solution.py", line 1, in <module>\n'
+        "NameError: name 'x' is not defined\n"
+    )
+    assert fastrlrewards.parse_exception_type(traceback) == "NameError"
+    assert fastrlrewards.parse_exception_type("json.decoder.JSONDecodeError: bad\n\n") == "JSONDecodeError"
+    assert fastrlrewards.parse_exception_type("Traceback ...\nAssertionError") == "AssertionError"
+    assert fastrlrewards.parse_exception_type("") is None
+    assert fastrlrewards.parse_exception_type("some warning") is None
+    print("✓ test_parse_exception_type passed")
+
+def test_sandbox_fallback_policy():
+    """Test that fallback_policy runs code without Firejail when allowed"""
+    code = 'print("TESTS_PASSED:1/1")'
+
+    assert fastrlrewards.run_sandboxed_tests(code, fallback_policy="silent") == (True, 1, 1)
+
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="warn")
+    rewards = evaluator.execution_reward(
+        ["<answer>def add(a, b): return a + b</answer>"],
+        test=["def check(candidate):\n    assert candidate(2, 3) == 5"],
+        entry_point=["add"],
+    )
+    assert rewards == [1.0]
+
+    try:
+        fastrlrewards.RewardEvaluator(fallback_policy="sometimes")
+        assert False, "invalid policy should raise"
+    except ValueError:
+        pass
+    print("✓ test_sandbox_fallback_policy passed")
+
+def test_sandbox_backend():
+    """Test sandbox detection and explicitly choosing a backend"""
+    import pickle
+
+    code = 'print("TESTS_PASSED:1/1")'
+    available = fastrlrewards.is_sandbox_available()
+    assert isinstance(available, bool)
+
+    # The bare backend is an explicit opt-out, so the fallback policy is not consulted
+    assert fastrlrewards.run_sandboxed_tests(code, sandbox_backend="bare") == (True, 1, 1)
+
+    evaluator = fastrlrewards.RewardEvaluator()
+    assert evaluator.config.sandbox_backend in ("firejail", "bubblewrap")  # "auto" is resolved
+    assert evaluator.to_dict()["sandbox_backend"] == "auto"  # but saved as requested
+    assert pickle.loads(pickle.dumps(evaluator)).to_dict()["sandbox_backend"] == "auto"
+    assert fastrlrewards.RewardEvaluator(sandbox_backend="bare").config.sandbox_backend == "bare"
+
+    # The Python subprocess manager enforces the limits without a sandbox binary
+    assert fastrlrewards.run_sandboxed_tests(code, sandbox_backend="python_subprocess") == (True, 1, 1)
+    echo = 'assert input() == "hello"\nprint("TESTS_PASSED:1/1")'
+    assert fastrlrewards.run_sandboxed_tests(echo, sandbox_backend="python_subprocess", stdin_input="hello\n") == (True, 1, 1)
+    sleep = 'import time\ntime.sleep(10)\nprint("TESTS_PASSED:1/1")'
+    assert fastrlrewards.run_sandboxed_tests(sleep, timeout=1, sandbox_backend="python_subprocess") == (False, 0, 0)
+    hog = 'data = bytearray(400 * 1024 * 1024)\nprint("TESTS_PASSED:1/1")'
+    assert fastrlrewards.run_sandboxed_tests(hog, memory_limit_mb=100, sandbox_backend="python_subprocess") == (False, 0, 0)
+    managed = fastrlrewards.RewardEvaluator(sandbox_backend="python_subprocess")
+    assert managed.config.sandbox_backend == "python_subprocess"
+    assert managed.execution_reward(
+        ["<answer>def add(a, b):\n    return a + b</answer>"],
+        test=["def check(candidate):\n    assert candidate(1, 2) == 3"],
+        entry_point=["add"],
+    ) == [1.0]
+
+    try:
+        fastrlrewards.RewardEvaluator(sandbox_backend="docker")
+        assert False, "invalid backend should raise"
+    except ValueError:
+        pass
+    print("✓ test_sandbox_backend passed")
+
+def test_pass_at_k():
+    """Test Pass@K over grouped samples"""
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    correct = "<answer>def add(a, b): return a + b</answer>"
+    wrong = "<answer>def add(a, b): return a - b</answer>"
+
+    scores = evaluator.pass_at_k(
+        [[correct, wrong, wrong, wrong], [wrong, wrong]],
+        test=["def check(candidate):\n    assert candidate(2, 3) == 5"] * 2,
+        entry_point=["add", "add"],
+        k=1,
+    )
+    assert abs(scores[0] - 0.25) < 1e-9
+    assert scores[1] == 0.0
+    print("✓ test_pass_at_k passed")
+
+def test_pass_at_k_from_rewards():
+    """Test Pass@K computed from already evaluated rewards"""
+    grouped = fastrlrewards.compute_pass_at_k([[1.0, 0.0, 1.0, 0.0], [0.0, 0.0], [1.0, 1.0]], k=1)
+    assert [round(score, 9) for score in grouped] == [0.5, 0.0, 1.0]
+    # 1 - C(2, 2) / C(4, 2); partial rewards do not count as correct
+    assert abs(fastrlrewards.compute_pass_at_k([[1.0, 0.5, 1.0, 0.0]], k=2)[0] - 5 / 6) < 1e-9
+
+    flat = fastrlrewards.compute_pass_at_k_from_flat([1.0, 0.0, 1.0, 0.0, 0.0, 0.0], n_samples_per_problem=3, k=1)
+    assert [round(score, 9) for score in flat] == [round(2 / 3, 9), 0.0]
+    # Large n stays finite and accurate
+    large = fastrlrewards.compute_pass_at_k_from_flat([1.0] + [0.0] * 9999, n_samples_per_problem=10000, k=100)
+    assert abs(large[0] - 0.01) < 1e-9
+
+    for call in (
+        lambda: fastrlrewards.compute_pass_at_k([[1.0]], k=0),
+        lambda: fastrlrewards.compute_pass_at_k([[1.0]], k=2),
+        lambda: fastrlrewards.compute_pass_at_k_from_flat([1.0, 0.0, 1.0], n_samples_per_problem=2, k=1),
+        lambda: fastrlrewards.compute_pass_at_k_from_flat([1.0, 0.0], n_samples_per_problem=1, k=2),
+    ):
+        try:
+            call()
+            assert False, "invalid arguments should raise"
+        except ValueError:
+            pass
+    print("✓ test_pass_at_k_from_rewards passed")
+
+def test_length_mismatch_message():
+    """Test that length mismatches name the kwarg, both lengths and the likely cause"""
+    tests = ["def check(candidate):\n    assert candidate(1) == 1"] * 2
+    try:
+        fastrlrewards.execution_reward(["code"], test=tests, entry_point=["f"])
+        assert False, "Expected ValueError for mismatched lengths"
+    except ValueError as e:
+        message = str(e)
+        assert "'test' has 2 items but there are 1 completions" in message, message
+        assert "entire test dataset" in message, message
+    print("✓ test_length_mismatch_message passed")
+
+def test_grouped_aggregation_rewards():
+    """Test best-of-N, mean and majority aggregation over grouped rollouts"""
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    correct = "<answer>def add(a, b): return a + b</answer>"
+    wrong = "<answer>def add(a, b): return a - b</answer>"
+    rollouts = [[correct, wrong, wrong, correct], [wrong, wrong], []]
+    tests = ["def check(candidate):\n    assert candidate(2, 3) == 5"] * 3
+    entry_points = ["add"] * 3
+
+    assert evaluator.best_of_n_reward(rollouts, test=tests, entry_point=entry_points) == [1.0, 0.0, 0.0]
+    assert evaluator.mean_reward(rollouts, test=tests, entry_point=entry_points) == [0.5, 0.0, 0.0]
+    assert evaluator.majority_reward(rollouts, test=tests, entry_point=entry_points) == [0.5, 0.0, 0.0]
+    assert evaluator.execution_reward_batch_of_lists(rollouts, test=tests, entry_point=entry_points) == [
+        [1.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0],
+        [],
+    ]
+    records = evaluator.execution_reward_batch_of_lists(
+        rollouts, test=tests, entry_point=entry_points, detailed=True
+    )
+    assert [[r["flat_index"] for r in group] for group in records] == [[0, 1, 2, 3], [4, 5], []]
+    assert [r["reward"] for r in records[0]] == [1.0, 0.0, 0.0, 1.0]
+    try:
+        evaluator.execution_reward_batch_of_lists(rollouts, test=tests[:2], entry_point=entry_points[:2])
+        assert False, "Expected ValueError for mismatched lengths"
+    except ValueError:
+        pass
+    print("✓ test_grouped_aggregation_rewards passed")
+
+def test_combined_reward():
+    """Test single-pass format + execution reward with format gating"""
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    completions = [
+        "<think>easy</think>\n<answer>def add(a, b): return a + b</answer>",
+        "<think>hmm</think>\n<answer>def add(a, b): return a - b</answer>",
+        "<answer>def add(a, b): return a + b</answer>",  # No <think>, not executed
+    ]
+    rewards = evaluator.combined_reward(
+        completions,
+        test=["def check(candidate):\n    assert candidate(2, 3) == 5"] * 3,
+        entry_point=["add"] * 3,
+        format_weight=0.2,
+        exec_weight=0.8,
+    )
+    assert [round(r, 6) for r in rewards] == [1.0, 0.2, 0.0]
+
+    # The same gate for plain execution rewards is opt-in
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(2, 3) == 5"] * 3, "entry_point": ["add"] * 3}
+    assert evaluator.execution_reward(completions, **kwargs) == [1.0, 0.0, 1.0]
+    gated = fastrlrewards.RewardEvaluator(fallback_policy="silent", gate_execution_on_format=True)
+    assert gated.execution_reward(completions, **kwargs) == [1.0, 0.0, 0.0]
+    print("✓ test_combined_reward passed")
+
+def test_numpy_kwargs():
+    """Test numpy object arrays and pandas Series as test/entry_point kwargs"""
+    try:
+        import numpy as np
+    except ImportError:
+        print("⚠ test_numpy_kwargs skipped (numpy not installed)")
+        return
+
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    completions = ["<answer>def square(x): return x * x</answer>"] * 2
+    tests = np.array(["def check(candidate):\n    assert candidate(4) == 16"] * 2, dtype=object)
+    entry_points = np.array(["square", "square"], dtype=object)
+
+    rewards = evaluator.execution_reward(completions, test=tests, entry_point=entry_points)
+    assert rewards == [1.0, 1.0]
+
+    try:
+        import pandas as pd
+    except ImportError:
+        pd = None
+    if pd is not None:
+        rewards = evaluator.execution_reward(
+            completions, test=pd.Series(tests), entry_point=pd.Series(entry_points)
+        )
+        assert rewards == [1.0, 1.0]
+    print("✓ test_numpy_kwargs passed")
+
+def test_output_reward():
+    """Test stdout-matching reward for print-style problems"""
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    completions = [
+        "<answer>print(1 + 2)</answer>",
+        "<answer>print(1 + 2, end='   \\n\\n')</answer>",  # Trailing whitespace
+        "<answer>print(4)</answer>",
+    ]
+
+    rewards = evaluator.output_reward(completions, expected_output=["3\n"] * 3)
+    assert rewards == [1.0, 1.0, 0.0]
+
+    rewards = evaluator.output_reward(
+        completions, expected_output=["3\n"] * 3, normalize_whitespace=False
+    )
+    assert rewards == [1.0, 0.0, 0.0]
+
+    # Repeated blank lines are collapsed when normalizing
+    spaced = ["<answer>print('YES\\n\\n\\nNO')</answer>"]
+    assert evaluator.output_match_reward(spaced, expected_output=["YES\n\nNO\n"]) == [1.0]
+    assert evaluator.output_match_reward(spaced, normalize=False, expected_output=["YES\n\nNO\n"]) == [0.0]
+    print("✓ test_output_reward passed")
+
+def test_multi_file_execution():
+    """Test multi-file solutions where the entry file imports a sibling module"""
+    files = {
+        "utils.py": "def add(a, b):\n    return a + b\n",
+        "main.py": "from utils import add\nprint(f'TESTS_PASSED:{int(add(2, 3) == 5)}/1')\n",
+    }
+    result = fastrlrewards.execute_multi_file_with_tests_firejail(
+        files, "main.py", fallback_policy="silent"
+    )
+    assert result == (True, 1, 1)
+
+    for bad_files, entry in [(files, "missing.py"), ({"../evil.py": "pass"}, "../evil.py")]:
+        try:
+            fastrlrewards.execute_multi_file_with_tests_firejail(bad_files, entry)
+            assert False, "should raise ValueError"
+        except ValueError:
+            pass
+
+    # Shell metacharacters never reach the Firejail command line
+    try:
+        fastrlrewards.execute_multi_file_with_tests_firejail(
+            {"main;id.py": "pass"}, "main;id.py", sandbox_backend="firejail"
+        )
+        assert False, "unsafe script path should raise"
+    except ValueError:
+        pass
+    print("✓ test_multi_file_execution passed")
+
+def test_reward_registry():
+    """Test looking up built-in reward functions by name"""
+    evaluator = fastrlrewards.RewardEvaluator(fallback_policy="silent")
+    registry = fastrlrewards.RewardRegistry(evaluator=evaluator)
+    completions = ["<think>ok</think>\n<answer>def add(a, b): return a + b</answer>"]
+
+    assert registry.names() == ["execution", "format", "output"]
+    assert "format" in registry
+    assert registry.evaluate("format", completions) == [1.0]
+    assert registry.evaluate(
+        "execution",
+        completions,
+        test=["def check(candidate):\n    assert candidate(2, 3) == 5"],
+        entry_point=["add"],
+    ) == [1.0]
+
+    try:
+        registry.evaluate("missing", completions)
+        assert False, "unknown reward should raise"
+    except KeyError:
+        pass
+    print("✓ test_reward_registry passed")
+
+def test_python_udf_reward():
+    """Test registering Python callables as reward functions"""
+    registry = fastrlrewards.RewardRegistry()
+    registry.register(
+        "mentions_entry_point",
+        lambda completion, context: float(context["entry_point"] in completion),
+    )
+    registry.register("broken", lambda completion, context: 1 / 0)
+
+    completions = ["def add(a, b): ...", "def sub(a, b): ..."]
+    assert registry.evaluate(
+        "mentions_entry_point", completions, entry_point=["add", "add"]
+    ) == [1.0, 0.0]
+    assert registry.evaluate("broken", completions) == [0.0, 0.0]  # Exceptions score 0.0
+    print("✓ test_python_udf_reward passed")
+
+def test_evaluator_config():
+    """Test inspecting the evaluator configuration from Python"""
+    evaluator = fastrlrewards.RewardEvaluator(timeout_seconds=20, max_completion_chars=500)
+    config = evaluator.config
+    assert config.timeout_seconds == 20
+    assert config.fallback_policy == "error"
+    assert vars(config)["max_completion_chars"] == 500
+    assert config.clamp_rewards
+    assert not fastrlrewards.RewardEvaluator(clamp_rewards=False).config.clamp_rewards
+    assert config.extra_firejail_args == []
+    custom = fastrlrewards.RewardEvaluator(extra_firejail_args=["--nosound"])
+    assert custom.config.extra_firejail_args == ["--nosound"]
+    assert "extra_firejail_args=['--nosound']" in repr(custom)
+    assert config.max_open_files == 50
+    assert fastrlrewards.RewardEvaluator(max_open_files=200).config.max_open_files == 200
+    assert config.seccomp_profile == "strict"
+    assert fastrlrewards.RewardEvaluator(seccomp_profile="Permissive").config.seccomp_profile == "permissive"
+    custom = fastrlrewards.RewardEvaluator(seccomp_profile="ptrace, mount")
+    assert "seccomp_profile='ptrace,mount'" in repr(custom)
+    try:
+        fastrlrewards.RewardEvaluator(seccomp_profile="ptrace;id")
+        assert False, "invalid seccomp_profile should raise"
+    except ValueError:
+        pass
+
+    config.timeout_seconds = 3  # Modifying the copy leaves the evaluator untouched
+    assert evaluator.config.timeout_seconds == 20
+
+    # Common limits are also readable directly on the evaluator, but not writable
+    assert evaluator.timeout_seconds == 20
+    assert (evaluator.memory_limit_mb, evaluator.cpu_time_limit, evaluator.num_threads) == (512, 12, 32)
+    assert fastrlrewards.RewardEvaluator(num_threads=None).num_threads is None  # One thread per core
+    try:
+        evaluator.timeout_seconds = 3
+        assert False, "evaluator attributes should be read-only"
+    except AttributeError:
+        pass
+    print("✓ test_evaluator_config passed")
+
+def test_timeout_below_cpu_limit_rejected():
+    """Test that a wall-clock timeout below the CPU limit is rejected unless allowed"""
+    try:
+        fastrlrewards.RewardEvaluator(timeout_seconds=5, cpu_time_limit=10)
+        assert False, "timeout_seconds < cpu_time_limit should raise"
+    except ValueError as e:
+        assert "cpu_time_limit" in str(e)
+
+    evaluator = fastrlrewards.RewardEvaluator(
+        timeout_seconds=5, cpu_time_limit=10, allow_lower_wall_timeout=True
+    )
+    assert evaluator.config.allow_lower_wall_timeout
+    print("✓ test_timeout_below_cpu_limit_rejected passed")
+
+def test_memory_limit_floor():
+    """Test that memory limits down to 32MB are accepted"""
+    assert fastrlrewards.RewardEvaluator(memory_limit_mb=32).config.memory_limit_mb == 32
+    try:
+        fastrlrewards.RewardEvaluator(memory_limit_mb=31)
+        assert False, "memory_limit_mb below 32 should raise"
+    except ValueError as e:
+        assert "32MB" in str(e) and "interpreter" in str(e)
+    print("✓ test_memory_limit_floor passed")
+
+def test_config_dict_round_trip():
+    """Test to_dict/from_dict (through JSON) for checkpointing the configuration"""
+    import json
+
+    evaluator = fastrlrewards.RewardEvaluator(
+        timeout_seconds=20, sandbox_backend="bare", stdin_input="1 2", check_function_names=["verify"],
+        quality_weights={"comments": 0.5},
+    )
+    saved = json.dumps(evaluator.to_dict())
+    restored = fastrlrewards.RewardEvaluator.from_dict(json.loads(saved))
+    assert restored.to_dict() == evaluator.to_dict()
+    assert restored.config.timeout_seconds == 20
+    assert restored.config.check_function_names == ["verify"]
+    assert restored.config.quality_weights["comments"] == 0.5
+
+    # repr and str list every configuration field
+    for key, value in evaluator.to_dict().items():
+        assert f"{key}={value!r}" in repr(evaluator), key
+        assert f"\n  {key}:" in str(evaluator), key
+
+    # Missing keys take their defaults
+    assert fastrlrewards.RewardEvaluator.from_dict({}).to_dict() == fastrlrewards.RewardEvaluator().to_dict()
+
+    for bad_config, error in [
+        ({"timeout": 5}, ValueError),
+        ({"linting_tool": "pylint"}, ValueError),
+        ({"memory_limit_mb": 1}, ValueError),
+        ({"timeout_seconds": "fast"}, TypeError),
+    ]:
+        try:
+            fastrlrewards.RewardEvaluator.from_dict(bad_config)
+            assert False, f"Expected {error.__name__} for {bad_config}"
+        except error:
+            pass
+    print("✓ test_config_dict_round_trip passed")
+
+def test_pickle_and_copy():
+    """Test that evaluators survive copy, deepcopy, pickle and multiprocessing"""
+    import copy
+    import multiprocessing
+    import pickle
+
+    evaluator = fastrlrewards.RewardEvaluator(timeout_seconds=20, sandbox_backend="bare", num_threads=2)
+    for clone in [copy.copy(evaluator), copy.deepcopy(evaluator), pickle.loads(pickle.dumps(evaluator))]:
+        assert clone is not evaluator
+        assert clone.to_dict() == evaluator.to_dict()
+
+    batches = [["<think>a</think>\n<answer>x = 1</answer>"], ["x = 1"]]
+    with multiprocessing.Pool(2) as pool:
+        assert pool.map(evaluator.format_reward, batches) == [[1.0], [0.0]]
+    print("✓ test_pickle_and_copy passed")
+
+def test_min_code_lines():
+    """Test that suspiciously short solutions are skipped before execution"""
+    completions = [
+        "<answer>def add(a, b): return a + b</answer>",
+        "<answer>def add(a, b):\n    # add them\n\n    return a + b</answer>",
+    ]
+    tests = ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 2
+    evaluator = fastrlrewards.RewardEvaluator(min_code_lines=2, fallback_policy="silent")
+    scores = evaluator.execution_reward(completions, test=tests, entry_point=["add", "add"])
+    assert scores == [0.0, 1.0]  # Comments and blank lines do not count
+    print("✓ test_min_code_lines passed")
+
+def test_tuple_completions():
+    """Test that completions may be passed as tuples"""
+    completions = tuple(["<think>ok</think><answer>x = 1</answer>", "no tags"])
+    assert fastrlrewards.format_reward(completions) == [1.0, 0.0]
+
+    evaluator = fastrlrewards.RewardEvaluator()
+    assert evaluator.format_reward(completions) == [1.0, 0.0]
+
+    # Dicts keyed by "message", "text" or "response" instead of "content"
+    formatted = "<think>ok</think><answer>x = 1</answer>"
+    dicts = [{"message": formatted}, {"text": formatted}, [{"response": formatted}], {"other": formatted}]
+    assert evaluator.format_reward(dicts) == [1.0, 1.0, 1.0, 0.0]
+
+    try:
+        fastrlrewards.format_reward("not a sequence")
+        assert False, "a bare string should raise"
+    except TypeError:
+        pass
+    print("✓ test_tuple_completions passed")
+
+def test_execution_reward_callable():
+    """Test execution rewards computed by a Python test function"""
+    def test_fn(code):
+        namespace = {}
+        exec(code, namespace)
+        return namespace["add"](1, 2) == 3
+
+    completions = (
+        "<answer>def add(a, b): return a + b</answer>",
+        "<answer>def add(a, b): return a - b</answer>",
+        "<answer>syntax error(</answer>",
+    )
+    assert fastrlrewards.execution_reward_callable(completions, test_fn) == [1.0, 0.0, 0.0]
+    print("✓ test_execution_reward_callable passed")
+
+def test_batch_order_is_deterministic():
+    """Test that parallel evaluation returns rewards in input order"""
+    test = "def check(candidate):\n    assert candidate() == 0"
+    completions = [f"<answer>def f(): return {i % 3}</answer>" for i in range(100)]
+    expected = [1.0 if i % 3 == 0 else 0.0 for i in range(100)]
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    for _ in range(3):
+        rewards = evaluator.execution_reward(completions, test=[test] * 100, entry_point=["f"] * 100)
+        assert rewards == expected
+    print("✓ test_batch_order_is_deterministic passed")
+
+def test_complexity_reward():
+    """Test that simpler solutions get higher complexity rewards"""
+    simple = "<answer>def add(a, b):\n    return a + b</answer>"
+    branchy = (
+        "<answer>def sign(x):\n"
+        "    if x > 0 and x != 0:  # or else\n"
+        "        return 1\n"
+        "    elif x < 0:\n"
+        "        return -1\n"
+        "    return 0</answer>"
+    )
+    scores = fastrlrewards.complexity_reward([simple, branchy, ""], max_complexity=10)
+    assert scores == [0.9, 0.6, 0.0]  # Complexity 1 and 4 (comments are ignored)
+
+    try:
+        fastrlrewards.complexity_reward([simple], max_complexity=0)
+        assert False, "max_complexity=0 should raise"
+    except ValueError:
+        pass
+    print("✓ test_complexity_reward passed")
+
+def test_regex_format_reward():
+    """Test format rewards with custom regex patterns"""
+    completions = ["<reasoning>x</reasoning>\n<code>y</code>", "<code>y</code>", "answer: 42"]
+    pattern = r"(?s)<reasoning>.*</reasoning>\s*<code>.*</code>"
+    assert fastrlrewards.regex_format_reward(completions, pattern) == [1.0, 0.0, 0.0]
+    assert fastrlrewards.regex_format_reward(completions, [pattern, "<code>", r"answer: \d+"]) == [1.0, 1.0, 1.0]
+
+    evaluator = fastrlrewards.RewardEvaluator()
+    assert evaluator.regex_format_reward(completions, [r"\d"]) == [0.0, 0.0, 1.0]
+    for patterns in [["(unclosed"], [pattern, pattern]]:
+        try:
+            evaluator.regex_format_reward(completions, patterns)
+            assert False, "invalid patterns should raise"
+        except ValueError:
+            pass
+    print("✓ test_regex_format_reward passed")
+
+def test_stdin_execution():
+    """Test stdin/stdout problems evaluated through execution_reward"""
+    completions = [
+        "<answer>a, b = map(int, input().split())\nprint(a + b)</answer>",
+        "<answer>a, b = map(int, input().split())\nprint(a * b)</answer>",
+    ]
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    rewards = evaluator.execution_reward(
+        completions, stdin=["2 3\n", "2 3\n"], expected_output=["5\n", "5\n"]
+    )
+    assert rewards == [1.0, 0.0]
+
+    code = "import sys\nn = int(sys.stdin.read())\nprint(f'TESTS_PASSED:{n}/{n}')"
+    assert fastrlrewards.run_sandboxed_tests(code, sandbox_backend="bare", stdin_input="4") == (True, 4, 4)
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", stdin_input="7 8")
+    assert evaluator.output_reward(completions[:1], expected_output=["15"]) == [1.0]
+    print("✓ test_stdin_execution passed")
+
+def test_reject_stubs():
+    """Test that placeholder implementations score 0.0 unless reject_stubs is off"""
+    stubs = [
+        "<answer>def noop(x): pass</answer>",
+        "<answer>def noop(x):\n    \"\"\"Does nothing.\"\"\"\n    return None</answer>",
+        "<answer>class Solution:\n    def noop(self, x):\n        ...</answer>",
+    ]
+    implemented = "<answer>def noop(x):\n    print(x, end='')\n    return None</answer>"
+    tests = ["def check(candidate):\n    assert candidate(1) is None"] * 4
+    entry_points = ["noop", "noop", "Solution().noop", "noop"]
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    rewards = evaluator.execution_reward(stubs + [implemented], test=tests, entry_point=entry_points)
+    assert rewards == [0.0, 0.0, 0.0, 1.0]
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", reject_stubs=False)
+    rewards = evaluator.execution_reward(stubs + [implemented], test=tests, entry_point=entry_points)
+    assert rewards == [1.0, 1.0, 1.0, 1.0]
+    print("✓ test_reject_stubs passed")
+
+def test_class_entry_points():
+    """Test entry point validation for class instances and methods"""
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    solution = "<answer>class Solution:\n    def add(self, a, b):\n        return a + b</answer>"
+    wrong_class = "<answer>class Helper:\n    def add(self, a, b):\n        return a + b</answer>"
+    rewards = evaluator.execution_reward(
+        [solution, wrong_class, solution, wrong_class],
+        test=["def check(candidate):\n    assert candidate.add(a=1, b=2) == 3"] * 2
+        + ["def check(candidate):\n    assert candidate(1, b=2) == 3"] * 2,
+        entry_point=["Solution()", "Solution()", "Solution().add", "Solution().add"],
+    )
+    assert rewards == [1.0, 0.0, 1.0, 0.0]
+    print("✓ test_class_entry_points passed")
+
+def test_solution_class_names():
+    """Test entry points resolved against the configured solution classes"""
+    solver = "<answer>class Solver:\n    def add(self, a, b):\n        return a + b</answer>"
+    function = "<answer>def add(a, b):\n    return a + b</answer>"
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 2, "entry_point": ["add"] * 2}
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", solution_class_names=["Solver"])
+    assert evaluator.config.solution_class_names == ["Solver"]
+    assert "solution_class_names=['Solver']" in repr(evaluator)
+    assert evaluator.execution_reward([solver, function], **kwargs) == [1.0, 1.0]
+
+    # The method is not callable as a function without the matching class name
+    default = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    assert default.config.solution_class_names == ["Solution"]
+    assert default.execution_reward([solver, function], **kwargs) == [0.0, 1.0]
+
+    # A `Solution()` entry point uses whichever listed class the completion defines
+    main = "<answer>class Main:\n    def add(self, a, b):\n        return a + b</answer>"
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 2, "entry_point": ["Solution().add", "add"]}
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", solution_class_names=["Solution", "Main"])
+    assert evaluator.execution_reward([main, main], **kwargs) == [1.0, 1.0]
+    assert default.execution_reward([main, main], **kwargs) == [0.0, 0.0]
+
+    for names in ([], [""]):
+        try:
+            fastrlrewards.RewardEvaluator(solution_class_names=names)
+            assert False, f"solution_class_names={names} should raise"
+        except ValueError:
+            pass
+    print("✓ test_solution_class_names passed")
+
+def test_dry_run():
+    """Test that dry_run checks extraction and entry points without executing"""
+    completions = [
+        "<answer>def add(a, b):\n    return a - b</answer>",  # Wrong, but never executed
+        "<answer>def sub(a, b):\n    return a - b</answer>",
+        "no code here",
+    ]
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 3, "entry_point": ["add"] * 3}
+
+    # No sandbox is spawned, so even a missing Firejail does not matter
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="firejail", dry_run=True)
+    assert evaluator.config.dry_run
+    assert "dry_run=True" in repr(evaluator)
+    assert evaluator.execution_reward(completions, **kwargs) == [1.0, 0.0, 0.0]
+
+    bare = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    assert bare.execution_reward(completions, dry_run=True, **kwargs) == [1.0, 0.0, 0.0]
+    assert bare.execution_reward(completions, **kwargs) == [0.0, 0.0, 0.0]
+    print("✓ test_dry_run passed")
+
+def test_pre_wrapped_tests():
+    """Test that already instrumented tests run without being wrapped again"""
+    completion = "<answer>def add(a, b):\n    return a + b</answer>"
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(
+        "def check(candidate):\n    assert candidate(1, 2) == 3\n    assert candidate(2, 2) == 5", "add"
+    )
+    custom = "print('TESTS_PASSED:1/1' if add(1, 1) == 2 else 'TESTS_PASSED:0/1')"
+    kwargs = {"test": [wrapped, custom], "entry_point": ["add"] * 2}
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", pre_wrapped_tests=True)
+    assert "pre_wrapped_tests=True" in repr(evaluator)
+    # The wrapped tests report 1/2 instead of being counted as a single assertion
+    assert evaluator.execution_reward([completion] * 2, **kwargs) == [0.0, 1.0]
+    detailed = evaluator.execution_reward_detailed([completion], test=[wrapped], entry_point=["add"])
+    assert (detailed[0]["tests_passed"], detailed[0]["tests_total"]) == (1, 2), detailed
+
+    bare = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    assert bare.execution_reward([completion] * 2, pre_wrapped=True, **kwargs) == [0.0, 1.0]
+    assert not bare.config.pre_wrapped_tests
+    print("✓ test_pre_wrapped_tests passed")
+
+def test_null_fields():
+    """Test that None (or a blank string) marks a missing test or entry point"""
+    completion = "<answer>def add(a, b):\n    return a + b</answer>"
+    test = "def check(candidate):\n    assert add(1, 2) == 3"
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+
+    # A missing test scores 0.0 without running anything
+    assert evaluator.execution_reward([completion] * 2, test=[None, test], entry_point=["add"] * 2) == [0.0, 1.0]
+    # A missing entry point only skips the entry point check
+    assert evaluator.execution_reward([completion], test=[test], entry_point=[None]) == [1.0]
+    assert evaluator.execution_reward([completion], test=[test]) == [1.0]
+    # Empty or whitespace-only strings are missing values too
+    assert evaluator.execution_reward([completion] * 2, test=["", "  \n"], entry_point=["add"] * 2) == [0.0, 0.0]
+    assert evaluator.execution_reward([completion] * 2, test=[test] * 2, entry_point=["", " "]) == [1.0, 1.0]
+    # The string "null" is an entry point like any other, not a missing value
+    assert evaluator.execution_reward([completion], test=[test], entry_point=["null"]) == [0.0]
+    print("✓ test_null_fields passed")
+
+def test_chunked_execution():
+    """Test that chunked evaluation returns the same rewards in the same order"""
+    correct = "<answer>def add(a, b):\n    return a + b</answer>"
+    wrong = "<answer>def add(a, b):\n    return a - b</answer>"
+    completions = [correct, wrong, wrong, correct, correct]
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 5, "entry_point": ["add"] * 5}
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    expected = [1.0, 0.0, 0.0, 1.0, 1.0]
+    assert evaluator.execution_reward(completions, **kwargs) == expected
+    assert evaluator.execution_reward(completions, chunk_size=2, **kwargs) == expected
+    assert evaluator.execution_reward(completions, chunk_size=100, **kwargs) == expected
+    try:
+        evaluator.execution_reward(completions, chunk_size=0, **kwargs)
+        assert False, "chunk_size=0 should raise"
+    except ValueError:
+        pass
+    print("✓ test_chunked_execution passed")
+
+def test_execution_reward_stream():
+    """Test that streamed rewards are yielded lazily in input order"""
+    correct = "<answer>def add(a, b):\n    return a + b</answer>"
+    wrong = "<answer>def add(a, b):\n    return a - b</answer>"
+    completions = [correct, wrong, wrong, correct, correct]
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 5, "entry_point": ["add"] * 5}
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    stream = evaluator.execution_reward_stream(completions, chunk_size=2, **kwargs)
+    assert len(stream) == 5
+    assert next(stream) == 1.0
+    assert len(stream) == 4
+    assert list(stream) == [0.0, 0.0, 1.0, 1.0]
+    assert list(evaluator.execution_reward_stream(completions, **kwargs)) == [1.0, 0.0, 0.0, 1.0, 1.0]
+    assert list(evaluator.execution_reward_stream([], test=[], entry_point=[])) == []
+    try:
+        evaluator.execution_reward_stream(completions, chunk_size=0, **kwargs)
+        assert False, "chunk_size=0 should raise"
+    except ValueError:
+        pass
+    print("✓ test_execution_reward_stream passed")
+
+def test_filter_valid_completions():
+    """Test that completions failing the pre-execution checks are filtered out"""
+    correct = "<answer>def add(a, b):\n    return a + b</answer>"
+    wrong = "<answer>def add(a, b):\n    return a - b</answer>"
+    renamed = "<answer>def plus(a, b):\n    return a + b</answer>"
+    test = "def check(candidate):\n    assert candidate(1, 2) == 3"
+    completions = [correct, "no code here", wrong, renamed, correct, correct]
+    tests = [test, test, test, test, None, "  "]
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    indices, filtered, filtered_tests, entry_points = evaluator.filter_valid_completions(
+        completions, test=tests, entry_point=["add"] * 6
+    )
+    # Wrong answers are kept: only execution can reject them
+    assert indices == [0, 2]
+    assert filtered == [correct, wrong]
+    assert filtered_tests == [test, test]
+    assert entry_points == ["add", "add"]
+
+    rewards = [0.0] * len(completions)
+    for idx, reward in zip(indices, evaluator.execution_reward(filtered, test=filtered_tests, entry_point=entry_points)):
+        rewards[idx] = reward
+    assert rewards == evaluator.execution_reward(completions, test=tests, entry_point=["add"] * 6)
+
+    assert fastrlrewards.filter_valid_completions([correct], test=[test], entry_point=[None]) == ([0], [correct], [test], [None])
+    assert fastrlrewards.filter_valid_completions([], test=[], entry_point=[]) == ([], [], [], [])
+    print("✓ test_filter_valid_completions passed")
+
+def test_mixed_language_batch():
+    """Test per-item language dispatch in execution_reward"""
+    python = "<answer>```python\ndef add(a, b):\n    return a + b\n```</answer>"
+    javascript = "<answer>```javascript\nfunction add(a, b) { return a + b; }\n```</answer>"
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1, 2) == 3"] * 3, "entry_point": ["add"] * 3}
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    rewards = evaluator.execution_reward(
+        [python, javascript, python], language=["python", "javascript", ""], **kwargs
+    )
+    # Untagged items run as Python; there is no JavaScript runtime yet
+    assert rewards == [1.0, 0.0, 1.0], rewards
+    assert evaluator.execution_reward([python, javascript, python], language=None, **kwargs)[0] == 1.0
+    try:
+        evaluator.execution_reward([python], language=["python", "python"], test=kwargs["test"][:1], entry_point=["add"])
+        assert False, "mismatched language length should raise"
+    except ValueError:
+        pass
+    print("✓ test_mixed_language_batch passed")
+
+def test_rust_execution():
+    """Test compiling and running Rust completions against main-based and #[test] suites"""
+    if shutil.which("rustc") is None:
+        print("⚠ test_rust_execution skipped (rustc not installed)")
+        return
+    solution = "<answer>```rust\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```</answer>"
+    wrong = "<answer>```rust\nfn add(a: i32, b: i32) -> i32 {\n    a - b\n}\n```</answer>"
+    broken = "<answer>```rust\nfn add(a: i32, b: i32) -> i32 {\n    a +\n}\n```</answer>"
+    main_test = "fn main() {\n    let candidate = add;\n    assert_eq!(candidate(1, 2), 3);\n    assert_eq!(candidate(0, 0), 0);\n}\n"
+    unit_test = (
+        "#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_add() {\n"
+        "        assert_eq!(add(2, 2), 4);\n        assert!(add(-1, 1) == 0);\n    }\n}\n"
+    )
+
+    wrapped = fastrlrewards.wrap_rust_tests_for_complete_execution(unit_test, "add")
+    assert "#[test]" not in wrapped and "TESTS_PASSED" in wrapped
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    rewards = evaluator.execution_reward(
+        [solution, solution, wrong, broken],
+        test=[main_test, unit_test, main_test, main_test],
+        entry_point=["add"] * 4,
+        language=["rust"] * 4,
+    )
+    # The wrong solution still passes candidate(0, 0) == 0, but execution reward is all-or-nothing
+    assert rewards == [1.0, 1.0, 0.0, 0.0], rewards
+    print("✓ test_rust_execution passed")
+
+def test_multi_method_mode():
+    """Test that multi-method tests receive the class itself in multi_method_mode"""
+    cache = (
+        "<answer>class LRUCache:\n"
+        "    def __init__(self, capacity):\n"
+        "        self.data = {}\n"
+        "    def put(self, key, value):\n"
+        "        self.data[key] = value\n"
+        "    def get(self, key):\n"
+        "        return self.data.get(key, -1)</answer>"
+    )
+    test = (
+        "def check(candidate):\n"
+        "    cache = candidate(2)\n"
+        "    cache.put(1, 1)\n"
+        "    assert cache.get(1) == 1\n"
+        "    assert cache.get(2) == -1"
+    )
+    single_method = "<answer>class Solution:\n    def get(self, key):\n        return -1</answer>"
+    single_method_test = "def check(candidate):\n    assert candidate(3) == -1"
+    module_calls_test = (
+        "import math\n"
+        "def check(candidate):\n"
+        "    assert math.isclose(candidate(3), -1)\n"
+        "    assert math.floor(candidate(3)) == -1"
+    )
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", multi_method_mode=True)
+    assert evaluator.config.multi_method_mode
+    rewards = evaluator.execution_reward(
+        [cache, single_method, single_method, cache.replace("class LRUCache", "class Cache")],
+        test=[test, single_method_test, module_calls_test, test],
+        entry_point=["LRUCache().get", "Solution().get", "Solution().get", "LRUCache"],
+    )
+    assert rewards == [1.0, 1.0, 1.0, 0.0], rewards
+
+    default = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    assert default.execution_reward([cache], test=[test], entry_point=["LRUCache().get"]) == [0.0]
+    print("✓ test_multi_method_mode passed")
+
+def test_execution_reward_detailed():
+    """Test per-completion evaluation records"""
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    completions = [
+        "<answer>def f(x): return x + 1</answer>",
+        "<answer>def g(x): return x + 1</answer>",
+        "<answer>def f(x): raise ValueError('boom')</answer>",
+    ]
+    kwargs = {"test": ["def check(candidate):\n    assert candidate(1) == 2"] * 3, "entry_point": ["f"] * 3}
+    records = evaluator.execution_reward_detailed(completions, **kwargs)
+
+    assert [r["reward"] for r in records] == evaluator.execution_reward(completions, **kwargs)
+    assert records[0]["code_extracted"] == "def f(x): return x + 1"
+    assert (records[0]["tests_passed"], records[0]["tests_total"]) == (1, 1)
+    assert records[0]["stderr_snippet"] is None
+    assert not records[1]["entry_point_found"] and records[1]["tests_total"] == 0
+    assert records[2]["entry_point_found"] and records[2]["reward"] == 0.0
+    assert (records[2]["tests_passed"], records[2]["tests_total"]) == (0, 1)
+    assert all(not r["timed_out"] and r["wall_ms"] >= 0 for r in records)
+    print("✓ test_execution_reward_detailed passed")
+
+def test_evaluation_summary():
+    """Test aggregate statistics of rewards and of detailed records"""
+    summary = fastrlrewards.evaluation_summary([1.0, 0.0, 1.0, 0.5])
+    assert (summary["total"], summary["passed"], summary["failed"]) == (4, 2, 2)
+    assert summary["pass_rate"] == 0.5 and summary["mean_reward"] == 0.625
+    assert abs(summary["std_reward"] - 0.414578) < 1e-6
+    assert fastrlrewards.evaluation_summary([])["pass_rate"] == 0.0
+
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    completions = [
+        "<answer>def f(x): return x + 1</answer>",
+        "<answer>def f(x):\n    return (x +</answer>",  # SyntaxError
+        "<answer>def g(x): return x + 1</answer>",  # Wrong entry point
+        "no code here </answer>",
+    ]
+    records = evaluator.execution_reward_detailed(
+        completions,
+        test=["def check(candidate):\n    assert candidate(1) == 2"] * 4,
+        entry_point=["f"] * 4,
+    )
+    assert records[1]["exception_type"] == "SyntaxError"
+    detailed = fastrlrewards.evaluation_summary_detailed(records)
+    assert detailed["passed"] == 1 and detailed["total"] == 4
+    assert detailed["syntax_error_rate"] == 0.25
+    assert detailed["timeout_rate"] == 0.0
+    assert detailed["not_executed_rate"] == 0.5
+    assert detailed["mean_wall_ms"] >= 0.0
+    print("✓ test_evaluation_summary passed")
+
+def test_warmup():
+    """Test that warmup runs without affecting later evaluations"""
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    assert evaluator.warmup() is None
+    rewards = evaluator.execution_reward(
+        ["<answer>def f(): return 1</answer>"],
+        test=["def check(candidate):\n    assert candidate() == 1"],
+        entry_point=["f"],
+    )
+    assert rewards == [1.0]
+    print("✓ test_warmup passed")
+
+def test_large_output():
+    """Test that output larger than a pipe buffer is drained while the script runs"""
+    code = "import sys\nprint('x' * 1_000_000)\nsys.stderr.write('e' * 200_000)\nprint('TESTS_PASSED:1/1')"
+    assert fastrlrewards.run_sandboxed_tests(code, timeout=5, sandbox_backend="bare") == (True, 1, 1)
+
+    # Only the end of a flood of stderr is kept, which still names the exception
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", debug="DEBUG")
+    assert evaluator.config.debug == "debug"
+    completion = "<answer>import sys\ndef f(x):\n    return x\nsys.stderr.write('e' * 200_000)\nraise KeyError(1)</answer>"
+    record = evaluator.execution_reward_detailed(
+        [completion], test=["def check(candidate):\n    assert candidate(1) == 1"], entry_point=["f"]
+    )[0]
+    assert record["reward"] == 0.0
+    assert record["exception_type"] == "KeyError", record["exception_type"]
+    assert record["stderr_snippet"].endswith("KeyError: 1")
+    print("✓ test_large_output passed")
+
+def test_ram_tmpdir():
+    """Test that scripts are written to /dev/shm when preferred and mounted"""
+    import os
+
+    code = "import os\nprint('TESTS_PASSED:1/1' if os.path.dirname(__file__) == {!r} else 'TESTS_PASSED:0/1')"
+    has_shm = any(line.split()[1:3] == ["/dev/shm", "tmpfs"] for line in open("/proc/mounts"))
+    if has_shm and os.access("/dev/shm", os.W_OK):
+        assert fastrlrewards.run_sandboxed_tests(code.format("/dev/shm"), sandbox_backend="bare")[0]
+    assert fastrlrewards.run_sandboxed_tests(
+        code.format("/tmp"), sandbox_backend="bare", prefer_ram_tmpdir=False
+    )[0]
+
+    evaluator = fastrlrewards.RewardEvaluator(prefer_ram_tmpdir=False)
+    assert evaluator.config.prefer_ram_tmpdir is False
+    print("✓ test_ram_tmpdir passed")
+
+def test_concurrent_isolation():
+    """Test that concurrent sandboxed evaluations cannot see each other's /tmp or /dev/shm files"""
+    if not fastrlrewards.is_sandbox_available():
+        print("⚠ test_concurrent_isolation skipped (no sandbox installed)")
+        return
+
+    completion = (
+        "<answer>\n"
+        "import glob, os, time\n\n"
+        "def f():\n"
+        "    own = os.path.abspath(__file__)\n"
+        "    found = 0\n"
+        "    for root in ['/tmp', '/dev/shm']:\n"
+        "        marker = root + '/fastrl_marker_{}'\n"
+        "        try:\n"
+        "            open(marker, 'w').close()\n"
+        "        except OSError:\n"
+        "            pass  # Not writable in this sandbox\n"
+        "        time.sleep(0.5)\n"
+        "        others = [p for p in glob.glob(root + '/fastrl_marker_*') if p != marker]\n"
+        "        scripts = [p for p in glob.glob(root + '/**/*.py', recursive=True) if p != own]\n"
+        "        found += len(others) + len(scripts)\n"
+        "    return found\n"
+        "</answer>"
+    )
+    # Default settings: scripts go to the RAM tmpfs where the backend allows it
+    evaluator = fastrlrewards.RewardEvaluator(num_threads=10)
+    rewards = evaluator.execution_reward(
+        [completion.format(i) for i in range(10)],
+        test=["def check(candidate):\n    assert candidate() == 0"] * 10,
+        entry_point=["f"] * 10,
+    )
+    assert rewards == [1.0] * 10
+    print("✓ test_concurrent_isolation passed")
+
+def test_execution_reward_tqdm():
+    """Test progress reporting through tqdm (and evaluation without it)"""
+    import sys
+    import types
+
+    completions = ["<answer>def f(): return 0</answer>", "<answer>def f(): return 1</answer>"]
+    kwargs = {"test": ["def check(candidate):\n    assert candidate() == 0"] * 2, "entry_point": ["f"] * 2}
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+
+    bars = []
+
+    class FakeTqdm:
+        def __init__(self, total, desc):
+            self.total, self.desc, self.n, self.closed = total, desc, 0, False
+            bars.append(self)
+
+        def update(self, n):
+            self.n += n
+
+        def close(self):
+            self.closed = True
+
+    def run_with_tqdm_module(module, **extra):
+        original = sys.modules.get("tqdm")
+        sys.modules["tqdm"] = module  # None makes `import tqdm` fail
+        try:
+            return evaluator.execution_reward_tqdm(completions, **extra, **kwargs)
+        finally:
+            if original is None:
+                del sys.modules["tqdm"]
+            else:
+                sys.modules["tqdm"] = original
+
+    assert run_with_tqdm_module(types.SimpleNamespace(tqdm=FakeTqdm), desc="rewards") == [1.0, 0.0]
+    assert len(bars) == 1
+    assert (bars[0].total, bars[0].desc, bars[0].n, bars[0].closed) == (2, "rewards", 2, True)
+
+    assert run_with_tqdm_module(None) == [1.0, 0.0]  # tqdm not installed
+    print("✓ test_execution_reward_tqdm passed")
+
+def test_type_check_reward():
+    """Test type check rewards, using a stand-in mypy on PATH"""
+    import os
+    import tempfile
+
+    completions = [
+        "<answer>def add(a: int, b: int) -> int:\n    return a + b</answer>",
+        "<answer>def add(a, b):\n    return a + b</answer>",
+    ]
+    with tempfile.TemporaryDirectory() as bin_dir:
+        # Fails files that contain unannotated functions, like `mypy --strict`
+        fake_mypy = os.path.join(bin_dir, "mypy")
+        with open(fake_mypy, "w") as f:
+            f.write("#!/bin/sh\n! grep -q 'def add(a, b)' \"$3\"\n")
+        os.chmod(fake_mypy, 0o755)
+
+        original_path = os.environ["PATH"]
+        os.environ["PATH"] = f"{bin_dir}:{original_path}"
+        try:
+            assert fastrlrewards.type_check_reward(completions) == [1.0, 0.0]
+        finally:
+            os.environ["PATH"] = original_path
+    print("✓ test_type_check_reward passed")
+
+def test_linting_reward():
+    """Test linting rewards, using a stand-in ruff on PATH"""
+    import os
+    import tempfile
+
+    completions = [
+        "<answer>def add(a, b):\n    return a + b</answer>",
+        "<answer>import os\nimport sys\n\ndef add(a, b):\n    return a + b</answer>",
+        "<answer></answer>",
+    ]
+    with tempfile.TemporaryDirectory() as bin_dir:
+        # Reports every import from stdin as unused, in ruff's concise format
+        fake_ruff = os.path.join(bin_dir, "ruff")
+        with open(fake_ruff, "w") as f:
+            f.write(
+                "#!/bin/sh\n"
+                "out=$(grep -n '^import' | sed 's/:.*//; s/^/-:/; s/$/:1: F401 unused import/')\n"
+                "[ -z \"$out\" ] && exit 0\n"
+                "echo \"$out\"\n"
+                "exit 1\n"
+            )
+        os.chmod(fake_ruff, 0o755)
+
+        original_path = os.environ["PATH"]
+        os.environ["PATH"] = f"{bin_dir}:{original_path}"
+        try:
+            rewards = fastrlrewards.linting_reward(completions)
+            assert [round(r, 6) for r in rewards] == [1.0, round(1 / 3, 6), 0.0]
+        finally:
+            os.environ["PATH"] = original_path
+
+    evaluator = fastrlrewards.RewardEvaluator(linting_tool="none")
+    assert evaluator.linting_reward(completions) == [1.0, 1.0, 0.0]
+    try:
+        fastrlrewards.RewardEvaluator(linting_tool="pylint")
+        assert False, "Expected ValueError for unknown linting tool"
+    except ValueError:
+        pass
+    print("✓ test_linting_reward passed")
+
+def test_thread_pool_size_warning():
+    """Test that a second evaluator asking for another pool size logs a warning"""
+    import subprocess
+
+    # The thread pool is process-wide, so use a fresh interpreter
+    script = (
+        "import fastrlrewards\n"
+        "fastrlrewards.init_logging('warn')\n"
+        "fastrlrewards.RewardEvaluator(num_threads=2)\n"
+        "fastrlrewards.RewardEvaluator(num_threads=2)\n"
+        "fastrlrewards.RewardEvaluator(num_threads=3)\n"
+    )
+    result = subprocess.run([sys.executable, "-c", script], capture_output=True, text=True, timeout=30)
+    assert result.returncode == 0, result.stderr
+    warnings = [line for line in result.stderr.splitlines() if "already initialized" in line]
+    assert len(warnings) == 1, result.stderr
+    assert "with 2 threads; ignoring request for 3 threads" in warnings[0]
+    print("✓ test_thread_pool_size_warning passed")
+
+def test_log_completion_index():
+    """Test that per-completion log entries carry the completion's batch index"""
+    import subprocess
+
+    script = (
+        "import fastrlrewards\n"
+        "fastrlrewards.init_logging('warn')\n"
+        "evaluator = fastrlrewards.RewardEvaluator(sandbox_backend='bare', max_completion_chars=10)\n"
+        "evaluator.output_reward(['ok', 'x' * 50], expected_output=['', ''])\n"
+    )
+    result = subprocess.run([sys.executable, "-c", script], capture_output=True, text=True, timeout=30)
+    assert result.returncode == 0, result.stderr
+    warnings = [line for line in result.stderr.splitlines() if "overlong completion" in line]
+    assert len(warnings) == 1 and '"completion_idx":1' in warnings[0], result.stderr
+    print("✓ test_log_completion_index passed")
+
+def test_code_quality_reward():
+    """Test execution combined with type hint, docstring and comment signals"""
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare")
+    documented = '''<answer>def add(a: int, b: int) -> int:
+    """Add two numbers."""
+    return a + b  # Plain sum</answer>'''
+    bare = "<answer>def add(a, b):\n    return a + b</answer>"
+    wrong = "<answer>def add(a, b):\n    s = '#'  # Not a sum\n    return a - b</answer>"
+    tests = ["def check(candidate):\n    assert candidate(2, 3) == 5"] * 3
+
+    scores = evaluator.code_quality_reward([documented, bare, wrong], test=tests, entry_point=["add"] * 3)
+    assert scores[0] == {
+        "execution": 1.0,
+        "has_type_hints": True,
+        "has_docstring": True,
+        "has_comments": True,
+        "code_length_chars": len(fastrlrewards.extract_code_from_completion(documented)),
+        "composite": 1.0,
+    }, scores[0]
+    assert (scores[1]["has_type_hints"], scores[1]["has_docstring"], scores[1]["has_comments"]) == (False, False, False)
+    assert abs(scores[1]["composite"] - 0.7) < 1e-9
+    assert scores[2]["execution"] == 0.0 and scores[2]["has_comments"]
+    assert abs(scores[2]["composite"] - 0.1) < 1e-9
+
+    # Only the ratios of the weights matter
+    evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="bare", quality_weights={"execution": 1, "docstring": 1})
+    assert evaluator.config.quality_weights == {"execution": 1.0, "type_hints": 0.1, "docstring": 1.0, "comments": 0.1}
+    scores = evaluator.code_quality_reward([bare], test=tests[:1], entry_point=["add"])
+    assert abs(scores[0]["composite"] - 1 / 2.2) < 1e-9
+
+    for weights in [{"style": 1.0}, {"execution": -1.0}, dict.fromkeys(["execution", "type_hints", "docstring", "comments"], 0.0)]:
+        try:
+            fastrlrewards.RewardEvaluator(quality_weights=weights)
+            assert False, f"Expected ValueError for {weights}"
+        except ValueError:
+            pass
+    print("✓ test_code_quality_reward passed")
+
+def test_firejail_arguments():
+    """Test the Firejail invocation, using a stand-in firejail on PATH that records its arguments"""
+    import os
+    import tempfile
+
+    with tempfile.TemporaryDirectory() as bin_dir:
+        args_file = os.path.join(bin_dir, "args")
+        fake_firejail = os.path.join(bin_dir, "firejail")
+        with open(fake_firejail, "w") as f:
+            f.write(f"#!/bin/sh\nprintf '%s\\n' \"$@\" > {args_file}\necho TESTS_PASSED:1/1\n")
+        os.chmod(fake_firejail, 0o755)
+
+        original_path = os.environ["PATH"]
+        os.environ["PATH"] = f"{bin_dir}:{original_path}"
+        try:
+            evaluator = fastrlrewards.RewardEvaluator(sandbox_backend="firejail")
+            rewards = evaluator.execution_reward(
+                ["<answer>def f():\n    return 1</answer>"],
+                test=["def check(candidate):\n    assert candidate() == 1"],
+                entry_point=["f"],
+            )
+        finally:
+            os.environ["PATH"] = original_path
+        with open(args_file) as f:
+            args = f.read().splitlines()
+
+    assert rewards == [1.0]
+    for arg in ["--read-only=/usr", "--read-only=/lib", "--net=none", "--private-tmp", "--seccomp"]:
+        assert arg in args, args
+    assert args[-3:-1] == ["python3", "-u"]
+    print("✓ test_firejail_arguments passed")
+
+def test_adaptive_timeout():
+    """Test that tests estimated as slow get a longer timeout with adaptive_timeout"""
+    completion = "<answer>import time\ndef f(xs):\n    time.sleep(1.5)\n    return len(xs)</answer>"
+    # A large literal input and a recursive helper: "high", so the 1s timeouts are doubled
+    test = """def size(xs):
+    return 0 if not xs else 1 + size(xs[1:])
+
+def check(candidate):
+    assert candidate(%s) == size(%s)""" % (str([0] * 101), str([0] * 101))
+    assert fastrlrewards.estimate_test_complexity(test) == "high"
+
+    rewards = []
+    for adaptive_timeout in [False, True]:
+        evaluator = fastrlrewards.RewardEvaluator(
+            sandbox_backend="bare", timeout_seconds=1, cpu_time_limit=1, adaptive_timeout=adaptive_timeout
+        )
+        rewards += evaluator.execution_reward([completion], test=[test], entry_point=["f"])
+    assert rewards == [0.0, 1.0], rewards
+    assert "adaptive_timeout=True" in repr(evaluator)
+    print("✓ test_adaptive_timeout passed")
+
 if __name__ == "__main__":
     print("\nRunning reward evaluator tests...\n")
     test_format_reward_function()
+    test_single_completion_functions()
     test_execution_reward_function()
     test_evaluator_class()
     test_trl_dict_format()
     test_multiple_evaluators()
+    test_parse_test_output()
+    test_parse_exception_type()
+    test_sandbox_fallback_policy()
+    test_sandbox_backend()
+    test_pass_at_k()
+    test_pass_at_k_from_rewards()
+    test_length_mismatch_message()
+    test_grouped_aggregation_rewards()
+    test_combined_reward()
+    test_numpy_kwargs()
+    test_output_reward()
+    test_multi_file_execution()
+    test_reward_registry()
+    test_python_udf_reward()
+    test_evaluator_config()
+    test_timeout_below_cpu_limit_rejected()
+    test_memory_limit_floor()
+    test_config_dict_round_trip()
+    test_pickle_and_copy()
+    test_min_code_lines()
+    test_tuple_completions()
+    test_execution_reward_callable()
+    test_batch_order_is_deterministic()
+    test_complexity_reward()
+    test_regex_format_reward()
+    test_stdin_execution()
+    test_reject_stubs()
+    test_class_entry_points()
+    test_solution_class_names()
+    test_null_fields()
+    test_chunked_execution()
+    test_execution_reward_stream()
+    test_filter_valid_completions()
+    test_mixed_language_batch()
+    test_rust_execution()
+    test_dry_run()
+    test_pre_wrapped_tests()
+    test_multi_method_mode()
+    test_execution_reward_detailed()
+    test_evaluation_summary()
+    test_warmup()
+    test_large_output()
+    test_ram_tmpdir()
+    test_concurrent_isolation()
+    test_execution_reward_tqdm()
+    test_type_check_reward()
+    test_linting_reward()
+    test_thread_pool_size_warning()
+    test_log_completion_index()
+    test_code_quality_reward()
+    test_firejail_arguments()
+    test_adaptive_timeout()
     print("\n✅ All tests passed!\n")
@@ -10,58 +10,214 @@ def wrap_tests_for_complete_execution_python(test_code: str, entry_point: str) -
     
     if not assertions:
         return test_code
+    if "TESTS_PASSED:" in test_code and any(
+        f"{var} = []" in test_code for var in ("_results", "_test_results_internal_")
+    ):
+        return test_code
     
-    lines = test_code.split('\n')
+    def_pattern = r'\s*(?:async\s+)?def\s+(\w+)\s*\('
+    lines = []
+    in_main_guard = False
+    for line in test_code.split('\n'):
+        if re.match(r'if\s+__name__\s*==\s*[\'"]__main__[\'"]\s*:', line):
+            in_main_guard = True
+            continue
+        if in_main_guard:
+            if line.strip() == "" or line.startswith((" ", "\t")):
+                continue
+            in_main_guard = False
+        lines.append(line)
+    assertion_count = count_assertions(lines)
+    defined = [m.group(1) for m in (re.match(def_pattern, l) for l in test_code.split('\n')) if m]
+    check_name = next((n for n in ("check", "test", "validate", "verify") if n in defined), "check")
+    results_var = "_results"
+    if re.search(r'^[ \t]*_results\s*(?::|[-+*/|&]?=(?:[^=]|$))', test_code, re.M):
+        results_var = "_test_results_internal_"
     wrapped_lines = []
     in_check_function = False
     check_function_indent = ""
+    held_lines = []
+    nested_def_indent = None
     
-    for line in lines:
-        if re.match(r'def\s+check\s*\(', line):
+    lines_iter = iter(lines)
+    for line in lines_iter:
+        def_match = re.match(def_pattern, line)
+        nested_in_check = in_check_function and len(line) - len(line.lstrip()) > len(check_function_indent)
+        if def_match and def_match.group(1) == check_name and not nested_in_check:
             in_check_function = True
             check_function_indent = re.match(r'(\s*)', line).group(1)
             wrapped_lines.append(line)
-            wrapped_lines.append(f"{check_function_indent}    _results = []")
-            continue
-        
-        assert_match = re.match(r'(\s*)(assert\s+.+)', line)
-        if assert_match and in_check_function:
-            indent = assert_match.group(1)
-            assertion = assert_match.group(2)
-            
-            wrapped_lines.append(f"{indent}try:")
-            wrapped_lines.append(f"{indent}    {assertion}")
-            wrapped_lines.append(f"{indent}    _results.append(True)")
-            wrapped_lines.append(f"{indent}except:")
-            wrapped_lines.append(f"{indent}    _results.append(False)")
+            wrapped_lines.append(f"{check_function_indent}    {results_var} = []")
+            nested_def_indent = None
             continue
         
         if in_check_function:
-            if line.strip() == "" or (line.strip() and not line.startswith(check_function_indent + ' ') and not line.startswith(check_function_indent + '\t')):
-                wrapped_lines.append(f"{check_function_indent}    return _results")
+            if line.strip() == "" or line.strip().startswith("#"):
+                held_lines.append(line)
+                continue
+            
+            if not line.startswith(check_function_indent + ' ') and not line.startswith(check_function_indent + '\t'):
+                wrapped_lines.extend(held for held in held_lines if held.strip())
+                held_lines = []
+                wrapped_lines.append(f"{check_function_indent}    return {results_var}")
                 wrapped_lines.append("")
                 in_check_function = False
-                
-                if line.strip():
+                wrapped_lines.append(line)
+                continue
+            
+            assert_match = re.match(r'(\s*)(assert\s+.+)', line)
+            comments_above = 0
+            if assert_match:
+                while comments_above < len(held_lines) and held_lines[-1 - comments_above].strip():
+                    comments_above += 1
+            split = len(held_lines) - comments_above
+            assertion_comments = held_lines[split:]
+            wrapped_lines.extend(held_lines[:split])
+            held_lines = []
+            
+            stripped = line.strip()
+            indent_width = len(line) - len(line.lstrip())
+            if nested_def_indent is not None and indent_width <= nested_def_indent:
+                nested_def_indent = None
+            if nested_def_indent is None:
+                if stripped.startswith("def ") or stripped.startswith("async def "):
+                    nested_def_indent = indent_width
+                elif re.match(r'return(?:$|[\s(;#])', stripped) and stripped != f"return {results_var}":
+                    wrapped_lines.append(f"{line[:indent_width]}return {results_var}")
+                    continue
+            
+            if re.match(r'(?:async\s+)?with[\s(]', stripped):
+                header_end = with_header_end(line)
+                body = line[header_end + 1:].strip() if header_end is not None else ""
+                if header_end is not None and re.match(r'assert\s+.+', body):
+                    wrapped_lines.append(line[:header_end + 1])
+                    assert_match = re.match(r'(\s*)(assert\s+.+)', f"{line[:indent_width]}    {body}")
+                else:
+                    wrapped_lines.extend(assertion_comments)
                     wrapped_lines.append(line)
+                    balance = bracket_balance(line)
+                    continued = line.rstrip().endswith("\\")
+                    while header_end is None and (balance > 0 or continued):
+                        continuation = next(lines_iter, None)
+                        if continuation is None:
+                            break
+                        balance += bracket_balance(continuation)
+                        continued = continuation.rstrip().endswith("\\")
+                        wrapped_lines.append(continuation)
+                    continue
+            
+            if assert_match:
+                indent = assert_match.group(1)
+                assertion = assert_match.group(2)
+                
+                wrapped_lines.append(f"{indent}try:")
+                wrapped_lines.extend(f"{indent}    {comment.strip()}" for comment in assertion_comments)
+                wrapped_lines.append(f"{indent}    {assertion}")
+                balance = bracket_balance(assertion)
+                continued = assertion.rstrip().endswith("\\")
+                while balance > 0 or continued:
+                    continuation = next(lines_iter, None)
+                    if continuation is None:
+                        break
+                    balance += bracket_balance(continuation)
+                    continued = continuation.rstrip().endswith("\\")
+                    wrapped_lines.append(continuation)
+                wrapped_lines.append(f"{indent}    {results_var}.append(True)")
+                wrapped_lines.append(f"{indent}except Exception:")
+                wrapped_lines.append(f"{indent}    {results_var}.append(False)")
                 continue
         
         wrapped_lines.append(line)
     
     if in_check_function:
-        wrapped_lines.append(f"{check_function_indent}    return _results")
+        wrapped_lines.extend(held for held in held_lines if held.strip())
+        wrapped_lines.append(f"{check_function_indent}    return {results_var}")
         wrapped_lines.append("")
     
-    wrapped_lines.append(f"_test_results = check({entry_point})")
+    wrapped_lines.append("import sys")
+    wrapped_lines.append("try:")
+    wrapped_lines.append(f"    _test_results = {check_name}({entry_point})")
+    wrapped_lines.append("except Exception:")
+    wrapped_lines.append(f'    print("TESTS_PASSED:0/{assertion_count}")')
+    wrapped_lines.append("    sys.exit(1)")
     wrapped_lines.append("")
     wrapped_lines.append("# Report test results")
     wrapped_lines.append("_passed = sum(_test_results)")
     wrapped_lines.append("_total = len(_test_results)")
     wrapped_lines.append('print(f"TESTS_PASSED:{_passed}/{_total}")')
-    wrapped_lines.append("exit(0 if _passed == _total else 1)")
+    wrapped_lines.append("sys.exit(0 if _passed == _total else 1)")
     
     return '\n'.join(wrapped_lines)
 
+def count_assertions(lines) -> int:
+    """Number of assert statements (comment lines skipped, multi-line assertions once)"""
+    count = 0
+    lines_iter = iter(lines)
+    for line in lines_iter:
+        if line.lstrip().startswith("#"):
+            continue
+        assert_match = re.search(r'(\s*)(assert\s+.+)', line)
+        if assert_match:
+            assertion = assert_match.group(2)
+            balance = bracket_balance(assertion)
+            continued = assertion.rstrip().endswith("\\")
+            while balance > 0 or continued:
+                continuation = next(lines_iter, None)
+                if continuation is None:
+                    break
+                balance += bracket_balance(continuation)
+                continued = continuation.rstrip().endswith("\\")
+            count += 1
+    return count
+
+def with_header_end(line: str):
+    """Index of the colon ending a `with` header on this line, or None if it continues"""
+    depth = 0
+    quote = None
+    escaped = False
+    for idx, c in enumerate(line):
+        if quote:
+            if escaped:
+                escaped = False
+            elif c == "\\":
+                escaped = True
+            elif c == quote:
+                quote = None
+        elif c in "'\"":
+            quote = c
+        elif c == "#":
+            return None
+        elif c in "([{":
+            depth += 1
+        elif c in ")]}":
+            depth -= 1
+        elif c == ":" and depth == 0:
+            return idx
+    return None
+
+def bracket_balance(line: str) -> int:
+    """Net number of brackets left open by a line (ignoring strings and comments)"""
+    balance = 0
+    quote = None
+    escaped = False
+    for c in line:
+        if quote:
+            if escaped:
+                escaped = False
+            elif c == "\\":
+                escaped = True
+            elif c == quote:
+                quote = None
+        elif c in "'\"":
+            quote = c
+        elif c == "#":
+            break
+        elif c in "([{":
+            balance += 1
+        elif c in ")]}":
+            balance -= 1
+    return balance
+
 def test_with_dataset(num_samples=100, show_failures_only=True, verbose=False):
     """
     Test wrapping function with real Code-R1 dataset
@@ -0,0 +1,413 @@
+#!/usr/bin/env python3
+"""
+Edge-case tests for wrap_tests_for_complete_execution.
+
+Each test wraps a test snippet, executes it together with a solution in a
+subprocess, and checks the reported TESTS_PASSED line.
+"""
+
+import subprocess
+import sys
+import fastrlrewards
+
+def run_wrapped(solution, test_code, entry_point):
+    """Wrap test_code, run it after solution, and return the parsed (passed, total)"""
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, entry_point)
+    output = subprocess.run(
+        [sys.executable, "-c", f"{solution}\n\n{wrapped}"],
+        capture_output=True,
+        text=True,
+        timeout=10,
+    )
+    return fastrlrewards.parse_test_output(output.stdout)
+
+def test_helper_defined_before_check():
+    """Helpers defined before check are preserved verbatim and not wrapped"""
+    test_code = """def make_input(n):
+    assert n >= 0
+    return list(range(n))
+
+def check(candidate):
+    assert candidate(make_input(3)) == 3
+    assert candidate(make_input(0)) == 0"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "length")
+    assert wrapped.startswith("def make_input(n):\n    assert n >= 0\n")
+    assert run_wrapped("def length(xs): return len(xs)", test_code, "length") == (2, 2)
+    print("✓ test_helper_defined_before_check passed")
+
+def test_closure_separated_by_blank_line():
+    """Blank lines inside check (e.g. after a closure) do not end the function"""
+    test_code = """NUMS = [1, 2, 3]
+
+def check(candidate):
+    def expected(xs):
+        return sum(xs)
+
+    assert candidate(NUMS) == expected(NUMS)
+
+    assert candidate([]) == 0
+"""
+
+    assert run_wrapped("def total(xs): return sum(xs)", test_code, "total") == (2, 2)
+    assert run_wrapped("def total(xs): return 6", test_code, "total") == (1, 2)
+    print("✓ test_closure_separated_by_blank_line passed")
+
+def test_nested_functions_in_check():
+    """Nested defs (even one named check) and helpers after check keep check's structure"""
+    test_code = """def check(candidate):
+    def check(x):
+        assert x >= 0
+        return x
+
+    assert candidate(check(2)) == 4
+    assert candidate(helper()) == 9
+
+def helper():
+    return 3"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "square")
+    assert wrapped.count("_results = []") == 1
+    assert "    return _results\n\ndef helper():\n    return 3" in wrapped
+    assert run_wrapped("def square(x): return x * x", test_code, "square") == (3, 3)
+    assert run_wrapped("def square(x): return x + x", test_code, "square") == (2, 3)
+    print("✓ test_nested_functions_in_check passed")
+
+def test_main_guard_removed():
+    """A trailing `if __name__ == "__main__":` block does not run check a second time"""
+    test_code = """def check(candidate):
+    assert candidate(1) == 2
+    assert candidate(2) == 4
+
+if __name__ == "__main__":
+    check(double)
+    print("done")
+
+HELPER = 1"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "double")
+    assert "__main__" not in wrapped and wrapped.count("check(double)") == 1
+    assert "\nHELPER = 1\n" in wrapped
+    assert run_wrapped("def double(x): return x * 2", test_code, "double") == (2, 2)
+
+    inline = "def check(candidate):\n    assert candidate(1) == 2\n\nif __name__ == '__main__': check(double)"
+    assert run_wrapped("def double(x): return x * 2", inline, "double") == (1, 1)
+    print("✓ test_main_guard_removed passed")
+
+def test_results_variable_in_check():
+    """A `_results` variable used by the tests is not clobbered by the wrapper"""
+    test_code = """def check(candidate):
+    _results = [candidate(x) for x in range(3)]
+    assert _results == [0, 2, 4]
+    assert len(_results) == 3"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "double")
+    assert "_test_results_internal_ = []" in wrapped
+    assert "    _results = [candidate(x) for x in range(3)]" in wrapped
+    assert run_wrapped("def double(x): return x * 2", test_code, "double") == (2, 2)
+    assert run_wrapped("def double(x): return x + 2", test_code, "double") == (1, 2)
+
+    # A comparison is not an assignment
+    unchanged = fastrlrewards.wrap_tests_for_complete_execution(
+        "def check(candidate):\n    assert candidate(1) == 2", "double"
+    )
+    assert "    _results = []" in unchanged
+    print("✓ test_results_variable_in_check passed")
+
+def test_early_return_in_check():
+    """Early returns inside check keep the results collected so far"""
+    test_code = """def check(candidate):
+    def expected(x):
+        return x * 2
+
+    assert candidate(1) == expected(1)
+    if candidate(0) != 0:
+        return
+    assert candidate(2) == expected(2)
+    return True"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "double")
+    assert "        return x * 2" in wrapped  # Nested helper returns are untouched
+    assert "        return _results" in wrapped
+    assert "    return True" not in wrapped
+    assert run_wrapped("def double(x): return x * 2", test_code, "double") == (2, 2)
+    assert run_wrapped("def double(x): return x * 2 + 1", test_code, "double") == (0, 1)
+    print("✓ test_early_return_in_check passed")
+
+def test_timeout_per_assertion():
+    """A hanging assertion fails on its own timeout and the remaining ones still run"""
+    test_code = """def check(candidate):
+    assert candidate(1) == 1
+    assert candidate(-1) == 1
+    assert candidate(2) == 2"""
+    solution = "def f(x):\n    while x < 0:\n        pass\n    return x"
+
+    wrapped = fastrlrewards.wrap_tests_with_timeout_per_assertion(test_code, "f", 200)
+    assert "_signal.setitimer(_signal.ITIMER_REAL, 0.2)" in wrapped
+    output = subprocess.run(
+        [sys.executable, "-c", f"{solution}\n\n{wrapped}"],
+        capture_output=True,
+        text=True,
+        timeout=10,
+    )
+    assert fastrlrewards.parse_test_output(output.stdout) == (2, 3)
+
+    # A timeout of 0 disables per-assertion timeouts
+    assert fastrlrewards.wrap_tests_with_timeout_per_assertion(
+        test_code, "f", 0
+    ) == fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    print("✓ test_timeout_per_assertion passed")
+
+def test_comments_before_assertions():
+    """Comments directly above an assertion are moved into its try block"""
+    test_code = """def check(candidate):
+    # Basic case
+    assert candidate(1) == 1
+
+    # Setup shared by the next assertions
+    value = 2
+
+    # Negative input
+    # (absolute value)
+    assert candidate(-value) == value
+# Unindented comment inside check
+    assert candidate(0) == 0"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    assert "    try:\n        # Basic case\n        assert candidate(1) == 1" in wrapped
+    assert "    # Setup shared by the next assertions\n    value = 2" in wrapped
+    assert "    try:\n        # Negative input\n        # (absolute value)\n" in wrapped
+    assert "    try:\n        # Unindented comment inside check\n" in wrapped
+    assert run_wrapped("f = abs", test_code, "f") == (3, 3)
+    print("✓ test_comments_before_assertions passed")
+
+def test_multi_line_assertions():
+    """Assertions whose expression spans several lines are wrapped as one statement"""
+    test_code = """def check(candidate):
+    assert candidate([1, 2, 4, 8, 16, 32, 64]) == [1, 2, 4, 8,
+        16, 32, 64]
+    assert candidate({"a": 1, "b": 2}) == {
+        "a": 1,
+        "b": 2,  # not "]"
+    }
+    assert candidate((1, 2)) == (
+        1, 2)
+    assert candidate("([{") == \\
+        "([{"
+    assert candidate(0) == 1"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    assert "        assert candidate([1, 2, 4, 8, 16, 32, 64]) == [1, 2, 4, 8,\n        16, 32, 64]\n        _results.append(True)" in wrapped
+    assert "        \"b\": 2,  # not \"]\"\n    }\n        _results.append(True)" in wrapped
+    assert "        1, 2)\n        _results.append(True)" in wrapped
+    assert run_wrapped("f = lambda x: x", test_code, "f") == (4, 5)
+    print("✓ test_multi_line_assertions passed")
+
+def test_system_exit_not_counted_as_failure():
+    """Only Exception subclasses are caught: an exit inside an assertion ends the run"""
+    test_code = """def check(candidate):
+    assert candidate(1) == 1
+    assert candidate(2) == 2"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    assert "    except Exception:\n" in wrapped
+    assert "    except:\n" not in wrapped
+    assert wrapped.endswith('print(f"TESTS_PASSED:{_passed}/{_total}")\nsys.exit(0 if _passed == _total else 1)')
+    assert run_wrapped("def f(x):\n    raise ValueError(x)", test_code, "f") == (0, 2)
+    # No TESTS_PASSED line, so the sandbox scores 0.0 instead of counting a failure
+    assert run_wrapped("import sys\ndef f(x):\n    sys.exit(0)", test_code, "f") is None
+    print("✓ test_system_exit_not_counted_as_failure passed")
+
+def test_check_name_variants():
+    """Test that verify/validate/test are accepted as the check function name"""
+    test_code = """def verify(fn):
+    assert fn(2) == 4
+    assert fn(3) == 9"""
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "square")
+    assert "_test_results = verify(square)" in wrapped
+    assert run_wrapped("def square(x):\n    return x * x", test_code, "square") == (2, 2)
+
+    # `check` wins over a helper named `validate`
+    test_code = """def validate(x):
+    assert x >= 0
+    return x
+
+def check(candidate):
+    assert validate(candidate(1)) == 1"""
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    assert "_test_results = check(f)" in wrapped
+    assert "def validate(x):\n    assert x >= 0\n" in wrapped
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(
+        "def my_check(c):\n    assert c(1) == 1", "f", check_names=["my_check"]
+    )
+    assert "_test_results = my_check(f)" in wrapped
+    print("✓ test_check_name_variants passed")
+
+def test_class_entry_points():
+    """Constructor-call and method entry points are passed to check unchanged"""
+    solution = """class Solution:
+    def twoSum(self, nums, target):
+        seen = {}
+        for i, n in enumerate(nums):
+            if target - n in seen:
+                return [seen[target - n], i]
+            seen[n] = i"""
+    test_code = """def check(candidate):
+    assert candidate.twoSum(nums=[2, 7, 11], target=9) == [0, 1]
+    assert candidate.twoSum([3, 3], target=6) == [0, 1]"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "Solution()")
+    assert "\n    _test_results = check(Solution())\n" in wrapped
+    assert run_wrapped(solution, test_code, "Solution()") == (2, 2)
+
+    test_code = """def check(candidate):
+    assert candidate(nums=[1, 4], target=5) == [0, 1]"""
+    assert run_wrapped(solution, test_code, "Solution().twoSum") == (1, 1)
+    print("✓ test_class_entry_points passed")
+
+def test_validate_test_code():
+    """Test style detection and warnings before wrapping"""
+    result = fastrlrewards.validate_test_code("def check(candidate):\n    assert candidate(1) == 1")
+    assert result == {
+        "is_valid": True,
+        "detected_style": "check",
+        "assertion_count": 1,
+        "warnings": [],
+    }
+
+    result = fastrlrewards.validate_test_code("def test_add():\n    assert add(1, 2) == 3")
+    assert not result["is_valid"]
+    assert result["detected_style"] == "pytest"
+    assert len(result["warnings"]) == 1
+
+    result = fastrlrewards.validate_test_code("def check(candidate):\n    pass")
+    assert not result["is_valid"]
+    assert result["warnings"] == ["No assertions found"]
+    print("✓ test_validate_test_code passed")
+
+def test_count_assertions():
+    """Assertions are counted without wrapping (each counts once, even in a loop)"""
+    test_code = """def check(candidate):
+    assert candidate(1) == 1
+    for x in [2, 3]:
+        assert candidate(x) == x"""
+    assert fastrlrewards.count_assertions(test_code) == 2
+    assert run_wrapped("f = abs", test_code, "f")[1] == 3  # The loop runs its assertion twice
+    assert fastrlrewards.count_assertions("def check(candidate):\n    pass") == 0
+
+    # Commented-out assertions are not counted, and a multi-line assertion counts once
+    test_code = """def check(candidate):
+    # assert candidate(0) == 0
+    assert candidate(1) == candidate(
+        1,
+    ), "assert failed"
+    assert candidate(2) == 2"""
+    assert fastrlrewards.count_assertions(test_code) == 2
+    assert run_wrapped("def f(x, *args):\n    return x", test_code, "f")[1] == 2
+    print("✓ test_count_assertions passed")
+def test_error_before_first_assertion():
+    """An exception in check before any assertion runs reports every assertion as failed"""
+    test_code = """def check(candidate):
+    nums = create_test_data()
+    assert candidate(nums) == 6
+    assert candidate([]) == 0"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "f")
+    assert 'except Exception:\n    print("TESTS_PASSED:0/2")\n    sys.exit(1)\n' in wrapped
+    assert run_wrapped("f = sum", test_code, "f") == (0, 2)
+
+    # A check that runs normally is unaffected
+    assert run_wrapped("f = sum", test_code.replace("create_test_data()", "[1, 2, 3]"), "f") == (2, 2)
+    print("✓ test_error_before_first_assertion passed")
+
+def test_wrapping_is_idempotent():
+    """Wrapping already wrapped tests returns them unchanged"""
+    test_code = """def check(candidate):
+    assert candidate([1, 2]) == 3
+    assert candidate([]) == 0"""
+    shadowing = """def check(candidate):
+    _results = candidate([1])
+    assert _results == 1"""
+
+    for code in (test_code, shadowing):
+        once = fastrlrewards.wrap_tests_for_complete_execution(code, "f")
+        assert fastrlrewards.wrap_tests_for_complete_execution(once, "f") == once
+        assert fastrlrewards.wrap_tests_with_timeout_per_assertion(once, "f", 100) == once
+    assert run_wrapped("f = sum", fastrlrewards.wrap_tests_for_complete_execution(test_code, "f"), "f") == (2, 2)
+    print("✓ test_wrapping_is_idempotent passed")
+
+def test_mock_patch_with_blocks():
+    """Assertions inside `with patch(...)` blocks are wrapped, whatever the header layout"""
+    solution = "def ask():\n    return int(input())"
+    test_code = """from unittest.mock import patch
+
+def check(candidate):
+    with patch("builtins.input", return_value="3"):
+        assert candidate() == 3
+        assert candidate() == 4
+    with patch("builtins.input", return_value="5"): assert candidate() == 5
+    with patch("builtins.input",
+return_value="7") as mocked:
+        assert candidate() == 7
+        assert mocked.call_count == 1
+    assert candidate is not None"""
+
+    wrapped = fastrlrewards.wrap_tests_for_complete_execution(test_code, "ask")
+    assert '    with patch("builtins.input", return_value="5"):\n        try:\n            assert candidate() == 5' in wrapped
+    assert '    with patch("builtins.input",\nreturn_value="7") as mocked:\n' in wrapped
+    assert run_wrapped(solution, test_code, "ask") == (5, 6)
+    assert run_wrapped(solution, fastrlrewards.wrap_tests_with_timeout_per_assertion(test_code, "ask", 1000), "ask") == (5, 6)
+    print("✓ test_mock_patch_with_blocks passed")
+
+def test_estimate_test_complexity():
+    """Large literals, nested loops and recursion each raise the estimate"""
+    simple = "def check(candidate):\n    assert candidate([1, 2]) == 3"
+    large = "def check(candidate):\n    assert candidate(%s) == 0" % str([0] * 101)
+    nested = """def check(candidate):
+    for i in range(3):
+        for j in range(3):
+            assert candidate(i, j) == i + j"""
+    recursive = """def fib(n):
+    return n if n < 2 else fib(n - 1) + fib(n - 2)
+
+def check(candidate):
+    assert candidate(20) == fib(20)"""
+
+    assert fastrlrewards.estimate_test_complexity(simple) == "low"
+    # 100 elements, or commas inside strings, are not large
+    assert fastrlrewards.estimate_test_complexity(simple.replace("[1, 2]", str([0] * 100))) == "low"
+    assert fastrlrewards.estimate_test_complexity(simple.replace("[1, 2]", repr("," * 200))) == "low"
+    assert fastrlrewards.estimate_test_complexity(large) == "medium"
+    assert fastrlrewards.estimate_test_complexity(nested) == "medium"
+    assert fastrlrewards.estimate_test_complexity("def check(c):\n    assert c([x * y for x in a for y in b])") == "medium"
+    assert fastrlrewards.estimate_test_complexity(recursive) == "medium"
+    assert fastrlrewards.estimate_test_complexity(recursive + "\n    assert candidate(%s) == 0" % str([0] * 101)) == "high"
+
+    # Sequential loops are not nested
+    sequential = "def check(candidate):\n    for i in range(3):\n        assert candidate(i)\n    for j in range(3):\n        assert candidate(j)"
+    assert fastrlrewards.estimate_test_complexity(sequential) == "low"
+    print("✓ test_estimate_test_complexity passed")
+
+
+if __name__ == "__main__":
+    print("\nRunning test wrapper edge-case tests...\n")
+    test_helper_defined_before_check()
+    test_closure_separated_by_blank_line()
+    test_nested_functions_in_check()
+    test_main_guard_removed()
+    test_results_variable_in_check()
+    test_early_return_in_check()
+    test_timeout_per_assertion()
+    test_comments_before_assertions()
+    test_multi_line_assertions()
+    test_system_exit_not_counted_as_failure()
+    test_check_name_variants()
+    test_class_entry_points()
+    test_validate_test_code()
+    test_count_assertions()
+    test_error_before_first_assertion()
+    test_wrapping_is_idempotent()
+    test_mock_patch_with_blocks()
+    test_estimate_test_complexity()
+    print("\n✅ All tests passed!\n")